@@ -0,0 +1,158 @@
+//! Operator authorization. Besides the unrestricted operator role (`set_operator`), the admin
+//! can grant an operator key a scoped role: a set of operator entrypoints it may call and an
+//! expiry. Every operator entrypoint goes through `require_operator`. The risk engine is a
+//! separate backend key that opens lines and sets their risk parameters, and nothing else.
+
+use soroban_sdk::{contracttype, Address, Env, Symbol, Vec};
+
+use crate::types::OperatorGrant;
+use crate::{CreditError, DataKey};
+
+/// Storage keys for scoped operator grants.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AccessKey {
+    Grant(Address),
+    /// Address proposed as the next admin, until it accepts.
+    PendingAdmin,
+    /// Backend key that opens lines and updates their risk parameters.
+    RiskEngine,
+    /// Governance contract allowed to apply protocol parameter changes.
+    Governance,
+}
+
+/// Maximum number of functions in one grant.
+pub const MAX_GRANT_FUNCTIONS: u32 = 8;
+
+/// Entrypoints an operator can be granted.
+pub fn operator_functions(env: &Env) -> Vec<Symbol> {
+    Vec::from_array(
+        env,
+        [
+            Symbol::new(env, "waive_fees"),
+            Symbol::new(env, "append_line_note"),
+            Symbol::new(env, "snapshot"),
+        ],
+    )
+}
+
+pub fn grant(env: &Env, operator: &Address) -> Option<OperatorGrant> {
+    env.storage()
+        .instance()
+        .get(&AccessKey::Grant(operator.clone()))
+}
+
+/// Store a grant, or remove it with `None`.
+///
+/// # Errors
+/// * If the grant is empty, too long, already expired, or names a function operators cannot
+///   be granted
+pub fn set_grant(
+    env: &Env,
+    operator: &Address,
+    grant: Option<OperatorGrant>,
+) -> Result<(), CreditError> {
+    let key = AccessKey::Grant(operator.clone());
+    let Some(grant) = grant else {
+        env.storage().instance().remove(&key);
+        return Ok(());
+    };
+    let allowed = operator_functions(env);
+    if grant.functions.is_empty()
+        || grant.functions.len() > MAX_GRANT_FUNCTIONS
+        || grant.expires_at <= env.ledger().timestamp()
+        || grant.functions.iter().any(|f| !allowed.contains(&f))
+    {
+        return Err(CreditError::InvalidParameter);
+    }
+    env.storage().instance().set(&key, &grant);
+    Ok(())
+}
+
+pub fn pending_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&AccessKey::PendingAdmin)
+}
+
+/// Record the proposed next admin, or clear the proposal with `None`.
+pub fn set_pending_admin(env: &Env, admin: Option<&Address>) {
+    match admin {
+        Some(admin) => env
+            .storage()
+            .instance()
+            .set(&AccessKey::PendingAdmin, admin),
+        None => env.storage().instance().remove(&AccessKey::PendingAdmin),
+    }
+}
+
+pub fn risk_engine(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&AccessKey::RiskEngine)
+}
+
+/// Store the risk engine, or remove it with `None`.
+pub fn set_risk_engine(env: &Env, engine: Option<&Address>) {
+    match engine {
+        Some(engine) => env.storage().instance().set(&AccessKey::RiskEngine, engine),
+        None => env.storage().instance().remove(&AccessKey::RiskEngine),
+    }
+}
+
+/// Require the risk engine's authorization, or the admin's when no risk engine is set. Returns
+/// the address that authorized.
+///
+/// # Errors
+/// * `NotInitialized` if no risk engine is set and the contract has no admin
+pub fn require_risk_engine(env: &Env) -> Result<Address, CreditError> {
+    match risk_engine(env) {
+        Some(engine) => {
+            engine.require_auth();
+            Ok(engine)
+        }
+        None => crate::require_admin_auth(env),
+    }
+}
+
+pub fn governance(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&AccessKey::Governance)
+}
+
+/// Store the governance contract, or remove it with `None`.
+pub fn set_governance(env: &Env, governance: Option<&Address>) {
+    match governance {
+        Some(governance) => env
+            .storage()
+            .instance()
+            .set(&AccessKey::Governance, governance),
+        None => env.storage().instance().remove(&AccessKey::Governance),
+    }
+}
+
+/// Whether `operator` may call `function`: it holds the unrestricted operator role, or an
+/// unexpired grant that lists `function`.
+pub fn is_allowed(env: &Env, operator: &Address, function: &Symbol) -> bool {
+    if env
+        .storage()
+        .instance()
+        .has(&DataKey::Operator(operator.clone()))
+    {
+        return true;
+    }
+    grant(env, operator).is_some_and(|grant| {
+        env.ledger().timestamp() < grant.expires_at && grant.functions.contains(function)
+    })
+}
+
+/// Require `operator`'s authorization and permission to call `function`.
+///
+/// # Errors
+/// * `Unauthorized` if `operator` may not call `function`
+pub fn require_operator(
+    env: &Env,
+    operator: &Address,
+    function: Symbol,
+) -> Result<(), CreditError> {
+    operator.require_auth();
+    if !is_allowed(env, operator, &function) {
+        return Err(CreditError::Unauthorized);
+    }
+    Ok(())
+}
@@ -0,0 +1,461 @@
+//! Interest accrual engine.
+//!
+//! Accrual is lazy: it is settled whenever a line is touched, covering the time since
+//! `last_accrual_ts`. Each line accrues from its own index snapshot, so accrual costs the same
+//! per interaction however many lines exist; the interest accrued across the book in between is
+//! tracked by the book index (see `borrow_index`). Lines on a capitalization schedule have
+//! accrued interest added to principal at each period boundary crossed, so later interest
+//! compounds on it.
+//!
+//! A rate change settles the line at the old rate and checkpoints its index, so every period is
+//! charged its own rate. Rate changes are also kept in a per-line log for audit.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::events::{
+    publish_capitalization, publish_interest_accrued, CapitalizationEvent, InterestAccruedEvent,
+};
+use crate::types::{
+    CapitalizationPeriod, CapitalizationSchedule, CreditLineData, PaymentDue, RateChange,
+};
+use crate::{
+    behavior, borrow_index, incentives, interest, maturity, promo, summary, CreditError, DataKey,
+};
+
+/// Storage keys for compounding configuration.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CompoundingKey {
+    /// Capitalization period given to newly opened lines.
+    Default,
+}
+
+/// Seconds per capitalization day.
+pub const DAY_SECS: u64 = 86_400;
+
+/// Seconds per capitalization month (30 days).
+pub const MONTH_SECS: u64 = 30 * 86_400;
+
+/// Seconds per capitalization quarter (90 days).
+pub const QUARTER_SECS: u64 = 90 * 86_400;
+
+/// Upper bound on capitalization boundaries settled in a single accrual; any remainder is
+/// settled the next time the line is touched.
+const MAX_CAPITALIZATIONS_PER_ACCRUAL: u32 = 120;
+
+/// Maximum number of entries kept in a line's rate change log.
+pub const MAX_RATE_HISTORY: u32 = 20;
+
+/// Seconds after a payment due date before it counts as overdue.
+pub fn grace_period(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::GracePeriod)
+        .unwrap_or(0)
+}
+
+/// Penalty rate for a line: per-line override, else the global default, else the line's own
+/// `rate_bps`.
+fn effective_penalty_rate(env: &Env, borrower: &Address, rate_bps: u32) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::LinePenaltyRate(borrower.clone()))
+        .or_else(|| env.storage().instance().get(&DataKey::PenaltyRateBps))
+        .unwrap_or(rate_bps)
+}
+
+pub fn rate_history(env: &Env, borrower: &Address) -> Vec<RateChange> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RateHistory(borrower.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Start a fresh rate log for a newly opened line.
+pub fn start_rate_history(env: &Env, borrower: &Address, line: &CreditLineData, actor: Address) {
+    let mut history = Vec::new(env);
+    history.push_back(RateChange {
+        interest_rate_bps: line.interest_rate_bps,
+        effective_ts: line.last_accrual_ts,
+        actor,
+    });
+    env.storage()
+        .persistent()
+        .set(&DataKey::RateHistory(borrower.clone()), &history);
+}
+
+/// Settle the line at its old rate, set the new rate from now on, checkpoint the line's index
+/// and log the change. Caller is responsible for persisting the line.
+pub fn record_rate_change(
+    env: &Env,
+    borrower: &Address,
+    line: &mut CreditLineData,
+    interest_rate_bps: u32,
+    actor: Address,
+) -> Result<(), CreditError> {
+    accrue_interest(env, borrower, line)?;
+    borrow_index::checkpoint_rate(env, borrower, line, interest_rate_bps)?;
+    let mut history = rate_history(env, borrower);
+    if history.len() >= MAX_RATE_HISTORY {
+        history.pop_front();
+    }
+    history.push_back(RateChange {
+        interest_rate_bps,
+        effective_ts: env.ledger().timestamp(),
+        actor,
+    });
+    env.storage()
+        .persistent()
+        .set(&DataKey::RateHistory(borrower.clone()), &history);
+    line.interest_rate_bps = interest_rate_bps;
+    Ok(())
+}
+
+pub fn period_secs(period: CapitalizationPeriod) -> u64 {
+    match period {
+        CapitalizationPeriod::None | CapitalizationPeriod::PerLedger => 0,
+        CapitalizationPeriod::Daily => DAY_SECS,
+        CapitalizationPeriod::Monthly => MONTH_SECS,
+        CapitalizationPeriod::Quarterly => QUARTER_SECS,
+    }
+}
+
+/// Capitalization period given to newly opened lines; `None` (simple interest) by default.
+pub fn default_period(env: &Env) -> CapitalizationPeriod {
+    env.storage()
+        .instance()
+        .get(&CompoundingKey::Default)
+        .unwrap_or(CapitalizationPeriod::None)
+}
+
+pub fn set_default_period(env: &Env, period: CapitalizationPeriod) {
+    match period {
+        CapitalizationPeriod::None => env.storage().instance().remove(&CompoundingKey::Default),
+        _ => env
+            .storage()
+            .instance()
+            .set(&CompoundingKey::Default, &period),
+    }
+}
+
+/// Schedule for `period` with periods counted from now; `None` for simple interest.
+pub fn schedule_from_now(
+    env: &Env,
+    period: CapitalizationPeriod,
+) -> Option<CapitalizationSchedule> {
+    match period {
+        CapitalizationPeriod::None => None,
+        _ => Some(CapitalizationSchedule {
+            period,
+            last_capitalized_ts: env.ledger().timestamp(),
+        }),
+    }
+}
+
+/// Effective annual rate (bps) of `rate_bps` under `period`: the growth of a balance left
+/// untouched for a 365-day year, compounded as the accrual engine would.
+pub fn effective_annual_rate(
+    rate_bps: u32,
+    period: CapitalizationPeriod,
+) -> Result<u32, CreditError> {
+    if period == CapitalizationPeriod::None {
+        return Ok(rate_bps);
+    }
+    // Large enough that rounding is negligible at basis-point precision.
+    let start: i128 = 1_000_000_000_000;
+    let end = grown_balance(start, rate_bps, period, interest::SECONDS_PER_YEAR)?;
+    let bps = (end - start) * interest::BPS_DENOMINATOR / start;
+    u32::try_from(bps).map_err(|_| CreditError::Overflow)
+}
+
+/// What `start` grows to when left untouched for `secs` at `rate_bps` under `period`,
+/// compounded as the accrual engine would.
+pub fn grown_balance(
+    start: i128,
+    rate_bps: u32,
+    period: CapitalizationPeriod,
+    secs: u64,
+) -> Result<i128, CreditError> {
+    Ok(match period {
+        CapitalizationPeriod::None => start + interest::simple_interest(start, rate_bps, secs)?,
+        CapitalizationPeriod::PerLedger => {
+            let simple = interest::simple_interest(start, rate_bps, secs)?;
+            start + interest::continuous_interest(start, simple)?
+        }
+        _ => {
+            let period_secs = period_secs(period);
+            let mut balance = start;
+            for _ in 0..secs / period_secs {
+                balance += interest::simple_interest(balance, rate_bps, period_secs)?;
+            }
+            balance + interest::simple_interest(balance, rate_bps, secs % period_secs)?
+        }
+    })
+}
+
+pub fn get_schedule(env: &Env, borrower: &Address) -> Option<CapitalizationSchedule> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Capitalization(borrower.clone()))
+}
+
+pub fn set_schedule(env: &Env, borrower: &Address, schedule: Option<CapitalizationSchedule>) {
+    let key = DataKey::Capitalization(borrower.clone());
+    match schedule {
+        Some(schedule) => env.storage().persistent().set(&key, &schedule),
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+/// One accrual pass. A committed pass persists its side effects (promo and schedule state,
+/// rebate budgets, summaries, delinquencies and events); a preview only moves the line, keeping
+/// track of the rebate budget it would have spent.
+struct Pass {
+    commit: bool,
+    rebated: i128,
+    /// The line's index as of its `last_accrual_ts`.
+    snapshot: i128,
+}
+
+/// Accrue interest on `line` up to the current ledger timestamp, capitalizing at every schedule
+/// boundary crossed and settling a deferred-interest promo whose end has passed. Caller is
+/// responsible for persisting the line.
+pub fn accrue_interest(
+    env: &Env,
+    borrower: &Address,
+    line: &mut CreditLineData,
+) -> Result<(), CreditError> {
+    run(
+        env,
+        borrower,
+        line,
+        &mut Pass {
+            commit: true,
+            rebated: 0,
+            snapshot: 0,
+        },
+    )
+}
+
+/// Bring `line` to where `accrue_interest` would leave it now, without writing anything.
+pub fn preview(
+    env: &Env,
+    borrower: &Address,
+    line: &mut CreditLineData,
+) -> Result<(), CreditError> {
+    run(
+        env,
+        borrower,
+        line,
+        &mut Pass {
+            commit: false,
+            rebated: 0,
+            snapshot: 0,
+        },
+    )
+}
+
+fn run(
+    env: &Env,
+    borrower: &Address,
+    line: &mut CreditLineData,
+    pass: &mut Pass,
+) -> Result<(), CreditError> {
+    let now = env.ledger().timestamp();
+    if now <= line.last_accrual_ts {
+        return Ok(());
+    }
+    pass.snapshot = borrow_index::snapshot(env, borrower, line)?;
+    let schedule = get_schedule(env, borrower);
+    let continuous = schedule
+        .as_ref()
+        .is_some_and(|schedule| schedule.period == CapitalizationPeriod::PerLedger);
+    if let Some(mut promo) = promo::get(env, borrower) {
+        // Interest inside a deferred-interest promo is tracked on the promo, not the line.
+        if line.last_accrual_ts < promo.ends_at {
+            let to = now.min(promo.ends_at);
+            let interest = span_interest(env, borrower, line, to, continuous, pass)?;
+            promo.deferred_interest = promo
+                .deferred_interest
+                .checked_add(interest)
+                .ok_or(CreditError::Overflow)?;
+            line.last_accrual_ts = to;
+            if pass.commit {
+                promo::set(env, borrower, Some(promo.clone()));
+            }
+        }
+        if now >= promo.ends_at {
+            if pass.commit {
+                promo::settle(env, borrower, line, &promo)?;
+            } else {
+                line.accrued_interest = line
+                    .accrued_interest
+                    .checked_add(promo::charged_interest(line, &promo))
+                    .ok_or(CreditError::Overflow)?;
+            }
+        }
+    }
+    if let Some(mut schedule) = schedule {
+        let period = period_secs(schedule.period);
+        let mut settled = 0;
+        while period > 0 && settled < MAX_CAPITALIZATIONS_PER_ACCRUAL {
+            let boundary = schedule.last_capitalized_ts.saturating_add(period);
+            if boundary > now {
+                break;
+            }
+            accrue_span(env, borrower, line, boundary, false, pass)?;
+            capitalize(env, borrower, line, boundary, pass)?;
+            schedule.last_capitalized_ts = boundary;
+            settled += 1;
+        }
+        if settled > 0 && pass.commit {
+            set_schedule(env, borrower, Some(schedule));
+        }
+    }
+    accrue_span(env, borrower, line, now, continuous, pass)?;
+    if continuous {
+        capitalize(env, borrower, line, now, pass)?;
+    }
+    if pass.commit {
+        borrow_index::set_snapshot(env, borrower, line, pass.snapshot);
+    }
+    Ok(())
+}
+
+/// Move accrued interest into principal and emit a capitalization event.
+fn capitalize(
+    env: &Env,
+    borrower: &Address,
+    line: &mut CreditLineData,
+    at: u64,
+    pass: &Pass,
+) -> Result<(), CreditError> {
+    let amount = line.accrued_interest;
+    if amount <= 0 {
+        return Ok(());
+    }
+    line.utilized_amount = line
+        .utilized_amount
+        .checked_add(amount)
+        .ok_or(CreditError::Overflow)?;
+    line.accrued_interest = 0;
+    if !pass.commit {
+        return Ok(());
+    }
+    summary::record_utilization(env, borrower, line.asset.as_ref(), line.utilized_amount);
+    publish_capitalization(
+        env,
+        CapitalizationEvent {
+            borrower: borrower.clone(),
+            amount,
+            new_utilized_amount: line.utilized_amount,
+            timestamp: at,
+        },
+    );
+    Ok(())
+}
+
+/// Interest on `line` from `last_accrual_ts` to `to`, net of rebates, charged from the line's
+/// index snapshot to its index at `to`; moves the snapshot to `to`. The unpaid portion of a
+/// posted payment due accrues at the penalty rate once the due date plus grace period has
+/// passed, unless the balance is under the grace amount. With `continuous`, the span's interest
+/// is compounded continuously on the principal. A committed pass records a delinquency crossed
+/// in the span; does not move `last_accrual_ts`.
+fn span_interest(
+    env: &Env,
+    borrower: &Address,
+    line: &CreditLineData,
+    to: u64,
+    continuous: bool,
+    pass: &mut Pass,
+) -> Result<i128, CreditError> {
+    if to <= line.last_accrual_ts {
+        return Ok(0);
+    }
+    let due: Option<PaymentDue> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::PaymentDue(borrower.clone()));
+    let (overdue, penalty_start) = match due {
+        Some(due) if !maturity::below_grace_amount(env, line) => (
+            due.amount,
+            Some(due.due_ts.saturating_add(grace_period(env))),
+        ),
+        _ => (0, None),
+    };
+    let from = line.last_accrual_ts;
+    let to_index = borrow_index::index_at(env, borrower, line, to)?;
+    let grown = to_index
+        .checked_sub(pass.snapshot)
+        .ok_or(CreditError::Overflow)?;
+    let principal = line.utilized_amount;
+    let overdue = overdue.clamp(0, principal.max(0));
+    let mut interest = match penalty_start {
+        Some(start) if overdue > 0 && start < to => {
+            // The overdue portion is charged the index up to the penalty start, then the
+            // penalty rate.
+            let penalty_from = start.max(from);
+            let before_penalty = borrow_index::index_at(env, borrower, line, penalty_from)?
+                .checked_sub(pass.snapshot)
+                .ok_or(CreditError::Overflow)?;
+            interest::index_interest(principal - overdue, grown)?
+                + interest::index_interest(overdue, before_penalty)?
+                + interest::simple_interest(
+                    overdue,
+                    effective_penalty_rate(env, borrower, line.interest_rate_bps),
+                    to - penalty_from,
+                )?
+        }
+        _ => interest::index_interest(principal, grown)?,
+    };
+    pass.snapshot = to_index;
+    if continuous {
+        interest = interest::continuous_interest(line.utilized_amount, interest)?;
+    }
+    if !pass.commit {
+        let rebate =
+            incentives::preview_interest_rebate(env, borrower, from, to, interest, pass.rebated);
+        pass.rebated += rebate;
+        return Ok(interest - rebate);
+    }
+    if overdue > 0 && penalty_start.is_some_and(|ts| ts > from && ts <= to) {
+        summary::record_delinquency(env, borrower, line.asset.as_ref());
+        behavior::record_delinquency(env, line);
+    }
+    let rebate = incentives::apply_interest_rebate(env, borrower, from, to, interest);
+    Ok(interest - rebate)
+}
+
+/// Accrue interest from `line.last_accrual_ts` to `to` into `accrued_interest` (see
+/// `span_interest`).
+fn accrue_span(
+    env: &Env,
+    borrower: &Address,
+    line: &mut CreditLineData,
+    to: u64,
+    continuous: bool,
+    pass: &mut Pass,
+) -> Result<(), CreditError> {
+    if to <= line.last_accrual_ts {
+        return Ok(());
+    }
+    let interest = span_interest(env, borrower, line, to, continuous, pass)?;
+    line.accrued_interest = line
+        .accrued_interest
+        .checked_add(interest)
+        .ok_or(CreditError::Overflow)?;
+    line.last_accrual_ts = to;
+    if interest > 0 && pass.commit {
+        summary::record_accrual(env, borrower, line.asset.as_ref(), interest);
+        publish_interest_accrued(
+            env,
+            InterestAccruedEvent {
+                borrower: borrower.clone(),
+                interest,
+                accrued_interest: line.accrued_interest,
+                timestamp: to,
+            },
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,96 @@
+//! Per-line utilization alert thresholds. The risk engine sets a few utilization levels (in
+//! basis points of the credit limit); a draw that takes the line across one emits a dedicated
+//! alert event, so monitoring does not have to replay every draw.
+
+use soroban_sdk::{contracttype, Address, Env, Val, Vec};
+
+use crate::events::{self, UtilizationAlertEvent};
+use crate::types::CreditLineData;
+use crate::{ttl, CreditError};
+
+/// Storage keys for alert thresholds.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AlertKey {
+    UtilizationAlerts(Address),
+}
+
+/// Maximum number of thresholds on one line.
+pub const MAX_ALERT_THRESHOLDS: u32 = 5;
+
+fn key(env: &Env, borrower: &Address, asset: Option<&Address>) -> Val {
+    ttl::entry_key(env, AlertKey::UtilizationAlerts(borrower.clone()), asset)
+}
+
+/// The line's thresholds in basis points, lowest first; empty if none are set.
+pub fn thresholds(env: &Env, borrower: &Address, asset: Option<&Address>) -> Vec<u32> {
+    env.storage()
+        .persistent()
+        .get(&key(env, borrower, asset))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Store the line's thresholds, or remove them with an empty list.
+///
+/// # Errors
+/// * `InvalidParameter` if there are too many, one is outside `1..=10_000` or they are not
+///   strictly ascending
+pub fn set_thresholds(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+    thresholds: &Vec<u32>,
+) -> Result<(), CreditError> {
+    let key = key(env, borrower, asset);
+    if thresholds.is_empty() {
+        env.storage().persistent().remove(&key);
+        return Ok(());
+    }
+    if thresholds.len() > MAX_ALERT_THRESHOLDS {
+        return Err(CreditError::InvalidParameter);
+    }
+    let mut previous = 0;
+    for threshold_bps in thresholds.iter() {
+        if threshold_bps <= previous || threshold_bps > 10_000 {
+            return Err(CreditError::InvalidParameter);
+        }
+        previous = threshold_bps;
+    }
+    env.storage().persistent().set(&key, thresholds);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ttl::LINE_BUMP_THRESHOLD, ttl::LINE_BUMP_AMOUNT);
+    Ok(())
+}
+
+/// Clear the line's thresholds, when a new line is opened.
+pub fn clear(env: &Env, borrower: &Address, asset: Option<&Address>) {
+    env.storage()
+        .persistent()
+        .remove(&key(env, borrower, asset));
+}
+
+/// Publish an alert for every threshold a draw crossed, going from `previous_utilized` to the
+/// line's current utilization. Utilization is measured against the credit limit, without any
+/// temporary boost.
+pub fn notify_draw(env: &Env, line: &CreditLineData, previous_utilized: i128) {
+    if line.credit_limit <= 0 {
+        return;
+    }
+    for threshold_bps in thresholds(env, &line.borrower, line.asset.as_ref()).iter() {
+        let level = line.credit_limit.saturating_mul(threshold_bps as i128);
+        if previous_utilized.saturating_mul(10_000) < level
+            && line.utilized_amount.saturating_mul(10_000) >= level
+        {
+            events::publish_utilization_alert(
+                env,
+                UtilizationAlertEvent {
+                    borrower: line.borrower.clone(),
+                    threshold_bps,
+                    utilized_amount: line.utilized_amount,
+                    credit_limit: line.credit_limit,
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Repayment in an approved alternate token. The borrower pays the alternate token to the
+//! asset's counterparty, which supplies the line's own token in exchange from an allowance it
+//! granted this contract; the line is credited with the oracle value of the payment less the
+//! asset's haircut, which compensates the counterparty for price risk.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::types::RepaymentAsset;
+use crate::{price, statement, CreditError};
+
+/// Storage keys for alternate repayment assets.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AltRepayKey {
+    RepaymentAsset(Address),
+}
+
+pub fn get(env: &Env, asset: &Address) -> Option<RepaymentAsset> {
+    env.storage()
+        .instance()
+        .get(&AltRepayKey::RepaymentAsset(asset.clone()))
+}
+
+/// Approve `asset` for repayments, or withdraw the approval with `None`.
+pub fn set(env: &Env, asset: &Address, config: Option<&RepaymentAsset>) {
+    let key = AltRepayKey::RepaymentAsset(asset.clone());
+    match config {
+        Some(config) => env.storage().instance().set(&key, config),
+        None => env.storage().instance().remove(&key),
+    }
+}
+
+/// Amount of `line_token` credited for paying `amount` of `asset`: its value at the price
+/// oracle's latest prices, less `haircut_bps`. Rounds down.
+///
+/// # Errors
+/// * `NotConfigured` if no price oracle is set
+/// * The errors of `price::rate`
+pub fn credited(
+    env: &Env,
+    asset: &Address,
+    line_token: &Address,
+    haircut_bps: u32,
+    amount: i128,
+) -> Result<i128, CreditError> {
+    let oracle = statement::price_oracle(env).ok_or(CreditError::NotConfigured)?;
+    let (numerator, denominator) = price::rate(env, &oracle, asset, line_token)?;
+    let value = amount.checked_mul(numerator).ok_or(CreditError::Overflow)? / denominator;
+    Ok(value
+        .checked_mul(i128::from(10_000 - haircut_bps))
+        .ok_or(CreditError::Overflow)?
+        / 10_000)
+}
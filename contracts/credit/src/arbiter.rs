@@ -0,0 +1,105 @@
+//! Arbiter registry: approved addresses that resolve disputes, chargebacks and fee waivers on
+//! lines, so servicing decisions need not all go through the admin. Arbiters are added and
+//! removed through governance (directly by the admin only while no timelock or council is
+//! set). Each arbiter is bounded by a per-action amount cap and a number of actions per 30-day
+//! period, and its actions are counted.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::types::ArbiterLimits;
+use crate::{ttl, CreditError};
+
+/// Length of an arbiter action-count period.
+pub const PERIOD_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Storage keys for the arbiter registry.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ArbiterKey {
+    /// Arbiter -> its limits; present only while the arbiter is approved.
+    Limits(Address),
+    /// Arbiter -> actions taken since it was first approved.
+    Actions(Address),
+    /// (arbiter, period index) -> actions taken in that period.
+    ActionsInPeriod(Address, u64),
+}
+
+pub fn limits(env: &Env, arbiter: &Address) -> Option<ArbiterLimits> {
+    env.storage()
+        .persistent()
+        .get(&ArbiterKey::Limits(arbiter.clone()))
+}
+
+/// Approve `arbiter` with `limits`, or remove it with `None`. Counters are kept across
+/// removal so a re-approved arbiter keeps its record.
+pub fn set_limits(
+    env: &Env,
+    arbiter: &Address,
+    limits: Option<ArbiterLimits>,
+) -> Result<(), CreditError> {
+    let key = ArbiterKey::Limits(arbiter.clone());
+    match limits {
+        Some(limits) => {
+            if limits.max_amount <= 0 || limits.max_actions_per_period == 0 {
+                return Err(CreditError::InvalidParameter);
+            }
+            env.storage().persistent().set(&key, &limits);
+            env.storage().persistent().extend_ttl(
+                &key,
+                ttl::LINE_BUMP_THRESHOLD,
+                ttl::LINE_BUMP_AMOUNT,
+            );
+        }
+        None => env.storage().persistent().remove(&key),
+    }
+    Ok(())
+}
+
+pub fn total_actions(env: &Env, arbiter: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&ArbiterKey::Actions(arbiter.clone()))
+        .unwrap_or(0)
+}
+
+fn used_key(env: &Env, arbiter: &Address) -> ArbiterKey {
+    ArbiterKey::ActionsInPeriod(arbiter.clone(), env.ledger().timestamp() / PERIOD_SECS)
+}
+
+/// Actions the arbiter has taken in the current period.
+pub fn period_actions(env: &Env, arbiter: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&used_key(env, arbiter))
+        .unwrap_or(0)
+}
+
+/// Check that `arbiter` is approved and may act on `amount` now, then count the action.
+/// Returns the actions taken in the current period, including this one.
+///
+/// # Errors
+/// * `Unauthorized` if `arbiter` is not in the registry
+/// * `ArbiterLimitExceeded` if `amount` is above the arbiter's cap or its actions for the
+///   period are used up
+pub fn consume(env: &Env, arbiter: &Address, amount: i128) -> Result<u32, CreditError> {
+    let limits = limits(env, arbiter).ok_or(CreditError::Unauthorized)?;
+    let used = period_actions(env, arbiter);
+    if amount > limits.max_amount || used >= limits.max_actions_per_period {
+        return Err(CreditError::ArbiterLimitExceeded);
+    }
+    for (key, count) in [
+        (used_key(env, arbiter), used + 1),
+        (
+            ArbiterKey::Actions(arbiter.clone()),
+            total_actions(env, arbiter) + 1,
+        ),
+    ] {
+        env.storage().persistent().set(&key, &count);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ttl::LINE_BUMP_THRESHOLD,
+            ttl::LINE_BUMP_AMOUNT,
+        );
+    }
+    Ok(used + 1)
+}
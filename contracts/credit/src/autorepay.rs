@@ -0,0 +1,93 @@
+//! Borrower prepaid deposit accounts: funds a borrower parks in the contract, which they can
+//! repay from at any time and which keepers sweep towards the line's payment due once it falls
+//! due if the borrower opted in to auto-repay.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::ttl;
+
+/// Storage keys for repayment deposits.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AutoRepayKey {
+    RepayDeposit(Address),
+    AutoRepay(Address),
+    /// Sum of all borrowers' deposits in a token, held by this contract and not lendable.
+    DepositTotal(Address),
+}
+
+/// A borrower's deposit and the token it is held in.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Deposit {
+    pub token: Address,
+    pub amount: i128,
+}
+
+pub fn deposit(env: &Env, borrower: &Address) -> Option<Deposit> {
+    env.storage()
+        .persistent()
+        .get(&AutoRepayKey::RepayDeposit(borrower.clone()))
+}
+
+/// Balance of the borrower's deposit held in `token`.
+pub fn balance_in(env: &Env, borrower: &Address, token: &Address) -> i128 {
+    deposit(env, borrower)
+        .filter(|deposit| deposit.token == *token)
+        .map_or(0, |deposit| deposit.amount)
+}
+
+pub fn total(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .instance()
+        .get(&AutoRepayKey::DepositTotal(token.clone()))
+        .unwrap_or(0)
+}
+
+/// Add `delta` (negative to take funds out) to the borrower's deposit in `token` and to the
+/// token's total, returning the new balance.
+pub fn adjust(env: &Env, borrower: &Address, token: &Address, delta: i128) -> i128 {
+    let balance = balance_in(env, borrower, token) + delta;
+    let key = AutoRepayKey::RepayDeposit(borrower.clone());
+    if balance == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(
+            &key,
+            &Deposit {
+                token: token.clone(),
+                amount: balance,
+            },
+        );
+        env.storage().persistent().extend_ttl(
+            &key,
+            ttl::LINE_BUMP_THRESHOLD,
+            ttl::LINE_BUMP_AMOUNT,
+        );
+    }
+    env.storage().instance().set(
+        &AutoRepayKey::DepositTotal(token.clone()),
+        &(total(env, token) + delta),
+    );
+    balance
+}
+
+pub fn enabled(env: &Env, borrower: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&AutoRepayKey::AutoRepay(borrower.clone()))
+}
+
+pub fn set_enabled(env: &Env, borrower: &Address, enabled: bool) {
+    let key = AutoRepayKey::AutoRepay(borrower.clone());
+    if enabled {
+        env.storage().persistent().set(&key, &true);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ttl::LINE_BUMP_THRESHOLD,
+            ttl::LINE_BUMP_AMOUNT,
+        );
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
@@ -0,0 +1,32 @@
+//! Automatic suspension of lines whose risk score falls below an admin-configured minimum.
+
+use soroban_sdk::{contracttype, Env};
+
+/// Storage keys for the auto-suspension threshold.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AutoSuspendKey {
+    MinRiskScore,
+}
+
+pub fn min_risk_score(env: &Env) -> Option<u32> {
+    env.storage().instance().get(&AutoSuspendKey::MinRiskScore)
+}
+
+pub fn set_min_risk_score(env: &Env, min_risk_score: Option<u32>) {
+    match min_risk_score {
+        Some(score) => env
+            .storage()
+            .instance()
+            .set(&AutoSuspendKey::MinRiskScore, &score),
+        None => env
+            .storage()
+            .instance()
+            .remove(&AutoSuspendKey::MinRiskScore),
+    }
+}
+
+/// The configured minimum if `risk_score` is below it.
+pub fn breached(env: &Env, risk_score: u32) -> Option<u32> {
+    min_risk_score(env).filter(|min| risk_score < *min)
+}
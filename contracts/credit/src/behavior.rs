@@ -0,0 +1,92 @@
+//! On-chain behavioral credit score. Repayment behavior, delinquencies, defaults and
+//! utilization are tracked per borrower from contract activity, complementing the externally
+//! supplied `risk_score`. The record spans every line the borrower has held.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::ttl;
+use crate::types::{BehaviorScore, CreditLineData};
+
+/// Score before any activity.
+const BASE_SCORE: i64 = 50;
+/// Points per on-time repayment, counted for at most `MAX_ON_TIME_COUNTED` repayments.
+const ON_TIME_POINTS: i64 = 2;
+const MAX_ON_TIME_COUNTED: u32 = 25;
+const LATE_PENALTY: i64 = 5;
+const DELINQUENCY_PENALTY: i64 = 10;
+const DEFAULT_PENALTY: i64 = 40;
+/// One point off per 5% of the limit in use, so a fully drawn line loses 20 points.
+const UTILIZATION_BPS_PER_POINT: u32 = 500;
+
+/// Storage keys for behavior scores.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BehaviorKey {
+    Score(Address),
+}
+
+pub fn get(env: &Env, borrower: &Address) -> Option<BehaviorScore> {
+    env.storage()
+        .persistent()
+        .get(&BehaviorKey::Score(borrower.clone()))
+}
+
+fn update(env: &Env, line: &CreditLineData, f: impl FnOnce(&mut BehaviorScore)) {
+    let mut record = get(env, &line.borrower).unwrap_or(BehaviorScore {
+        score: 0,
+        on_time_repayments: 0,
+        late_repayments: 0,
+        delinquencies: 0,
+        defaults: 0,
+        utilization_bps: 0,
+        updated_at: 0,
+    });
+    f(&mut record);
+    record.utilization_bps = if line.credit_limit > 0 {
+        (line.utilized_amount.max(0) * 10_000 / line.credit_limit).min(10_000) as u32
+    } else {
+        0
+    };
+    record.score = score(&record);
+    record.updated_at = env.ledger().timestamp();
+    let key = BehaviorKey::Score(line.borrower.clone());
+    env.storage().persistent().set(&key, &record);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ttl::LINE_BUMP_THRESHOLD, ttl::LINE_BUMP_AMOUNT);
+}
+
+/// Score in `0..=100` from the tracked counters and current utilization.
+fn score(record: &BehaviorScore) -> u32 {
+    let score = BASE_SCORE
+        + ON_TIME_POINTS * record.on_time_repayments.min(MAX_ON_TIME_COUNTED) as i64
+        - LATE_PENALTY * record.late_repayments as i64
+        - DELINQUENCY_PENALTY * record.delinquencies as i64
+        - DEFAULT_PENALTY * record.defaults as i64
+        - (record.utilization_bps / UTILIZATION_BPS_PER_POINT) as i64;
+    score.clamp(0, 100) as u32
+}
+
+/// Refresh the utilization component after a draw.
+pub fn record_draw(env: &Env, line: &CreditLineData) {
+    update(env, line, |_| {});
+}
+
+/// Count a repayment; it is late if it was made while a payment due was past its grace period.
+pub fn record_repayment(env: &Env, line: &CreditLineData, late: bool) {
+    update(env, line, |record| {
+        if late {
+            record.late_repayments += 1;
+        } else {
+            record.on_time_repayments += 1;
+        }
+    });
+}
+
+pub fn record_delinquency(env: &Env, line: &CreditLineData) {
+    update(env, line, |record| record.delinquencies += 1);
+}
+
+pub fn record_default(env: &Env, line: &CreditLineData) {
+    update(env, line, |record| record.defaults += 1);
+}
@@ -0,0 +1,52 @@
+//! Sanctions blacklist, kept apart from the KYC allowlist: a blacklisted address cannot open a
+//! line or draw on one, whatever its KYC status, but can still repay. The list is managed by
+//! the admin or by compliance officers the admin appoints.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::ttl;
+
+/// Storage keys for the blacklist.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BlacklistKey {
+    /// Address holding the compliance role.
+    Officer(Address),
+    /// Blacklisted address.
+    Blocked(Address),
+}
+
+pub fn is_officer(env: &Env, account: &Address) -> bool {
+    env.storage()
+        .instance()
+        .has(&BlacklistKey::Officer(account.clone()))
+}
+
+pub fn set_officer(env: &Env, account: &Address, enabled: bool) {
+    let key = BlacklistKey::Officer(account.clone());
+    if enabled {
+        env.storage().instance().set(&key, &true);
+    } else {
+        env.storage().instance().remove(&key);
+    }
+}
+
+pub fn is_blocked(env: &Env, account: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&BlacklistKey::Blocked(account.clone()))
+}
+
+pub fn set_blocked(env: &Env, account: &Address, blocked: bool) {
+    let key = BlacklistKey::Blocked(account.clone());
+    if blocked {
+        env.storage().persistent().set(&key, &true);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ttl::LINE_BUMP_THRESHOLD,
+            ttl::LINE_BUMP_AMOUNT,
+        );
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
@@ -0,0 +1,238 @@
+//! Borrow indexes: cumulative interest indexes for each line and for the whole book.
+//!
+//! Lines are priced individually, so each keeps its own index rather than reading one
+//! Compound-style index per asset. A line's index is the cumulative `rate * seconds` it has been
+//! charged since it opened. It is checkpointed on every rate change (`checkpoint_rate`), which
+//! settles the line first, so between checkpoints the index grows linearly at a single rate and
+//! its value at any time follows from the last checkpoint. The line stores a snapshot of its
+//! index as of `last_accrual_ts`, and accrual charges the principal the difference between the
+//! index now and the snapshot (see `accrual`).
+//!
+//! The book index tracks what the lines owe together, in constant time however many lines
+//! exist: it holds the sum of `utilized * rate` over open lines and the interest accrued across
+//! them since their snapshots, checkpointed on every line write and by `bump_borrow_index`.
+//! When a line settles its interest, its snapshot moves to now and the interest moves from the
+//! book index into the line's `accrued_interest`. Penalty interest, rebates and compounding are
+//! only counted once the line settles.
+
+use soroban_sdk::{contracttype, panic_with_error, Address, Env};
+
+use crate::interest::{BPS_DENOMINATOR, SECONDS_PER_YEAR};
+use crate::types::{BorrowIndex, CreditLineData, CreditStatus};
+use crate::{ttl, CreditError};
+
+/// Storage keys for the borrow indexes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BorrowIndexKey {
+    /// The book index (`BookIndex`), in instance storage.
+    Book,
+    /// A line's index (`LineIndex`).
+    Line(Address),
+}
+
+/// The book index as of its last checkpoint.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BookIndex {
+    /// Sum of `utilized_amount * interest_rate_bps` over open lines.
+    pub rate_weighted_principal: i128,
+    /// Interest accrued across open lines since their snapshots, in `principal * bps * seconds`.
+    pub accrued: i128,
+    pub updated_ts: u64,
+}
+
+/// A line's index as of its last rate checkpoint, and its snapshot as of `last_accrual_ts`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineIndex {
+    /// Cumulative `rate_bps * seconds` charged to the line up to `updated_ts`.
+    pub index: i128,
+    /// Rate the index has grown at since `updated_ts`.
+    pub rate_bps: u32,
+    pub updated_ts: u64,
+    /// Index at `snapshot_ts`, where the line last settled.
+    pub snapshot: i128,
+    pub snapshot_ts: u64,
+}
+
+fn read_line_index(env: &Env, borrower: &Address) -> Option<LineIndex> {
+    env.storage()
+        .persistent()
+        .get(&BorrowIndexKey::Line(borrower.clone()))
+}
+
+fn write_line_index(env: &Env, borrower: &Address, index: &LineIndex) {
+    let key = BorrowIndexKey::Line(borrower.clone());
+    env.storage().persistent().set(&key, index);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ttl::LINE_BUMP_THRESHOLD, ttl::LINE_BUMP_AMOUNT);
+}
+
+/// The line's index, starting from zero at its last settlement for lines opened before indexes
+/// were kept.
+fn line_index(env: &Env, borrower: &Address, line: &CreditLineData) -> LineIndex {
+    read_line_index(env, borrower).unwrap_or(LineIndex {
+        index: 0,
+        rate_bps: line.interest_rate_bps,
+        updated_ts: line.last_accrual_ts,
+        snapshot: 0,
+        snapshot_ts: line.last_accrual_ts,
+    })
+}
+
+fn value_at(index: &LineIndex, ts: u64) -> Result<i128, CreditError> {
+    (index.rate_bps as i128)
+        .checked_mul(ts.saturating_sub(index.updated_ts) as i128)
+        .and_then(|grown| index.index.checked_add(grown))
+        .ok_or(CreditError::Overflow)
+}
+
+/// Start a newly opened line's index at zero.
+pub fn start_line(env: &Env, line: &CreditLineData) {
+    write_line_index(
+        env,
+        &line.borrower,
+        &LineIndex {
+            index: 0,
+            rate_bps: line.interest_rate_bps,
+            updated_ts: line.last_accrual_ts,
+            snapshot: 0,
+            snapshot_ts: line.last_accrual_ts,
+        },
+    );
+}
+
+/// The line's index at `ts`, which must not precede its last rate checkpoint.
+pub fn index_at(
+    env: &Env,
+    borrower: &Address,
+    line: &CreditLineData,
+    ts: u64,
+) -> Result<i128, CreditError> {
+    value_at(&line_index(env, borrower, line), ts)
+}
+
+/// The line's index as of its `last_accrual_ts`: the stored snapshot when it is current.
+pub fn snapshot(env: &Env, borrower: &Address, line: &CreditLineData) -> Result<i128, CreditError> {
+    let index = line_index(env, borrower, line);
+    if index.snapshot_ts == line.last_accrual_ts {
+        return Ok(index.snapshot);
+    }
+    value_at(&index, line.last_accrual_ts)
+}
+
+/// Store `snapshot` as the line's index as of its `last_accrual_ts`.
+pub fn set_snapshot(env: &Env, borrower: &Address, line: &CreditLineData, snapshot: i128) {
+    let mut index = line_index(env, borrower, line);
+    index.snapshot = snapshot;
+    index.snapshot_ts = line.last_accrual_ts;
+    write_line_index(env, borrower, &index);
+}
+
+/// Checkpoint the line's index at now, from where it grows at `rate_bps`. The line must have
+/// been settled to now, so no accrual span runs across the checkpoint.
+pub fn checkpoint_rate(
+    env: &Env,
+    borrower: &Address,
+    line: &CreditLineData,
+    rate_bps: u32,
+) -> Result<(), CreditError> {
+    let now = env.ledger().timestamp();
+    let mut index = line_index(env, borrower, line);
+    index.index = value_at(&index, now)?;
+    index.rate_bps = rate_bps;
+    index.updated_ts = now;
+    write_line_index(env, borrower, &index);
+    Ok(())
+}
+
+fn book(env: &Env) -> BookIndex {
+    env.storage()
+        .instance()
+        .get(&BorrowIndexKey::Book)
+        .unwrap_or(BookIndex {
+            rate_weighted_principal: 0,
+            accrued: 0,
+            updated_ts: env.ledger().timestamp(),
+        })
+}
+
+/// The book index brought forward to now.
+fn book_now(env: &Env) -> Result<BookIndex, CreditError> {
+    let mut book = book(env);
+    let now = env.ledger().timestamp();
+    book.accrued = book
+        .rate_weighted_principal
+        .checked_mul(now.saturating_sub(book.updated_ts) as i128)
+        .and_then(|grown| book.accrued.checked_add(grown))
+        .ok_or(CreditError::Overflow)?;
+    book.updated_ts = now;
+    Ok(book)
+}
+
+/// A line's `utilized * rate` and its interest accrued since its snapshot as of `now`, in book
+/// index units; closed lines accrue nothing.
+fn weights(line: &CreditLineData, now: u64) -> Result<(i128, i128), CreditError> {
+    if line.status == CreditStatus::Closed || line.utilized_amount <= 0 {
+        return Ok((0, 0));
+    }
+    let weighted = line
+        .utilized_amount
+        .checked_mul(line.interest_rate_bps as i128)
+        .ok_or(CreditError::Overflow)?;
+    let accrued = weighted
+        .checked_mul(now.saturating_sub(line.last_accrual_ts) as i128)
+        .ok_or(CreditError::Overflow)?;
+    Ok((weighted, accrued))
+}
+
+/// Checkpoint the book index at now and record the transition of a line from `before` (None
+/// when newly created) to `after`.
+pub fn record_line_change(env: &Env, before: Option<&CreditLineData>, after: &CreditLineData) {
+    let now = env.ledger().timestamp();
+    let change = || -> Result<BookIndex, CreditError> {
+        let (before_weighted, before_accrued) = match before {
+            Some(before) => weights(before, now)?,
+            None => (0, 0),
+        };
+        let (after_weighted, after_accrued) = weights(after, now)?;
+        let mut book = book_now(env)?;
+        book.rate_weighted_principal = book
+            .rate_weighted_principal
+            .checked_sub(before_weighted)
+            .and_then(|sum| sum.checked_add(after_weighted))
+            .ok_or(CreditError::Overflow)?;
+        book.accrued = book
+            .accrued
+            .checked_sub(before_accrued)
+            .and_then(|sum| sum.checked_add(after_accrued))
+            .ok_or(CreditError::Overflow)?;
+        Ok(book)
+    };
+    match change() {
+        Ok(book) => env.storage().instance().set(&BorrowIndexKey::Book, &book),
+        Err(error) => panic_with_error!(env, error),
+    }
+}
+
+fn to_borrow_index(book: &BookIndex) -> BorrowIndex {
+    BorrowIndex {
+        rate_weighted_principal: book.rate_weighted_principal,
+        pending_interest: book.accrued / (BPS_DENOMINATOR * SECONDS_PER_YEAR as i128),
+        updated_ts: book.updated_ts,
+    }
+}
+
+/// The book index as of now, without checkpointing it.
+pub fn current(env: &Env) -> Result<BorrowIndex, CreditError> {
+    Ok(to_borrow_index(&book_now(env)?))
+}
+
+/// Checkpoint the book index at now and return it.
+pub fn bump(env: &Env) -> Result<BorrowIndex, CreditError> {
+    let book = book_now(env)?;
+    env.storage().instance().set(&BorrowIndexKey::Book, &book);
+    Ok(to_borrow_index(&book))
+}
@@ -0,0 +1,70 @@
+//! Protocol-wide bounds on the terms a line can be opened or repriced with, guarding against a
+//! faulty risk-engine run (e.g. a 0 bps rate or an absurd limit). They add to the fixed range
+//! checks (rate at most 10000 bps, score at most 100) and the rate floor.
+
+use soroban_sdk::{contracttype, Env};
+
+use crate::types::LineBounds;
+use crate::CreditError;
+
+/// Storage keys for term bounds.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BoundsKey {
+    LineBounds,
+}
+
+pub fn get(env: &Env) -> LineBounds {
+    env.storage()
+        .instance()
+        .get(&BoundsKey::LineBounds)
+        .unwrap_or_default()
+}
+
+pub fn set(env: &Env, bounds: &LineBounds) {
+    env.storage().instance().set(&BoundsKey::LineBounds, bounds);
+}
+
+fn within<T: PartialOrd>(value: T, min: Option<T>, max: Option<T>) -> bool {
+    min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+}
+
+/// Check a line's limit and rate against the bounds.
+///
+/// # Errors
+/// * `InvalidAmount` if `credit_limit` is out of bounds
+/// * `InterestRateOutOfRange` if `interest_rate_bps` is out of bounds
+pub fn check_terms(
+    env: &Env,
+    credit_limit: i128,
+    interest_rate_bps: u32,
+) -> Result<(), CreditError> {
+    let bounds = get(env);
+    if !within(
+        credit_limit,
+        bounds.min_credit_limit,
+        bounds.max_credit_limit,
+    ) {
+        return Err(CreditError::InvalidAmount);
+    }
+    if !within(
+        interest_rate_bps,
+        bounds.min_interest_rate_bps,
+        bounds.max_interest_rate_bps,
+    ) {
+        return Err(CreditError::InterestRateOutOfRange);
+    }
+    Ok(())
+}
+
+/// Check a risk score against the bounds.
+///
+/// # Errors
+/// * `InvalidRiskScore` if `risk_score` is out of bounds
+pub fn check_score(env: &Env, risk_score: u32) -> Result<(), CreditError> {
+    let bounds = get(env);
+    if !within(risk_score, bounds.min_risk_score, bounds.max_risk_score) {
+        return Err(CreditError::InvalidRiskScore);
+    }
+    Ok(())
+}
@@ -0,0 +1,154 @@
+//! Default-rate circuit breaker. While configured, every transition into `Defaulted` is counted
+//! in a per-day bucket; when the defaults of the trailing window exceed the configured share of
+//! the open lines, the breaker trips and blocks new openings and draws until the admin resets it.
+
+use soroban_sdk::{contracttype, Env, Map};
+
+use crate::events::{self, CircuitBreakerTrippedEvent};
+use crate::interest::BPS_DENOMINATOR;
+use crate::keeper::DAY_SECS;
+use crate::types::{
+    CircuitBreakerConfig, CircuitBreakerStatus, CreditLineData, CreditStatus, ProtocolStats,
+};
+use crate::CreditError;
+
+/// Longest accepted window, in days.
+pub const MAX_WINDOW_DAYS: u32 = 90;
+
+/// Storage keys for the circuit breaker.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BreakerKey {
+    BreakerConfig,
+    /// Defaults per day index (`timestamp / 86400`) within the window.
+    RecentDefaults,
+    /// Timestamp at which the breaker tripped.
+    BreakerTrippedAt,
+}
+
+pub fn config(env: &Env) -> Option<CircuitBreakerConfig> {
+    env.storage().instance().get(&BreakerKey::BreakerConfig)
+}
+
+/// Store the settings, or disable the breaker with `None`, which also clears its window and
+/// any trip.
+///
+/// # Errors
+/// * `InvalidParameter` if the rate is 0 or above 10_000 bps, or the window is 0 or longer
+///   than `MAX_WINDOW_DAYS`
+pub fn set_config(env: &Env, config: Option<&CircuitBreakerConfig>) -> Result<(), CreditError> {
+    let Some(config) = config else {
+        env.storage().instance().remove(&BreakerKey::BreakerConfig);
+        reset(env);
+        return Ok(());
+    };
+    if config.max_default_rate_bps == 0
+        || config.max_default_rate_bps > BPS_DENOMINATOR as u32
+        || config.window_days == 0
+        || config.window_days > MAX_WINDOW_DAYS
+    {
+        return Err(CreditError::InvalidParameter);
+    }
+    env.storage()
+        .instance()
+        .set(&BreakerKey::BreakerConfig, config);
+    Ok(())
+}
+
+pub fn tripped_at(env: &Env) -> Option<u64> {
+    env.storage().instance().get(&BreakerKey::BreakerTrippedAt)
+}
+
+/// Fail with `CircuitBreakerTripped` while the breaker is tripped.
+pub fn require_closed(env: &Env) -> Result<(), CreditError> {
+    if tripped_at(env).is_some() {
+        return Err(CreditError::CircuitBreakerTripped);
+    }
+    Ok(())
+}
+
+/// Clear the trip and start a fresh window. Returns when the breaker had tripped.
+pub fn reset(env: &Env) -> Option<u64> {
+    let tripped_at = tripped_at(env);
+    env.storage()
+        .instance()
+        .remove(&BreakerKey::BreakerTrippedAt);
+    env.storage().instance().remove(&BreakerKey::RecentDefaults);
+    tripped_at
+}
+
+/// Per-day default counts still inside the window, older days dropped.
+fn defaults(env: &Env, config: &CircuitBreakerConfig) -> Map<u64, u32> {
+    let today = env.ledger().timestamp() / DAY_SECS;
+    let mut defaults: Map<u64, u32> = env
+        .storage()
+        .instance()
+        .get(&BreakerKey::RecentDefaults)
+        .unwrap_or(Map::new(env));
+    for day in defaults.keys().iter() {
+        if day + (config.window_days as u64) <= today {
+            defaults.remove(day);
+        }
+    }
+    defaults
+}
+
+fn default_rate_bps(defaults_in_window: u32, stats: &ProtocolStats) -> u32 {
+    let open_lines = stats.active_count + stats.suspended_count + stats.defaulted_count;
+    if open_lines == 0 {
+        return 0;
+    }
+    (defaults_in_window as u64 * BPS_DENOMINATOR as u64 / open_lines as u64) as u32
+}
+
+pub fn status(env: &Env, stats: &ProtocolStats) -> CircuitBreakerStatus {
+    let defaults_in_window = config(env)
+        .map(|config| defaults(env, &config).values().iter().sum())
+        .unwrap_or(0);
+    CircuitBreakerStatus {
+        tripped_at: tripped_at(env),
+        defaults_in_window,
+        default_rate_bps: default_rate_bps(defaults_in_window, stats),
+    }
+}
+
+/// Count a line's transition from `before` into `Defaulted`, with `stats` already updated for
+/// it, and trip the breaker if the rolling default rate now exceeds the threshold.
+pub fn record_line_change(
+    env: &Env,
+    before: Option<&CreditLineData>,
+    after: &CreditLineData,
+    stats: &ProtocolStats,
+) {
+    if after.status != CreditStatus::Defaulted
+        || before.map(|b| b.status) == Some(CreditStatus::Defaulted)
+    {
+        return;
+    }
+    let Some(config) = config(env) else {
+        return;
+    };
+    let today = env.ledger().timestamp() / DAY_SECS;
+    let mut defaults = defaults(env, &config);
+    defaults.set(today, defaults.get(today).unwrap_or(0) + 1);
+    env.storage()
+        .instance()
+        .set(&BreakerKey::RecentDefaults, &defaults);
+
+    let defaults_in_window: u32 = defaults.values().iter().sum();
+    let default_rate_bps = default_rate_bps(defaults_in_window, stats);
+    if tripped_at(env).is_some() || default_rate_bps <= config.max_default_rate_bps {
+        return;
+    }
+    env.storage()
+        .instance()
+        .set(&BreakerKey::BreakerTrippedAt, &env.ledger().timestamp());
+    events::publish_circuit_breaker_tripped(
+        env,
+        CircuitBreakerTrippedEvent {
+            default_rate_bps,
+            defaults_in_window,
+            max_default_rate_bps: config.max_default_rate_bps,
+        },
+    );
+}
@@ -0,0 +1,73 @@
+//! Collateral-linked limit unlock for hybrid secured/unsecured lines.
+//!
+//! A line with a `LimitUnlock` has an unsecured `base_limit`; every unit of collateral the
+//! borrower posts unlocks `multiplier_bps / 10000` units of additional limit, up to
+//! `max_limit`. The effective `credit_limit` is recalculated whenever collateral or the base
+//! limit changes.
+
+use soroban_sdk::{Address, Env};
+
+use crate::interest::BPS_DENOMINATOR;
+use crate::types::{CreditLineData, LimitUnlock};
+use crate::{ttl, DataKey};
+
+pub fn collateral_of(env: &Env, borrower: &Address, asset: Option<&Address>) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&ttl::entry_key(
+            env,
+            DataKey::Collateral(borrower.clone()),
+            asset,
+        ))
+        .unwrap_or(0)
+}
+
+pub fn set_collateral(env: &Env, borrower: &Address, asset: Option<&Address>, amount: i128) {
+    let key = ttl::entry_key(env, DataKey::Collateral(borrower.clone()), asset);
+    if amount == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &amount);
+    }
+}
+
+pub fn get_unlock(env: &Env, borrower: &Address, asset: Option<&Address>) -> Option<LimitUnlock> {
+    env.storage().persistent().get(&ttl::entry_key(
+        env,
+        DataKey::LimitUnlock(borrower.clone()),
+        asset,
+    ))
+}
+
+pub fn set_unlock(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+    unlock: Option<LimitUnlock>,
+) {
+    let key = ttl::entry_key(env, DataKey::LimitUnlock(borrower.clone()), asset);
+    match unlock {
+        Some(unlock) => env.storage().persistent().set(&key, &unlock),
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+/// Effective limit for `unlock` with `collateral` posted: the base limit plus the unlocked
+/// amount, capped at `max_limit` (never below the base limit).
+pub fn unlocked_limit(unlock: &LimitUnlock, collateral: i128) -> i128 {
+    let unlocked = collateral * unlock.multiplier_bps as i128 / BPS_DENOMINATOR;
+    unlock
+        .base_limit
+        .saturating_add(unlocked)
+        .min(unlock.max_limit)
+        .max(unlock.base_limit)
+}
+
+/// Recalculate `line.credit_limit` from its unlock terms, if it has any. Caller is
+/// responsible for persisting the line.
+pub fn refresh_limit(env: &Env, line: &mut CreditLineData) {
+    let asset = line.asset.as_ref();
+    if let Some(unlock) = get_unlock(env, &line.borrower, asset) {
+        line.credit_limit = unlocked_limit(&unlock, collateral_of(env, &line.borrower, asset));
+    }
+}
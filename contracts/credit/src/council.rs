@@ -0,0 +1,80 @@
+//! M-of-N admin council. Once configured, sensitive admin actions (`TimelockAction`) are
+//! proposed by a signer, approved by other signers, and enter the timelock queue only after
+//! `threshold` signers have approved.
+
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::types::{AdminCouncil, AdminProposal};
+use crate::{CreditError, DataKey};
+
+/// Maximum number of council signers.
+pub const MAX_SIGNERS: u32 = 20;
+
+pub fn get_council(env: &Env) -> Option<AdminCouncil> {
+    env.storage().instance().get(&DataKey::AdminCouncil)
+}
+
+/// Validate and store the council.
+///
+/// # Errors
+/// * If `signers` is empty, too long or has duplicates, or `threshold` is 0 or above the
+///   number of signers
+pub fn set_council(env: &Env, signers: Vec<Address>, threshold: u32) -> Result<(), CreditError> {
+    if signers.is_empty() || signers.len() > MAX_SIGNERS {
+        return Err(CreditError::InvalidParameter);
+    }
+    for (i, signer) in signers.iter().enumerate() {
+        if signers.first_index_of(&signer) != Some(i as u32) {
+            return Err(CreditError::InvalidParameter);
+        }
+    }
+    if threshold == 0 || threshold > signers.len() {
+        return Err(CreditError::InvalidParameter);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::AdminCouncil, &AdminCouncil { signers, threshold });
+    Ok(())
+}
+
+/// Require `signer` to be on the council and to have authorized the call.
+pub fn require_signer(env: &Env, signer: &Address) -> Result<AdminCouncil, CreditError> {
+    let council = get_council(env).ok_or(CreditError::NotConfigured)?;
+    if !council.signers.contains(signer) {
+        return Err(CreditError::Unauthorized);
+    }
+    signer.require_auth();
+    Ok(council)
+}
+
+pub fn get_proposal(env: &Env, id: u32) -> Option<AdminProposal> {
+    env.storage().persistent().get(&DataKey::AdminProposal(id))
+}
+
+pub fn set_proposal(env: &Env, id: u32, proposal: &AdminProposal) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::AdminProposal(id), proposal);
+}
+
+/// Id for a new proposal.
+pub fn next_id(env: &Env) -> u32 {
+    let id: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::ProposalNextId)
+        .unwrap_or(0);
+    env.storage()
+        .instance()
+        .set(&DataKey::ProposalNextId, &(id + 1));
+    id
+}
+
+/// Number of a proposal's approvals from current council signers.
+pub fn approval_count(council: &AdminCouncil, proposal: &AdminProposal) -> u32 {
+    proposal
+        .approvals
+        .iter()
+        .filter(|a| council.signers.contains(a))
+        .count() as u32
+}
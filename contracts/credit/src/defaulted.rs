@@ -0,0 +1,133 @@
+//! Servicing rules for `Defaulted` lines. A defaulted line never accepts draws; whether it
+//! still accepts ordinary repayments from the borrower is a product setting, on by default,
+//! since blocking a defaulted borrower who wants to pay only deepens the loss.
+//!
+//! A borrower may dispute a default within the dispute window. While the dispute is pending,
+//! collection actions on the line (write-offs, write-downs, recoveries and restructuring) are
+//! paused until an arbiter upholds the default or reverses it.
+
+use soroban_sdk::{contracttype, Address, Env, IntoVal, Val};
+
+use crate::types::DefaultDispute;
+use crate::{ttl, CreditError};
+
+/// Storage keys for defaulted-line settings.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DefaultedKey {
+    /// Whether `repay_credit` accepts payments on defaulted lines.
+    AcceptRepayments,
+    /// Seconds after a default during which the borrower may dispute it.
+    DisputeWindow,
+    /// When the line was last defaulted.
+    DefaultedAt(Address),
+    /// The borrower's dispute of the line's current default.
+    Dispute(Address),
+}
+
+pub fn accepts_repayments(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DefaultedKey::AcceptRepayments)
+        .unwrap_or(true)
+}
+
+pub fn set_accepts_repayments(env: &Env, enabled: bool) {
+    env.storage()
+        .instance()
+        .set(&DefaultedKey::AcceptRepayments, &enabled);
+}
+
+pub fn dispute_window(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DefaultedKey::DisputeWindow)
+        .unwrap_or(0)
+}
+
+pub fn set_dispute_window(env: &Env, secs: u64) {
+    env.storage()
+        .instance()
+        .set(&DefaultedKey::DisputeWindow, &secs);
+}
+
+fn set_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &Val, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, ttl::LINE_BUMP_THRESHOLD, ttl::LINE_BUMP_AMOUNT);
+}
+
+fn defaulted_at_key(env: &Env, borrower: &Address, asset: Option<&Address>) -> Val {
+    ttl::entry_key(env, DefaultedKey::DefaultedAt(borrower.clone()), asset)
+}
+
+fn dispute_key(env: &Env, borrower: &Address, asset: Option<&Address>) -> Val {
+    ttl::entry_key(env, DefaultedKey::Dispute(borrower.clone()), asset)
+}
+
+/// Record a new default on the borrower's line in `asset`, dropping any dispute of an earlier
+/// one.
+pub fn record_default(env: &Env, borrower: &Address, asset: Option<&Address>) {
+    set_persistent(
+        env,
+        &defaulted_at_key(env, borrower, asset),
+        &env.ledger().timestamp(),
+    );
+    env.storage()
+        .persistent()
+        .remove(&dispute_key(env, borrower, asset));
+}
+
+pub fn dispute(env: &Env, borrower: &Address, asset: Option<&Address>) -> Option<DefaultDispute> {
+    env.storage()
+        .persistent()
+        .get(&dispute_key(env, borrower, asset))
+}
+
+pub fn set_dispute(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+    dispute: &DefaultDispute,
+) {
+    set_persistent(env, &dispute_key(env, borrower, asset), dispute);
+}
+
+/// Whether the borrower's default may still be disputed: the window is open and the default
+/// has not been disputed yet.
+///
+/// # Errors
+/// * `LineDisputed` if the default has already been disputed
+/// * `DisputeWindowClosed` if disputes are disabled or the window has passed
+pub fn check_can_dispute(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+) -> Result<(), CreditError> {
+    if dispute(env, borrower, asset).is_some() {
+        return Err(CreditError::LineDisputed);
+    }
+    let defaulted_at: u64 = env
+        .storage()
+        .persistent()
+        .get(&defaulted_at_key(env, borrower, asset))
+        .unwrap_or(0);
+    let window = dispute_window(env);
+    if window == 0 || env.ledger().timestamp() > defaulted_at.saturating_add(window) {
+        return Err(CreditError::DisputeWindowClosed);
+    }
+    Ok(())
+}
+
+/// Fail with `LineDisputed` while a dispute of the line's default is pending.
+pub fn require_undisputed(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+) -> Result<(), CreditError> {
+    if dispute(env, borrower, asset).is_some_and(|d| d.upheld.is_none()) {
+        return Err(CreditError::LineDisputed);
+    }
+    Ok(())
+}
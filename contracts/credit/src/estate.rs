@@ -0,0 +1,124 @@
+//! Administrative transfer of a line to an estate or legal representative after the borrower's
+//! death or incapacity. Transfers only run as a queued `TimelockAction::TransferToEstate`, which
+//! always waits at least `MIN_ESTATE_DELAY` regardless of the configured timelock delay.
+
+use soroban_sdk::{Address, Env, IntoVal, Val, Vec};
+
+use crate::ttl::LineKey;
+use crate::types::{CreditLineData, CreditStatus};
+use crate::{positions, ttl, CreditError, DataKey};
+
+/// Minimum time (7 days) between queueing an estate transfer and executing it.
+pub const MIN_ESTATE_DELAY: u64 = 7 * 24 * 60 * 60;
+
+/// Move a persistent entry to a new key, if it exists.
+fn move_entry<K: IntoVal<Env, Val>>(env: &Env, from: K, to: K) {
+    let storage = env.storage().persistent();
+    if let Some(value) = storage.get::<K, Val>(&from) {
+        storage.set(&to, &value);
+        storage.remove(&from);
+    }
+}
+
+/// Re-key the borrower's lines, in the liquidity token and in every other asset, and their
+/// per-line entries (see `ttl::line_entry_keys`) to `representative`, leaving their status
+/// unchanged. Entries tied to the person rather than the line (obligor freeze, borrower hook,
+/// delegations, voucher signer, behavior score and history) stay with the old address.
+/// Returns the moved lines, the line in the liquidity token first.
+///
+/// # Errors
+/// * If the borrower has no line or the line is closed
+/// * If `representative` is the borrower or already has a line in one of the assets
+pub fn transfer(
+    env: &Env,
+    borrower: &Address,
+    representative: &Address,
+) -> Result<Vec<CreditLineData>, CreditError> {
+    let line = ttl::load_line(env, borrower, None).ok_or(CreditError::CreditLineNotFound)?;
+    if line.status == CreditStatus::Closed {
+        return Err(CreditError::CreditLineClosed);
+    }
+    if representative == borrower {
+        return Err(CreditError::InvalidParameter);
+    }
+    let assets = ttl::line_assets(env, borrower);
+    let taken = |asset: Option<&Address>| {
+        env.storage()
+            .persistent()
+            .has(&ttl::line_key(env, representative, asset))
+    };
+    if taken(None) || assets.iter().any(|asset| taken(Some(&asset))) {
+        return Err(CreditError::CreditLineAlreadyExists);
+    }
+
+    let (from, to) = (borrower.clone(), representative.clone());
+    let mut lines = Vec::new(env);
+    lines.push_back(move_line(env, &from, &to, None, line));
+    for asset in assets.iter() {
+        if let Some(line) = ttl::read_line(env, &from, Some(&asset)) {
+            lines.push_back(move_line(env, &from, &to, Some(&asset), line));
+        }
+    }
+    move_entry(
+        env,
+        LineKey::Assets(from.clone()),
+        LineKey::Assets(to.clone()),
+    );
+    move_entry(
+        env,
+        DataKey::RiskScoreRecord(from.clone()),
+        DataKey::RiskScoreRecord(to.clone()),
+    );
+
+    let slot: Option<u32> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::BorrowerSlot(from.clone()));
+    if let Some(slot) = slot {
+        move_entry(
+            env,
+            DataKey::BorrowerSlot(from.clone()),
+            DataKey::BorrowerSlot(to.clone()),
+        );
+        env.storage()
+            .persistent()
+            .set(&DataKey::BorrowerAt(slot), &to);
+    }
+    if let Some(position_id) = positions::position_of(env, &from) {
+        positions::move_to(env, position_id, &from, &to);
+    }
+    ttl::bump_line_entries(env, &to, None);
+    for asset in assets.iter() {
+        ttl::bump_line_entries(env, &to, Some(&asset));
+    }
+    Ok(lines)
+}
+
+/// Move the borrower's `line` in `asset` and its per-line entries from `from` to `to`. The line
+/// moves as-is, so protocol aggregates and risk-weighted balances are unchanged.
+fn move_line(
+    env: &Env,
+    from: &Address,
+    to: &Address,
+    asset: Option<&Address>,
+    mut line: CreditLineData,
+) -> CreditLineData {
+    let keys = ttl::line_entry_keys(env, from)
+        .into_iter()
+        .zip(ttl::line_entry_keys(env, to));
+    for (from_key, to_key) in keys {
+        move_entry(
+            env,
+            ttl::entry_key(env, from_key, asset),
+            ttl::entry_key(env, to_key, asset),
+        );
+    }
+    line.borrower = to.clone();
+    env.storage()
+        .persistent()
+        .remove(&ttl::line_key(env, from, asset));
+    env.storage()
+        .persistent()
+        .set(&ttl::line_key(env, to, asset), &line);
+    line
+}
@@ -1,9 +1,124 @@
 //! Event types and topic constants for the Credit contract.
 //! Stable event schemas for indexing and analytics.
+//!
+//! Every event is published under the topics `("credit", action, EVENT_SCHEMA_VERSION)` with a
+//! struct payload, wrapped in an `EventEnvelope` carrying the contract's event sequence number,
+//! the ledger timestamp and the id of the line the event concerns.
 
-use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol};
+use soroban_sdk::{
+    contracttype, symbol_short, Address, BytesN, ConversionError, Env, IntoVal, Map, Symbol,
+    TryFromVal, Val, Vec,
+};
 
-use crate::types::CreditStatus;
+use crate::types::{
+    BorrowIndex, CreditStatus, DisputeResolution, LineSummary, OperationFreezes, ParamChange,
+    PortfolioSnapshot, ProtocolStats, RiskTier, TimelockAction,
+};
+use crate::{positions, DataKey};
+
+/// Version of the event schema, published as the third topic of every event. Bumped whenever
+/// a payload changes shape, so indexers can decode old and new events side by side.
+pub const EVENT_SCHEMA_VERSION: u32 = 6;
+
+/// Topics for a credit event: `("credit", action, EVENT_SCHEMA_VERSION)`.
+pub fn topics(action: Symbol) -> (Symbol, Symbol, u32) {
+    (symbol_short!("credit"), action, EVENT_SCHEMA_VERSION)
+}
+
+/// Storage keys for event bookkeeping.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EventKey {
+    /// Sequence number of the last published event.
+    EventSeq,
+}
+
+/// Data of every published event: the payload plus what an indexer needs to order events and
+/// detect gaps independently of the RPC provider. Encoded as a map keyed by the field names, as
+/// a `#[contracttype]` struct would be (which cannot hold an arbitrary payload `Val`).
+#[derive(Clone, Debug)]
+pub struct EventEnvelope {
+    /// Per-contract sequence number, increasing by one with every event from 1.
+    pub seq: u64,
+    /// Ledger timestamp the event was published at.
+    pub timestamp: u64,
+    /// Position id of the line the event concerns, if its payload names a borrower with a line.
+    pub line_id: Option<u64>,
+    /// The event's struct payload.
+    pub payload: Val,
+}
+
+impl TryFromVal<Env, EventEnvelope> for Val {
+    type Error = ConversionError;
+
+    fn try_from_val(env: &Env, envelope: &EventEnvelope) -> Result<Self, Self::Error> {
+        let mut fields = Map::<Symbol, Val>::new(env);
+        fields.set(symbol_short!("seq"), envelope.seq.into_val(env));
+        fields.set(symbol_short!("timestamp"), envelope.timestamp.into_val(env));
+        fields.set(symbol_short!("line_id"), envelope.line_id.into_val(env));
+        fields.set(symbol_short!("payload"), envelope.payload);
+        Ok(fields.into_val(env))
+    }
+}
+
+impl TryFromVal<Env, Val> for EventEnvelope {
+    type Error = ConversionError;
+
+    fn try_from_val(env: &Env, val: &Val) -> Result<Self, Self::Error> {
+        let fields = Map::<Symbol, Val>::try_from_val(env, val)?;
+        let field = |name: Symbol| fields.get(name).ok_or(ConversionError);
+        Ok(EventEnvelope {
+            seq: u64::try_from_val(env, &field(symbol_short!("seq"))?)?,
+            timestamp: u64::try_from_val(env, &field(symbol_short!("timestamp"))?)?,
+            line_id: Option::<u64>::try_from_val(env, &field(symbol_short!("line_id"))?)
+                .map_err(|_| ConversionError)?,
+            payload: field(symbol_short!("payload"))?,
+        })
+    }
+}
+
+/// Publish `event` under the topics for `action`, wrapped in an `EventEnvelope`.
+fn publish<T: IntoVal<Env, Val>>(env: &Env, action: Symbol, event: T) {
+    let seq = env
+        .storage()
+        .instance()
+        .get::<_, u64>(&EventKey::EventSeq)
+        .unwrap_or(0)
+        + 1;
+    env.storage().instance().set(&EventKey::EventSeq, &seq);
+    let payload = event.into_val(env);
+    let line_id = Map::<Symbol, Val>::try_from_val(env, &payload)
+        .ok()
+        .and_then(|fields| fields.get(symbol_short!("borrower")))
+        .and_then(|borrower| Address::try_from_val(env, &borrower).ok())
+        .and_then(|borrower| positions::position_of(env, &borrower));
+    env.events().publish(
+        topics(action),
+        EventEnvelope {
+            seq,
+            timestamp: env.ledger().timestamp(),
+            line_id,
+            payload,
+        },
+    );
+}
+
+/// Sequence number of the last published event, 0 before the first.
+pub fn last_seq(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&EventKey::EventSeq)
+        .unwrap_or(0)
+}
+
+/// Event emitted when an asset is added to (`asset_add`) or removed from (`asset_rm`) the
+/// supported asset allowlist.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetEvent {
+    pub asset: Address,
+    pub supported: bool,
+}
 
 /// Event emitted when a credit line lifecycle event occurs (opened, suspend, closed, default).
 #[contracttype]
@@ -19,16 +134,29 @@ pub struct CreditLineEvent {
 
 /// Event emitted when a borrower repays credit.
 /// Used for indexing and analytics (borrower, amount, new utilized amount, timestamp).
+/// `overpaid` is the part of `amount` beyond the outstanding balance and `deposited` the part of
+/// that credited to the borrower's repayment deposit (both added in schema version 3). `payer`
+/// is the address the payment came from: the borrower, a third party paying on its behalf, or
+/// this contract for payments out of the borrower's deposit (added in schema version 6).
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct RepaymentEvent {
+pub struct CreditRepayEvent {
     pub borrower: Address,
     pub amount: i128,
     pub new_utilized_amount: i128,
     pub timestamp: u64,
+    pub overpaid: i128,
+    pub deposited: i128,
+    /// How the applied amount split across fees, interest and principal under the repayment
+    /// waterfall.
+    pub fees_paid: i128,
+    pub interest_paid: i128,
+    pub principal_paid: i128,
+    pub payer: Address,
 }
 
-/// Event emitted when admin updates risk parameters for a credit line.
+/// Event emitted when a line's risk parameters change. `actor` is the admin, the risk oracle
+/// or, for a voluntary limit decrease, the borrower.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct RiskParametersUpdatedEvent {
@@ -36,37 +164,1119 @@ pub struct RiskParametersUpdatedEvent {
     pub credit_limit: i128,
     pub interest_rate_bps: u32,
     pub risk_score: u32,
+    pub actor: Address,
 }
 
 /// Event emitted when a borrower draws credit.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct DrawnEvent {
+pub struct CreditDrawEvent {
     pub borrower: Address,
     pub amount: i128,
+    /// Origination fee charged on the draw, withheld or capitalized.
+    pub fee: i128,
     pub new_utilized_amount: i128,
     pub timestamp: u64,
 }
 
-/// Publish a credit line lifecycle event.
-pub fn publish_credit_line_event(env: &Env, topic: (Symbol, Symbol), event: CreditLineEvent) {
-    env.events().publish(topic, event);
+/// Event emitted when a line-level or obligor-level freeze is applied or lifted.
+/// The topic distinguishes the scope (`frozen`/`unfrozen` vs `obl_frz`/`obl_unfrz`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FreezeEvent {
+    pub borrower: Address,
+    pub frozen: bool,
 }
 
-/// Publish a repayment event.
-pub fn publish_repayment_event(env: &Env, event: RepaymentEvent) {
-    env.events()
-        .publish((symbol_short!("credit"), symbol_short!("repay")), event);
+/// High-frequency event emitted when interest is accrued on a line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterestAccruedEvent {
+    pub borrower: Address,
+    pub interest: i128,
+    pub accrued_interest: i128,
+    pub timestamp: u64,
 }
 
-/// Publish a drawn event.
-pub fn publish_drawn_event(env: &Env, event: DrawnEvent) {
-    env.events()
-        .publish((symbol_short!("credit"), symbol_short!("drawn")), event);
+/// Event emitted when accrued interest is capitalized into principal.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CapitalizationEvent {
+    pub borrower: Address,
+    pub amount: i128,
+    pub new_utilized_amount: i128,
+    pub timestamp: u64,
 }
 
-/// Publish a risk parameters updated event.
-pub fn publish_risk_parameters_updated(env: &Env, event: RiskParametersUpdatedEvent) {
-    env.events()
-        .publish((symbol_short!("credit"), symbol_short!("risk_upd")), event);
+/// High-frequency event emitted when an incentive campaign waives accrued interest.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterestRebateEvent {
+    pub borrower: Address,
+    pub campaign_id: u32,
+    pub amount: i128,
+}
+
+/// Event emitted when an incentive campaign rebates origination fees to a borrower who paid off
+/// or closed the line before the end of its promotional window.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OriginationRebateEvent {
+    pub borrower: Address,
+    pub campaign_id: u32,
+    pub amount: i128,
+}
+
+/// Event emitted when a protocol fee is collected. `kind` is `orig` (origination fee on a draw),
+/// `opening` (opening fee on a new line), `late` (late fee on an overdue payment) or `reserve`
+/// (reserve factor on collected interest), all kept in the treasury, `insure` (interest share sent
+/// to the insurance fund) or `provision` (interest set aside in the loss provision).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeEvent {
+    pub borrower: Address,
+    pub kind: Symbol,
+    pub amount: i128,
+}
+
+/// Event emitted when a defaulted line is written off. `covered` is the part of the principal
+/// paid to the pool by the insurance fund.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WriteOffEvent {
+    pub borrower: Address,
+    pub principal: i128,
+    pub interest: i128,
+    pub covered: i128,
+}
+
+/// Event emitted for each borrower whose debt is forgiven. `total_forgiven` is everything
+/// forgiven to the borrower so far.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DebtForgivenEvent {
+    pub borrower: Address,
+    pub principal: i128,
+    pub interest: i128,
+    pub total_forgiven: i128,
+}
+
+/// Event emitted when the loss provision absorbs principal written off a line. `remaining` is
+/// the provision left afterwards.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProvisionReleasedEvent {
+    pub borrower: Address,
+    pub amount: i128,
+    pub remaining: i128,
+}
+
+/// Event emitted when a borrower adds to (`rdep_add`), withdraws from (`rdep_rm`) or repays from
+/// (`rdep_pay`) their deposit, or when a sweep applies it to the line (`auto_rep`). `balance` is
+/// the deposit left afterwards.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RepayDepositEvent {
+    pub borrower: Address,
+    pub amount: i128,
+    pub balance: i128,
+}
+
+/// Event emitted when a line is repaid in an alternate token: the borrower paid `amount` of
+/// `asset` to `counterparty` and the line was credited with `credited` of its own token.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AltRepaymentEvent {
+    pub borrower: Address,
+    pub asset: Address,
+    pub amount: i128,
+    pub credited: i128,
+    pub counterparty: Address,
+}
+
+/// Checkpoint emitted when an end-of-day close completes its pass over the open lines, with the
+/// protocol aggregates after it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DayClosedEvent {
+    pub day: u64,
+    pub open_lines: u32,
+    pub stats: ProtocolStats,
+}
+
+/// Event emitted when a portfolio snapshot is recorded.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SnapshotTakenEvent {
+    pub caller: Address,
+    pub snapshot: PortfolioSnapshot,
+}
+
+/// Event emitted when the default-rate circuit breaker trips.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircuitBreakerTrippedEvent {
+    pub default_rate_bps: u32,
+    pub defaults_in_window: u32,
+    pub max_default_rate_bps: u32,
+}
+
+/// Event emitted when the admin resets the circuit breaker; `tripped_at` is when it had
+/// tripped, if it had.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircuitBreakerResetEvent {
+    pub tripped_at: Option<u64>,
+}
+
+/// Event emitted when keeper upkeep finds a collateral-linked line's health factor below the
+/// margin-call threshold.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarginCallEvent {
+    pub borrower: Address,
+    pub health_factor_bps: u32,
+    pub threshold_bps: u32,
+    pub debt: i128,
+    pub collateral_value: i128,
+}
+
+/// Event emitted when a keeper pokes a line; `bounty` is what the caller was paid.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeeperPokeEvent {
+    pub caller: Address,
+    pub borrower: Address,
+    pub status: CreditStatus,
+    pub bounty: i128,
+}
+
+/// Event emitted when a late fee is charged for the payment due at `due_ts`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LateFeeEvent {
+    pub borrower: Address,
+    pub amount: i128,
+    pub due_ts: u64,
+    pub new_utilized_amount: i128,
+}
+
+/// Event emitted when the risk manager role is granted or revoked.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskManagerEvent {
+    pub manager: Address,
+    pub enabled: bool,
+}
+
+/// Event emitted when the risk engine is set or removed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskEngineEvent {
+    pub engine: Option<Address>,
+}
+
+/// Event emitted when a line's accrued interest is corrected. `reason_hash` references the
+/// off-chain incident report justifying the correction.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccrualCorrectedEvent {
+    pub borrower: Address,
+    pub delta: i128,
+    pub accrued_interest: i128,
+    pub reason_hash: BytesN<32>,
+    pub approvers: Vec<Address>,
+}
+
+/// Event emitted when a borrower approves (`max_amount` > 0) or revokes (`max_amount` = 0) a
+/// delegate's drawing rights.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DelegationEvent {
+    pub borrower: Address,
+    pub delegate: Address,
+    pub max_amount: i128,
+}
+
+/// Event emitted when an operator waives accrued interest or penalties on a line.
+/// `remaining_budget` is what the operator may still waive in the current period.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeWaivedEvent {
+    pub operator: Address,
+    pub borrower: Address,
+    pub amount: i128,
+    pub accrued_interest: i128,
+    pub reason_hash: BytesN<32>,
+    pub remaining_budget: i128,
+}
+
+/// Event emitted when a risk score update below the configured minimum suspends a line,
+/// alongside the standard `suspend` event.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AutoSuspendEvent {
+    pub borrower: Address,
+    pub risk_score: u32,
+    pub min_risk_score: u32,
+}
+
+/// Event emitted when keeper upkeep defaults a line whose payment due has stayed unpaid past the
+/// configured `default_after`, alongside the standard `default` event.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AutoDefaultEvent {
+    pub borrower: Address,
+    pub seconds_overdue: u64,
+    pub default_after: u64,
+}
+
+/// Event emitted when keeper upkeep marks a term facility matured: `balance` is posted as due
+/// on `maturity_ts`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineMaturedEvent {
+    pub borrower: Address,
+    pub maturity_ts: u64,
+    pub balance: i128,
+}
+
+/// Event emitted when a line is renewed: its maturity moves to `maturity_ts` and its rate to
+/// `interest_rate_bps`, with the balance carried over.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineRenewedEvent {
+    pub borrower: Address,
+    pub previous_maturity_ts: Option<u64>,
+    pub maturity_ts: u64,
+    pub previous_rate_bps: u32,
+    pub interest_rate_bps: u32,
+    pub actor: Address,
+}
+
+/// Event emitted when a borrower disputes their line's default.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DefaultDisputedEvent {
+    pub borrower: Address,
+    pub reason_hash: BytesN<32>,
+}
+
+/// Event emitted when an arbiter resolves a default dispute: `upheld` keeps the default,
+/// otherwise the line is moved to `Suspended`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DefaultDisputeResolvedEvent {
+    pub borrower: Address,
+    pub arbiter: Address,
+    pub upheld: bool,
+}
+
+/// Event emitted when a queued estate transfer moves a line to the borrower's representative.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EstateTransferEvent {
+    pub borrower: Address,
+    pub representative: Address,
+    pub evidence_hash: BytesN<32>,
+    pub utilized_amount: i128,
+}
+
+/// Event emitted when a credit position moves to another address.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PositionTransferredEvent {
+    pub position_id: u64,
+    pub from: Address,
+    pub to: Address,
+    pub utilized_amount: i128,
+}
+
+/// Event emitted when a share of a line's receivable is sold to `to`, by the lender side
+/// (`from` is `None`) or by an earlier assignee.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReceivableAssignedEvent {
+    pub borrower: Address,
+    pub from: Option<Address>,
+    pub to: Address,
+    pub share_bps: u32,
+    pub price: i128,
+}
+
+/// Event emitted when a repayment pays an assignee its share of the line's receivable.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReceivablePaymentEvent {
+    pub borrower: Address,
+    pub assignee: Address,
+    pub principal: i128,
+    pub interest: i128,
+}
+
+/// Event emitted when a flash draw is paid back. `fee` is the fee owed, which `receiver` may
+/// have overpaid.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlashDrawEvent {
+    pub caller: Address,
+    pub receiver: Address,
+    pub amount: i128,
+    pub fee: i128,
+}
+
+/// Event emitted when a borrower pays a merchant from their credit line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MerchantPaymentEvent {
+    pub borrower: Address,
+    pub merchant: Address,
+    pub amount: i128,
+}
+
+/// Event emitted when a merchant is allowed or revoked.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MerchantAllowedEvent {
+    pub merchant: Address,
+    pub allowed: bool,
+}
+
+/// Event emitted when an admin handover is proposed (`adm_prop`) and when the proposed admin
+/// accepts it (`adm_acc`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminTransferEvent {
+    pub admin: Address,
+    pub new_admin: Address,
+}
+
+/// Event emitted when `migrate` has moved every registered line to a new storage schema.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MigrationEvent {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+/// Event emitted when a deferred-interest promo starts (`promo_new`, `deferred_interest` is 0)
+/// and when it ends, with the deferred interest either waived (`promo_wv`) or charged to the
+/// line (`promo_chg`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeferredPromoEvent {
+    pub borrower: Address,
+    pub ends_at: u64,
+    pub deferred_interest: i128,
+}
+
+/// Event emitted when a delegate draws on a borrower's line, alongside the `drawn` event.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DelegateDrawnEvent {
+    pub borrower: Address,
+    pub delegate: Address,
+    pub amount: i128,
+    pub delegate_drawn: i128,
+}
+
+/// Event emitted when an operator appends a note hash to a line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineNoteEvent {
+    pub borrower: Address,
+    pub note_hash: BytesN<32>,
+    pub author: Address,
+}
+
+/// Event emitted when a line's off-chain metadata reference is set, at open or by the admin.
+/// `metadata` is `None` when the reference is removed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineMetadataEvent {
+    pub borrower: Address,
+    pub metadata: Option<BytesN<32>>,
+}
+
+/// Event emitted when a draw takes a line's utilization across one of its alert thresholds
+/// (`threshold_bps` of `credit_limit`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UtilizationAlertEvent {
+    pub borrower: Address,
+    pub threshold_bps: u32,
+    pub utilized_amount: i128,
+    pub credit_limit: i128,
+}
+
+/// Final event emitted when a line closes, with its lifetime aggregates and any balance still
+/// outstanding (admin force-close).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineSummaryEvent {
+    pub borrower: Address,
+    pub closed_at: u64,
+    pub outstanding_principal: i128,
+    pub outstanding_interest: i128,
+    pub summary: LineSummary,
+}
+
+/// Event emitted when a borrower posts or withdraws collateral. `credit_limit` is the line's
+/// limit after recalculation.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollateralEvent {
+    pub borrower: Address,
+    pub amount: i128,
+    pub collateral: i128,
+    pub credit_limit: i128,
+}
+
+/// Event emitted when a line is restructured into a term loan.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RestructuredEvent {
+    pub borrower: Address,
+    pub balance: i128,
+    pub installment_amount: i128,
+    pub installment_count: u32,
+    pub first_due_ts: u64,
+}
+
+/// Event emitted when an address is added to or removed from the internal KYC allowlist.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KycEvent {
+    pub account: Address,
+    pub allowed: bool,
+}
+
+/// Event emitted when an address is added to or removed from the blacklist. `actor` is the
+/// admin or compliance officer who made the change.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlacklistEvent {
+    pub account: Address,
+    pub blocked: bool,
+    pub actor: Address,
+}
+
+/// Event emitted when operation freezes are set on a line, or protocol-wide when `borrower` is
+/// `None`, by the admin or a compliance officer (`actor`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OperationFreezeEvent {
+    pub borrower: Option<Address>,
+    pub freezes: OperationFreezes,
+    pub actor: Address,
+}
+
+/// Event emitted when a risk tier is defined or changed (`terms` are the new terms) or removed
+/// (`terms` are the terms it had).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskTierEvent {
+    pub tier: Symbol,
+    pub terms: RiskTier,
+}
+
+/// Event emitted when the compliance role is granted or revoked.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ComplianceRoleEvent {
+    pub officer: Address,
+    pub enabled: bool,
+}
+
+/// Event emitted when an arbiter is approved, has its limits changed, or is removed (both
+/// limits are zero).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArbiterEvent {
+    pub arbiter: Address,
+    pub max_amount: i128,
+    pub max_actions_per_period: u32,
+}
+
+/// Event emitted when an arbiter resolves a case on a line. `period_actions` counts the
+/// arbiter's actions in the current period, including this one.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeResolvedEvent {
+    pub arbiter: Address,
+    pub borrower: Address,
+    pub resolution: DisputeResolution,
+    pub amount: i128,
+    pub case_hash: BytesN<32>,
+    pub period_actions: u32,
+}
+
+/// Event emitted when collections are received on a defaulted line. `balance_paid` went to
+/// the line's remaining balance and `recovered` to principal already written off;
+/// `total_recovered` and `written_off` are the line's running totals.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecoveryEvent {
+    pub borrower: Address,
+    pub payer: Address,
+    pub amount: i128,
+    pub balance_paid: i128,
+    pub recovered: i128,
+    pub total_recovered: i128,
+    pub written_off: i128,
+}
+
+/// Event emitted when a defaulted line is restructured into a term loan and returns to
+/// Active.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DefaultRestructuredEvent {
+    pub borrower: Address,
+    pub credit_limit: i128,
+    pub interest_rate_bps: u32,
+    pub balance: i128,
+    pub installment_amount: i128,
+    pub installment_count: u32,
+    pub first_due_ts: u64,
+}
+
+/// Event emitted when a limit increase is requested (`lim_req`), approved (`lim_appr`) or
+/// rejected (`lim_rej`). `current_limit` is the limit before any change.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LimitRequestEvent {
+    pub borrower: Address,
+    pub current_limit: i128,
+    pub requested_limit: i128,
+}
+
+/// Event emitted when a timelocked admin action is queued (`tl_queue`), cancelled
+/// (`tl_cancel`) or executed (`tl_exec`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimelockEvent {
+    pub id: u32,
+    pub action: TimelockAction,
+    pub eta: u64,
+}
+
+/// Event emitted when a council proposal is created (`prop_new`) or approved (`prop_appr`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProposalEvent {
+    pub id: u32,
+    pub action: TimelockAction,
+    pub signer: Address,
+    pub approvals: u32,
+}
+
+/// Event emitted when a temporary limit boost is granted. `credit_limit` is the line's
+/// underlying limit, which the boost does not change.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LimitBoostEvent {
+    pub borrower: Address,
+    pub extra: i128,
+    pub until: u64,
+    pub credit_limit: i128,
+}
+
+/// Event emitted when a draw is scheduled (`draw_sch`), cancelled (`sch_cxl`) or executed
+/// (`sch_exec`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduledDrawEvent {
+    pub id: u32,
+    pub borrower: Address,
+    pub amount: i128,
+    pub at: u64,
+}
+
+/// Event emitted when a draw mandate is created (`mnd_new`), cancelled (`mnd_cxl`) or an
+/// installment is executed (`mnd_exec`). `next_at` is the installment time after the event,
+/// or 0 once the mandate has ended.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DrawMandateEvent {
+    pub id: u32,
+    pub borrower: Address,
+    pub recipient: Address,
+    pub amount: i128,
+    pub next_at: u64,
+}
+
+/// Event emitted when a relayer redeems a draw voucher, before the resulting draw.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoucherRedeemedEvent {
+    pub borrower: Address,
+    pub recipient: Address,
+    pub amount: i128,
+    pub nonce: u64,
+}
+
+/// Event emitted when a large draw is queued (`draw_q`), cancelled (`draw_cxl`) or released
+/// (`draw_rel`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingDrawEvent {
+    pub id: u32,
+    pub borrower: Address,
+    pub amount: i128,
+    pub release_at: u64,
+}
+
+/// Event emitted when the admin withdraws treasury fees.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeWithdrawnEvent {
+    pub to: Address,
+    pub amount: i128,
+}
+
+/// Event emitted when a referrer is credited its share of a fee charged on a referred line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralFeeEvent {
+    pub referrer: Address,
+    pub borrower: Address,
+    pub amount: i128,
+}
+
+/// Event emitted when a referrer claims its referral fees.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReferralClaimedEvent {
+    pub referrer: Address,
+    pub amount: i128,
+}
+
+/// Event emitted when the governance contract is set or cleared.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GovernanceEvent {
+    pub governance: Option<Address>,
+}
+
+/// Event emitted when governance applies a parameter change.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParamChangedEvent {
+    pub governance: Address,
+    pub change: ParamChange,
+}
+
+/// Event emitted when an external hook invocation fails; the core operation still completes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HookFailedEvent {
+    pub hook: Address,
+    pub function: Symbol,
+}
+
+/// Event emitted when a lifecycle hook is registered (`hook_reg`) or unregistered (`hook_rm`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LifecycleHookEvent {
+    pub hook: Address,
+}
+
+/// Whether high-frequency events (accrual, rebates, alerts) are emitted. Defaults to true;
+/// lifecycle, draw and repayment events are always emitted.
+pub fn high_frequency_events_enabled(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::HighFrequencyEvents)
+        .unwrap_or(true)
+}
+
+/// Publish a credit line lifecycle event under its `event_type` action.
+pub fn publish_credit_line_event(env: &Env, event: CreditLineEvent) {
+    publish(env, event.event_type.clone(), event);
+}
+
+/// Publish an asset allowlist change under the given action topic.
+pub fn publish_asset_event(env: &Env, action: Symbol, event: AssetEvent) {
+    publish(env, action, event);
+}
+
+/// Publish a repayment event.
+pub fn publish_repayment_event(env: &Env, event: CreditRepayEvent) {
+    publish(env, symbol_short!("repay"), event);
+}
+
+/// Publish a drawn event.
+pub fn publish_drawn_event(env: &Env, event: CreditDrawEvent) {
+    publish(env, symbol_short!("drawn"), event);
+}
+
+/// Publish a risk parameters updated event.
+pub fn publish_risk_parameters_updated(env: &Env, event: RiskParametersUpdatedEvent) {
+    publish(env, symbol_short!("risk_upd"), event);
+}
+
+/// Publish a freeze event under the given action topic.
+pub fn publish_freeze_event(env: &Env, action: Symbol, event: FreezeEvent) {
+    publish(env, action, event);
+}
+
+/// Publish an interest accrual event, if high-frequency events are enabled.
+pub fn publish_interest_accrued(env: &Env, event: InterestAccruedEvent) {
+    if high_frequency_events_enabled(env) {
+        publish(env, symbol_short!("accrue"), event);
+    }
+}
+
+/// Publish an interest rebate event, if high-frequency events are enabled.
+pub fn publish_interest_rebate(env: &Env, event: InterestRebateEvent) {
+    if high_frequency_events_enabled(env) {
+        publish(env, symbol_short!("rebate"), event);
+    }
+}
+
+/// Publish an origination fee rebate event.
+pub fn publish_origination_rebate(env: &Env, event: OriginationRebateEvent) {
+    publish(env, symbol_short!("orig_rbt"), event);
+}
+
+/// Publish a utilization alert, if high-frequency events are enabled.
+pub fn publish_utilization_alert(env: &Env, event: UtilizationAlertEvent) {
+    if high_frequency_events_enabled(env) {
+        publish(env, symbol_short!("util_alrt"), event);
+    }
+}
+
+/// Publish a hook failure event.
+pub fn publish_hook_failed(env: &Env, event: HookFailedEvent) {
+    publish(env, symbol_short!("hook_fail"), event);
+}
+
+/// Publish a capitalization event.
+pub fn publish_capitalization(env: &Env, event: CapitalizationEvent) {
+    publish(env, symbol_short!("capital"), event);
+}
+
+/// Publish a protocol fee accrual event.
+pub fn publish_fee_event(env: &Env, event: FeeEvent) {
+    publish(env, symbol_short!("fee"), event);
+}
+
+/// Publish a treasury fee withdrawal event.
+pub fn publish_fee_withdrawn(env: &Env, event: FeeWithdrawnEvent) {
+    publish(env, symbol_short!("fee_wd"), event);
+}
+
+/// Publish a referral fee credit.
+pub fn publish_referral_fee(env: &Env, event: ReferralFeeEvent) {
+    publish(env, symbol_short!("ref_fee"), event);
+}
+
+/// Publish a referral fee claim.
+pub fn publish_referral_claimed(env: &Env, event: ReferralClaimedEvent) {
+    publish(env, symbol_short!("ref_claim"), event);
+}
+
+/// Publish a governance contract change.
+pub fn publish_governance_event(env: &Env, event: GovernanceEvent) {
+    publish(env, symbol_short!("gov"), event);
+}
+
+/// Publish a parameter change applied by governance.
+pub fn publish_param_changed(env: &Env, event: ParamChangedEvent) {
+    publish(env, symbol_short!("param_chg"), event);
+}
+
+/// Publish a write-off event.
+pub fn publish_write_off(env: &Env, event: WriteOffEvent) {
+    publish(env, symbol_short!("write_off"), event);
+}
+
+/// Publish a partial write-off (write-down) event.
+pub fn publish_write_down(env: &Env, event: WriteOffEvent) {
+    publish(env, symbol_short!("write_dn"), event);
+}
+
+/// Publish a debt forgiveness event.
+pub fn publish_debt_forgiven(env: &Env, event: DebtForgivenEvent) {
+    publish(env, symbol_short!("forgiven"), event);
+}
+
+/// Publish an accrual correction event.
+pub fn publish_accrual_corrected(env: &Env, event: AccrualCorrectedEvent) {
+    publish(env, symbol_short!("acc_fix"), event);
+}
+
+/// Publish a fee waiver event.
+pub fn publish_fee_waived(env: &Env, event: FeeWaivedEvent) {
+    publish(env, symbol_short!("waiver"), event);
+}
+
+/// Publish an automatic suspension event.
+pub fn publish_auto_suspend(env: &Env, event: AutoSuspendEvent) {
+    publish(env, symbol_short!("auto_susp"), event);
+}
+
+/// Publish a lifecycle hook registration event under `action` (`hook_reg` or `hook_rm`).
+pub fn publish_lifecycle_hook_event(env: &Env, action: Symbol, event: LifecycleHookEvent) {
+    publish(env, action, event);
+}
+
+/// Publish an automatic default event.
+pub fn publish_auto_default(env: &Env, event: AutoDefaultEvent) {
+    publish(env, symbol_short!("auto_dflt"), event);
+}
+
+/// Publish a line matured event.
+pub fn publish_line_matured(env: &Env, event: LineMaturedEvent) {
+    publish(env, symbol_short!("matured"), event);
+}
+
+/// Publish a default disputed event.
+pub fn publish_default_disputed(env: &Env, event: DefaultDisputedEvent) {
+    publish(env, symbol_short!("dflt_disp"), event);
+}
+
+/// Publish a default dispute resolved event.
+pub fn publish_default_dispute_resolved(env: &Env, event: DefaultDisputeResolvedEvent) {
+    publish(env, symbol_short!("dsp_res"), event);
+}
+
+/// Publish a line renewed event.
+pub fn publish_line_renewed(env: &Env, event: LineRenewedEvent) {
+    publish(env, symbol_short!("renewed"), event);
+}
+
+/// Publish an estate transfer event.
+pub fn publish_estate_transfer(env: &Env, event: EstateTransferEvent) {
+    publish(env, symbol_short!("estate"), event);
+}
+
+/// Publish a position transfer event.
+pub fn publish_position_transferred(env: &Env, event: PositionTransferredEvent) {
+    publish(env, symbol_short!("pos_xfer"), event);
+}
+
+/// Publish a receivable assignment event.
+pub fn publish_receivable_assigned(env: &Env, event: ReceivableAssignedEvent) {
+    publish(env, symbol_short!("rcv_asgn"), event);
+}
+
+/// Publish a receivable payment event.
+pub fn publish_receivable_payment(env: &Env, event: ReceivablePaymentEvent) {
+    publish(env, symbol_short!("rcv_pay"), event);
+}
+
+/// Publish a flash draw event.
+pub fn publish_flash_draw(env: &Env, event: FlashDrawEvent) {
+    publish(env, symbol_short!("flash"), event);
+}
+
+/// Publish a merchant payment event.
+pub fn publish_merchant_payment(env: &Env, event: MerchantPaymentEvent) {
+    publish(env, symbol_short!("merch_pay"), event);
+}
+
+/// Publish a merchant allowlist change.
+pub fn publish_merchant_allowed(env: &Env, event: MerchantAllowedEvent) {
+    publish(env, symbol_short!("merchant"), event);
+}
+
+/// Publish an admin handover event under the given action topic.
+pub fn publish_admin_transfer_event(env: &Env, action: Symbol, event: AdminTransferEvent) {
+    publish(env, action, event);
+}
+
+/// Publish a storage migration event.
+pub fn publish_migration_event(env: &Env, event: MigrationEvent) {
+    publish(env, symbol_short!("migrated"), event);
+}
+
+/// Publish a deferred-interest promo event under the given action topic.
+pub fn publish_deferred_promo_event(env: &Env, action: Symbol, event: DeferredPromoEvent) {
+    publish(env, action, event);
+}
+
+/// Publish a delegation change under the given action topic.
+pub fn publish_delegation_event(env: &Env, action: Symbol, event: DelegationEvent) {
+    publish(env, action, event);
+}
+
+/// Publish a delegate draw event.
+pub fn publish_delegate_drawn(env: &Env, event: DelegateDrawnEvent) {
+    publish(env, symbol_short!("dlg_draw"), event);
+}
+
+/// Publish a line note event.
+pub fn publish_line_note(env: &Env, event: LineNoteEvent) {
+    publish(env, symbol_short!("note"), event);
+}
+
+/// Publish a line metadata event.
+pub fn publish_line_metadata(env: &Env, event: LineMetadataEvent) {
+    publish(env, symbol_short!("metadata"), event);
+}
+
+/// Publish the final summary of a closed line.
+pub fn publish_line_summary(env: &Env, event: LineSummaryEvent) {
+    publish(env, Symbol::new(env, "line_summary"), event);
+}
+
+/// Publish a collateral change under the given action topic.
+pub fn publish_collateral_event(env: &Env, action: Symbol, event: CollateralEvent) {
+    publish(env, action, event);
+}
+
+/// Publish a restructuring event.
+pub fn publish_restructured(env: &Env, event: RestructuredEvent) {
+    publish(env, symbol_short!("restruct"), event);
+}
+
+/// Publish a KYC allowlist change.
+pub fn publish_kyc_event(env: &Env, event: KycEvent) {
+    publish(env, symbol_short!("kyc"), event);
+}
+
+/// Publish a blacklist change.
+pub fn publish_blacklist_event(env: &Env, event: BlacklistEvent) {
+    publish(env, symbol_short!("blacklist"), event);
+}
+
+/// Publish an operation freeze change.
+pub fn publish_operation_freeze_event(env: &Env, event: OperationFreezeEvent) {
+    publish(env, symbol_short!("op_frz"), event);
+}
+
+/// Publish a risk tier event under the given action topic.
+pub fn publish_risk_tier_event(env: &Env, action: Symbol, event: RiskTierEvent) {
+    publish(env, action, event);
+}
+
+/// Publish a release of the loss provision.
+pub fn publish_provision_released(env: &Env, event: ProvisionReleasedEvent) {
+    publish(env, symbol_short!("prov_rel"), event);
+}
+
+/// Publish a repayment deposit change under `action`.
+pub fn publish_repay_deposit_event(env: &Env, action: Symbol, event: RepayDepositEvent) {
+    publish(env, action, event);
+}
+
+/// Publish an alternate-token repayment event.
+pub fn publish_alt_repayment_event(env: &Env, event: AltRepaymentEvent) {
+    publish(env, symbol_short!("repay_alt"), event);
+}
+
+/// Publish an end-of-day close checkpoint.
+pub fn publish_day_closed(env: &Env, event: DayClosedEvent) {
+    publish(env, Symbol::new(env, "day_closed"), event);
+}
+
+/// Publish a circuit breaker trip.
+pub fn publish_circuit_breaker_tripped(env: &Env, event: CircuitBreakerTrippedEvent) {
+    publish(env, symbol_short!("brk_trip"), event);
+}
+
+/// Publish a circuit breaker reset.
+pub fn publish_circuit_breaker_reset(env: &Env, event: CircuitBreakerResetEvent) {
+    publish(env, symbol_short!("brk_reset"), event);
+}
+
+/// Publish a margin call.
+pub fn publish_margin_call(env: &Env, event: MarginCallEvent) {
+    publish(env, symbol_short!("mrgn_call"), event);
+}
+
+/// Publish a portfolio snapshot.
+pub fn publish_snapshot_taken(env: &Env, event: SnapshotTakenEvent) {
+    publish(env, symbol_short!("snapshot"), event);
+}
+
+/// Publish a borrow index bump.
+pub fn publish_borrow_index_bumped(env: &Env, index: BorrowIndex) {
+    publish(env, symbol_short!("idx_bump"), index);
+}
+
+/// Publish a keeper poke.
+pub fn publish_keeper_poke(env: &Env, event: KeeperPokeEvent) {
+    publish(env, symbol_short!("poke"), event);
+}
+
+/// Publish a late fee charge.
+pub fn publish_late_fee(env: &Env, event: LateFeeEvent) {
+    publish(env, symbol_short!("late_fee"), event);
+}
+
+/// Publish a risk manager role change.
+pub fn publish_risk_manager_event(env: &Env, event: RiskManagerEvent) {
+    publish(env, symbol_short!("risk_role"), event);
+}
+
+/// Publish a risk engine change.
+pub fn publish_risk_engine_event(env: &Env, event: RiskEngineEvent) {
+    publish(env, symbol_short!("risk_eng"), event);
+}
+
+/// Publish a compliance role change.
+pub fn publish_compliance_role_event(env: &Env, event: ComplianceRoleEvent) {
+    publish(env, symbol_short!("comp_role"), event);
+}
+
+/// Publish an arbiter registry change.
+pub fn publish_arbiter_event(env: &Env, event: ArbiterEvent) {
+    publish(env, symbol_short!("arbiter"), event);
+}
+
+/// Publish an arbiter's dispute resolution.
+pub fn publish_dispute_resolved(env: &Env, event: DisputeResolvedEvent) {
+    publish(env, symbol_short!("dispute"), event);
+}
+
+/// Publish a post-default recovery event.
+pub fn publish_recovery(env: &Env, event: RecoveryEvent) {
+    publish(env, symbol_short!("recovery"), event);
+}
+
+/// Publish a defaulted-line restructuring event.
+pub fn publish_default_restructured(env: &Env, event: DefaultRestructuredEvent) {
+    publish(env, symbol_short!("dflt_rstr"), event);
+}
+
+/// Publish a limit increase request event under the given action topic.
+pub fn publish_limit_request_event(env: &Env, action: Symbol, event: LimitRequestEvent) {
+    publish(env, action, event);
+}
+
+/// Publish a timelock event under the given action topic.
+pub fn publish_timelock_event(env: &Env, action: Symbol, event: TimelockEvent) {
+    publish(env, action, event);
+}
+
+/// Publish a council proposal event under the given action topic.
+pub fn publish_proposal_event(env: &Env, action: Symbol, event: ProposalEvent) {
+    publish(env, action, event);
+}
+
+/// Publish a limit boost event.
+pub fn publish_limit_boost(env: &Env, event: LimitBoostEvent) {
+    publish(env, symbol_short!("boost"), event);
+}
+
+/// Publish a pending draw event under the given action topic.
+pub fn publish_pending_draw_event(env: &Env, action: Symbol, event: PendingDrawEvent) {
+    publish(env, action, event);
+}
+
+/// Publish a voucher redemption event.
+pub fn publish_voucher_redeemed(env: &Env, event: VoucherRedeemedEvent) {
+    publish(env, symbol_short!("voucher"), event);
+}
+
+/// Publish a scheduled draw event under the given action topic.
+pub fn publish_scheduled_draw_event(env: &Env, action: Symbol, event: ScheduledDrawEvent) {
+    publish(env, action, event);
+}
+
+/// Publish a draw mandate event under the given action topic.
+pub fn publish_draw_mandate_event(env: &Env, action: Symbol, event: DrawMandateEvent) {
+    publish(env, action, event);
 }
@@ -0,0 +1,68 @@
+//! Protocol-wide exposure caps, the solvency guardrail while the pool is small: an optional
+//! cap on the total utilized across all lines, checked on every draw, and an optional cap on
+//! the total committed credit limits, checked when a line is opened. Totals come from the
+//! protocol statistics. Lines in an asset other than the liquidity token are capped separately,
+//! against that asset's own caps and totals.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::stats;
+use crate::types::{ExposureCaps, ProtocolStats};
+
+/// Storage keys for exposure caps.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExposureKey {
+    Caps,
+    AssetCaps(Address),
+}
+
+/// Caps on the lines in `asset`, or on the book in the liquidity token for `None`.
+pub fn caps(env: &Env, asset: Option<&Address>) -> ExposureCaps {
+    let caps = match asset {
+        None => env.storage().instance().get(&ExposureKey::Caps),
+        Some(asset) => env
+            .storage()
+            .persistent()
+            .get(&ExposureKey::AssetCaps(asset.clone())),
+    };
+    caps.unwrap_or(ExposureCaps {
+        max_total_utilized: None,
+        max_total_committed: None,
+    })
+}
+
+pub fn set_caps(env: &Env, asset: Option<&Address>, caps: &ExposureCaps) {
+    match asset {
+        None => env.storage().instance().set(&ExposureKey::Caps, caps),
+        Some(asset) => env
+            .storage()
+            .persistent()
+            .set(&ExposureKey::AssetCaps(asset.clone()), caps),
+    }
+}
+
+/// Amount that can still be drawn on lines in `asset`, or `None` without a utilization cap.
+pub fn utilized_headroom(env: &Env, asset: Option<&Address>) -> Option<i128> {
+    let cap = caps(env, asset).max_total_utilized?;
+    Some((cap - stats::get_stats(env, asset).total_utilized).max(0))
+}
+
+/// Whether drawing `amount` in `asset` now would take its total utilization over the cap.
+pub fn draw_exceeds(env: &Env, asset: Option<&Address>, amount: i128) -> bool {
+    utilized_headroom(env, asset).is_some_and(|headroom| amount > headroom)
+}
+
+/// Whether committing another `credit_limit` in `asset` on top of `stats` would take total
+/// committed limits over the cap. Takes the statistics rather than reading them, so that a batch
+/// checks against the totals it has accumulated so far.
+pub fn commitment_exceeds(
+    env: &Env,
+    asset: Option<&Address>,
+    stats: &ProtocolStats,
+    credit_limit: i128,
+) -> bool {
+    caps(env, asset)
+        .max_total_committed
+        .is_some_and(|cap| stats.total_credit_limit.saturating_add(credit_limit) > cap)
+}
@@ -0,0 +1,174 @@
+//! Protocol fees: a reserve factor on collected interest, an origination fee on draws, an
+//! opening fee on new lines and late fees on overdue payments, all accruing to a treasury
+//! balance held by this contract in the liquidity token, and a share of collected interest sent
+//! to the insurance fund. Fees on a referred line are shared with its referrer (see
+//! `referrals`).
+
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol};
+
+use crate::events::{publish_fee_event, FeeEvent};
+use crate::interest::BPS_DENOMINATOR;
+use crate::types::{DrawFeeMode, LateFeeConfig, PaymentDue};
+use crate::{referrals, ttl, DataKey};
+
+/// Storage keys for fee settings.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FeeKey {
+    /// Fee (bps of the credit limit) charged when a line is opened.
+    OpeningFeeBps,
+    DrawFeeMode,
+    LateFee,
+    /// Due timestamp of the last payment due a line was charged a late fee for.
+    LateFeeAssessed(Address),
+    /// Fee (bps of the amount) charged on flash draws.
+    FlashFeeBps,
+}
+
+pub fn reserve_factor_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::ReserveFactorBps)
+        .unwrap_or(0)
+}
+
+pub fn origination_fee_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::OriginationFeeBps)
+        .unwrap_or(0)
+}
+
+pub fn opening_fee_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&FeeKey::OpeningFeeBps)
+        .unwrap_or(0)
+}
+
+pub fn set_opening_fee_bps(env: &Env, opening_fee_bps: u32) {
+    env.storage()
+        .instance()
+        .set(&FeeKey::OpeningFeeBps, &opening_fee_bps);
+}
+
+pub fn flash_fee_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&FeeKey::FlashFeeBps)
+        .unwrap_or(0)
+}
+
+pub fn set_flash_fee_bps(env: &Env, flash_fee_bps: u32) {
+    env.storage()
+        .instance()
+        .set(&FeeKey::FlashFeeBps, &flash_fee_bps);
+}
+
+pub fn draw_fee_mode(env: &Env) -> DrawFeeMode {
+    env.storage()
+        .instance()
+        .get(&FeeKey::DrawFeeMode)
+        .unwrap_or(DrawFeeMode::Withheld)
+}
+
+pub fn set_draw_fee_mode(env: &Env, mode: DrawFeeMode) {
+    env.storage().instance().set(&FeeKey::DrawFeeMode, &mode);
+}
+
+pub fn late_fee(env: &Env) -> Option<LateFeeConfig> {
+    env.storage().instance().get(&FeeKey::LateFee)
+}
+
+pub fn set_late_fee(env: &Env, config: Option<LateFeeConfig>) {
+    match config {
+        Some(config) => env.storage().instance().set(&FeeKey::LateFee, &config),
+        None => env.storage().instance().remove(&FeeKey::LateFee),
+    }
+}
+
+/// Late fee for an overdue `due`.
+pub fn late_fee_for(config: &LateFeeConfig, due: &PaymentDue) -> i128 {
+    config
+        .flat
+        .saturating_add(fee_for(due.amount, config.bps))
+        .min(config.max_per_period)
+}
+
+/// Whether the line was already charged a late fee for the payment due at `due_ts`.
+pub fn late_fee_assessed(env: &Env, borrower: &Address, due_ts: u64) -> bool {
+    env.storage()
+        .persistent()
+        .get::<_, u64>(&FeeKey::LateFeeAssessed(borrower.clone()))
+        == Some(due_ts)
+}
+
+pub fn record_late_fee(env: &Env, borrower: &Address, due_ts: u64) {
+    let key = FeeKey::LateFeeAssessed(borrower.clone());
+    env.storage().persistent().set(&key, &due_ts);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ttl::LINE_BUMP_THRESHOLD, ttl::LINE_BUMP_AMOUNT);
+}
+
+pub fn insurance_factor_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::InsuranceFactorBps)
+        .unwrap_or(0)
+}
+
+pub fn insurance_fund(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::InsuranceFund)
+}
+
+pub fn treasury_balance(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::TreasuryBalance)
+        .unwrap_or(0)
+}
+
+pub fn set_treasury_balance(env: &Env, balance: i128) {
+    env.storage()
+        .instance()
+        .set(&DataKey::TreasuryBalance, &balance);
+}
+
+/// Fee charged on `amount` at `fee_bps`, rounded down.
+pub fn fee_for(amount: i128, fee_bps: u32) -> i128 {
+    amount * fee_bps as i128 / BPS_DENOMINATOR
+}
+
+/// Credit `amount` of fees of the given `kind` to the treasury, less the referrer's share if
+/// the line has one, and emit a fee event.
+pub fn accrue_fee(env: &Env, borrower: &Address, kind: Symbol, amount: i128) {
+    if amount <= 0 {
+        return;
+    }
+    let referral = referrals::credit(env, borrower, amount);
+    set_treasury_balance(env, treasury_balance(env) + amount - referral);
+    publish_fee_event(
+        env,
+        FeeEvent {
+            borrower: borrower.clone(),
+            kind,
+            amount,
+        },
+    );
+}
+
+/// Emit a fee event for an interest share sent to the insurance fund (not held in the treasury).
+pub fn record_insurance_fee(env: &Env, borrower: &Address, amount: i128) {
+    if amount <= 0 {
+        return;
+    }
+    publish_fee_event(
+        env,
+        FeeEvent {
+            borrower: borrower.clone(),
+            kind: symbol_short!("insure"),
+            amount,
+        },
+    );
+}
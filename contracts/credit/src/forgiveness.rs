@@ -0,0 +1,45 @@
+//! Debt forgiveness records: the cumulative amount forgiven to each borrower under hardship
+//! programs, and across the protocol, kept for auditors.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::ttl;
+
+/// Storage keys for forgiveness records.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ForgivenessKey {
+    /// Amount forgiven to a borrower, across all its lines.
+    Forgiven(Address),
+    /// Amount forgiven across the protocol.
+    TotalForgiven,
+}
+
+pub fn forgiven(env: &Env, borrower: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&ForgivenessKey::Forgiven(borrower.clone()))
+        .unwrap_or(0)
+}
+
+pub fn total(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&ForgivenessKey::TotalForgiven)
+        .unwrap_or(0)
+}
+
+/// Add `amount` to the borrower's and the protocol's forgiven totals. Returns the borrower's
+/// new total.
+pub fn record(env: &Env, borrower: &Address, amount: i128) -> i128 {
+    let key = ForgivenessKey::Forgiven(borrower.clone());
+    let forgiven = forgiven(env, borrower) + amount;
+    env.storage().persistent().set(&key, &forgiven);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ttl::LINE_BUMP_THRESHOLD, ttl::LINE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&ForgivenessKey::TotalForgiven, &(total(env) + amount));
+    forgiven
+}
@@ -0,0 +1,116 @@
+//! Health factor of collateral-linked lines: the limit the line's unlock terms would give at the
+//! collateral's current value, over what the line owes. Keeper upkeep warns borrowers with a
+//! margin-call event when it falls below a configured threshold, so they can top up collateral.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::events::{self, MarginCallEvent};
+use crate::interest::BPS_DENOMINATOR;
+use crate::types::{CreditLineData, CreditStatus, HealthFactor};
+use crate::{collateral, price, statement, CreditError, DataKey};
+
+/// Storage keys for health monitoring.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HealthKey {
+    /// Health factor (bps) below which upkeep emits a margin call.
+    MarginCallThreshold,
+}
+
+pub fn margin_call_bps(env: &Env) -> Option<u32> {
+    env.storage()
+        .instance()
+        .get(&HealthKey::MarginCallThreshold)
+}
+
+pub fn set_margin_call_bps(env: &Env, threshold_bps: Option<u32>) {
+    match threshold_bps {
+        Some(threshold_bps) => env
+            .storage()
+            .instance()
+            .set(&HealthKey::MarginCallThreshold, &threshold_bps),
+        None => env
+            .storage()
+            .instance()
+            .remove(&HealthKey::MarginCallThreshold),
+    }
+}
+
+/// Value of `amount` of collateral in the line's token, `token`. Collateral in the line's own
+/// token, or on a line without a token, is taken at face value.
+///
+/// # Errors
+/// * `NotConfigured` if the tokens differ and no price oracle is set
+/// * The errors of `price::rate`
+fn collateral_value(env: &Env, amount: i128, token: Option<Address>) -> Result<i128, CreditError> {
+    let collateral_token: Option<Address> = env.storage().instance().get(&DataKey::CollateralToken);
+    match (collateral_token, token) {
+        (Some(collateral_token), Some(token)) if collateral_token != token && amount > 0 => {
+            let oracle = statement::price_oracle(env).ok_or(CreditError::NotConfigured)?;
+            let (numerator, denominator) = price::rate(env, &oracle, &collateral_token, &token)?;
+            Ok(amount.checked_mul(numerator).ok_or(CreditError::Overflow)? / denominator)
+        }
+        _ => Ok(amount),
+    }
+}
+
+/// Health of `line`, denominated in `token`, or `None` if the line has no collateral unlock
+/// terms. The line's accrued interest should be current.
+///
+/// # Errors
+/// * The errors of `collateral_value`
+pub fn health_factor(
+    env: &Env,
+    line: &CreditLineData,
+    token: Option<Address>,
+) -> Result<Option<HealthFactor>, CreditError> {
+    let Some(unlock) = collateral::get_unlock(env, &line.borrower, line.asset.as_ref()) else {
+        return Ok(None);
+    };
+    let collateral = collateral::collateral_of(env, &line.borrower, line.asset.as_ref());
+    let collateral_value = collateral_value(env, collateral, token)?;
+    let borrowing_capacity = collateral::unlocked_limit(&unlock, collateral_value);
+    let debt = line.utilized_amount + line.accrued_interest;
+    let health_factor_bps = (debt > 0).then(|| {
+        let ratio = borrowing_capacity.saturating_mul(BPS_DENOMINATOR) / debt;
+        ratio.clamp(0, u32::MAX as i128) as u32
+    });
+    Ok(Some(HealthFactor {
+        debt,
+        collateral,
+        collateral_value,
+        ltv_bps: unlock.multiplier_bps,
+        borrowing_capacity,
+        health_factor_bps,
+    }))
+}
+
+/// Publish a margin call if the open line's health factor is below the threshold. A line whose
+/// collateral has no price (no oracle, or no price for a token) is skipped rather than failing
+/// the upkeep.
+pub fn check_margin_call(env: &Env, line: &CreditLineData, token: Option<Address>) {
+    let Some(threshold_bps) = margin_call_bps(env) else {
+        return;
+    };
+    if !matches!(line.status, CreditStatus::Active | CreditStatus::Suspended) {
+        return;
+    }
+    let Ok(Some(health)) = health_factor(env, line, token) else {
+        return;
+    };
+    let Some(health_factor_bps) = health.health_factor_bps else {
+        return;
+    };
+    if health_factor_bps < threshold_bps {
+        events::publish_margin_call(
+            env,
+            MarginCallEvent {
+                borrower: line.borrower.clone(),
+                health_factor_bps,
+                threshold_bps,
+                debt: health.debt,
+                collateral_value: health.collateral_value,
+            },
+        );
+    }
+}
@@ -0,0 +1,190 @@
+//! Append-only per-borrower history of draws, repayments, status changes and parameter
+//! updates, stored in fixed-size pages so auditors can rebuild a line's life from contract
+//! state alone. Each asset the borrower borrows in has a history of its own, spanning every
+//! line the borrower has held in it.
+
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Val, Vec};
+
+use crate::ttl;
+use crate::types::{AuditEntry, AuditTrailPage, CreditLineData, HistoryEntry};
+
+/// Entries per persistent storage page.
+const PAGE_SIZE: u32 = 32;
+
+/// Most history entries an audit trail call scans, so a sparse trail stays cheap to page.
+pub const MAX_AUDIT_SCAN: u32 = 200;
+
+/// Storage keys for line history.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HistoryKey {
+    /// Number of entries recorded for the borrower.
+    Len(Address),
+    /// Page `n` holds entries `n * PAGE_SIZE ..`.
+    Page(Address, u32),
+}
+
+fn len_key(env: &Env, borrower: &Address, asset: Option<&Address>) -> Val {
+    ttl::entry_key(env, HistoryKey::Len(borrower.clone()), asset)
+}
+
+fn page_key(env: &Env, borrower: &Address, asset: Option<&Address>, page: u32) -> Val {
+    ttl::entry_key(env, HistoryKey::Page(borrower.clone(), page), asset)
+}
+
+pub fn len(env: &Env, borrower: &Address, asset: Option<&Address>) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&len_key(env, borrower, asset))
+        .unwrap_or(0)
+}
+
+/// Append an entry snapshotting `line` after `action`. `amount` is the drawn or repaid amount,
+/// or 0 for status and parameter changes.
+pub fn record(env: &Env, line: &CreditLineData, action: Symbol, amount: i128) {
+    let (borrower, asset) = (&line.borrower, line.asset.as_ref());
+    let index = len(env, borrower, asset);
+    let page_key = page_key(env, borrower, asset, index / PAGE_SIZE);
+    let mut page: Vec<HistoryEntry> = env
+        .storage()
+        .persistent()
+        .get(&page_key)
+        .unwrap_or(Vec::new(env));
+    page.push_back(HistoryEntry {
+        action,
+        amount,
+        utilized_amount: line.utilized_amount,
+        credit_limit: line.credit_limit,
+        interest_rate_bps: line.interest_rate_bps,
+        status: line.status,
+        timestamp: env.ledger().timestamp(),
+    });
+    let len_key = len_key(env, borrower, asset);
+    env.storage().persistent().set(&page_key, &page);
+    env.storage().persistent().set(&len_key, &(index + 1));
+    for key in [page_key, len_key] {
+        env.storage().persistent().extend_ttl(
+            &key,
+            ttl::LINE_BUMP_THRESHOLD,
+            ttl::LINE_BUMP_AMOUNT,
+        );
+    }
+}
+
+/// Entry at `index`, if recorded.
+pub fn get(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+    index: u32,
+) -> Option<HistoryEntry> {
+    if index >= len(env, borrower, asset) {
+        return None;
+    }
+    env.storage()
+        .persistent()
+        .get::<_, Vec<HistoryEntry>>(&page_key(env, borrower, asset, index / PAGE_SIZE))?
+        .get(index % PAGE_SIZE)
+}
+
+/// Index of the first entry recorded at or after `timestamp` (the history length if none).
+/// Entries are appended in time order, so this is a binary search.
+pub fn first_at_or_after(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+    timestamp: u64,
+) -> u32 {
+    let (mut low, mut high) = (0, len(env, borrower, asset));
+    while low < high {
+        let mid = low + (high - low) / 2;
+        match get(env, borrower, asset, mid) {
+            Some(entry) if entry.timestamp < timestamp => low = mid + 1,
+            _ => high = mid,
+        }
+    }
+    low
+}
+
+/// Whether `action` is taken by the admin or another servicing role (risk engine, operator,
+/// arbiter, governance) rather than by the borrower's own use of the line.
+pub fn is_administrative(action: &Symbol) -> bool {
+    [
+        symbol_short!("suspend"),
+        symbol_short!("reactive"),
+        symbol_short!("default"),
+        symbol_short!("modified"),
+        symbol_short!("risk_upd"),
+        symbol_short!("lim_appr"),
+        symbol_short!("unlock"),
+        symbol_short!("restruct"),
+        symbol_short!("dflt_rstr"),
+        symbol_short!("write_off"),
+        symbol_short!("write_dn"),
+        symbol_short!("waiver"),
+        symbol_short!("dispute"),
+        symbol_short!("estate"),
+    ]
+    .contains(action)
+}
+
+/// Up to `limit` administrative entries at or after history index `cursor`, oldest first.
+/// Scans at most `MAX_AUDIT_SCAN` entries per call.
+pub fn audit_trail(env: &Env, borrower: &Address, cursor: u32, limit: u32) -> AuditTrailPage {
+    let total = len(env, borrower, None);
+    let mut entries = Vec::new(env);
+    let mut index = cursor;
+    let scan_end = cursor.saturating_add(MAX_AUDIT_SCAN).min(total);
+    while index < scan_end && entries.len() < limit {
+        let batch = page(
+            env,
+            borrower,
+            None,
+            index,
+            (scan_end - index).min(PAGE_SIZE),
+        );
+        for entry in batch.iter() {
+            if entries.len() < limit {
+                if is_administrative(&entry.action) {
+                    entries.push_back(AuditEntry { index, entry });
+                }
+                index += 1;
+            }
+        }
+    }
+    AuditTrailPage {
+        entries,
+        next_cursor: (index < total).then_some(index),
+    }
+}
+
+/// Up to `limit` entries starting at `offset`, oldest first.
+pub fn page(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+    offset: u32,
+    limit: u32,
+) -> Vec<HistoryEntry> {
+    let end = offset.saturating_add(limit).min(len(env, borrower, asset));
+    let mut entries = Vec::new(env);
+    let mut loaded: Option<(u32, Vec<HistoryEntry>)> = None;
+    for index in offset..end {
+        let page_no = index / PAGE_SIZE;
+        if loaded.as_ref().map(|(no, _)| *no) != Some(page_no) {
+            let stored = env
+                .storage()
+                .persistent()
+                .get(&page_key(env, borrower, asset, page_no))
+                .unwrap_or(Vec::new(env));
+            loaded = Some((page_no, stored));
+        }
+        if let Some(entry) = loaded
+            .as_ref()
+            .and_then(|(_, page)| page.get(index % PAGE_SIZE))
+        {
+            entries.push_back(entry);
+        }
+    }
+    entries
+}
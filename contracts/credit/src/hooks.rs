@@ -0,0 +1,105 @@
+//! Isolated invocation of optional external hooks.
+//!
+//! Hooks are notified with `try_invoke_contract`, so an error or panic inside a hook (or a hook
+//! address that is not a contract) is caught and reported with a `(credit, hook_fail)` event
+//! instead of reverting the core operation. Any state the failed hook wrote is rolled back.
+//!
+//! Soroban meters CPU and memory for the whole transaction, so a hook that exhausts the budget
+//! still aborts the transaction; hooks should be kept cheap.
+//!
+//! Besides the per-borrower and per-assignee hooks, the admin can register protocol-wide
+//! lifecycle hooks, notified of every open, draw, repayment and default through
+//! `on_credit_event(event)`.
+
+use soroban_sdk::{contracttype, Address, Env, IntoVal, Symbol, Val, Vec};
+
+use crate::events::{publish_hook_failed, HookFailedEvent};
+use crate::types::CreditHookEvent;
+
+/// Storage keys for lifecycle hooks.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HookKey {
+    LifecycleHooks,
+}
+
+/// Maximum number of lifecycle hooks, bounding the work every notified operation does.
+pub const MAX_LIFECYCLE_HOOKS: u32 = 5;
+
+pub fn lifecycle_hooks(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&HookKey::LifecycleHooks)
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn set_lifecycle_hooks(env: &Env, hooks: &Vec<Address>) {
+    if hooks.is_empty() {
+        env.storage().instance().remove(&HookKey::LifecycleHooks);
+    } else {
+        env.storage()
+            .instance()
+            .set(&HookKey::LifecycleHooks, hooks);
+    }
+}
+
+/// Invoke `func` on `hook` with `args`, swallowing any failure. Returns whether the call succeeded.
+pub fn try_notify(env: &Env, hook: &Address, func: Symbol, args: Vec<Val>) -> bool {
+    let result = env.try_invoke_contract::<Val, soroban_sdk::Error>(hook, &func, args);
+    let ok = matches!(result, Ok(Ok(_)));
+    if !ok {
+        publish_hook_failed(
+            env,
+            HookFailedEvent {
+                hook: hook.clone(),
+                function: func,
+            },
+        );
+    }
+    ok
+}
+
+/// Notify the borrower's registered hook, if any, of activity on their line.
+pub fn notify_borrower_hook(env: &Env, borrower: &Address, action: Symbol, amount: i128) {
+    let hook: Option<Address> = env
+        .storage()
+        .persistent()
+        .get(&crate::DataKey::BorrowerHook(borrower.clone()));
+    if let Some(hook) = hook {
+        let args: Vec<Val> = (borrower.clone(), action, amount).into_val(env);
+        try_notify(env, &hook, Symbol::new(env, "on_credit_activity"), args);
+    }
+}
+
+/// Notify the assignee's registered receivable hook, if any, of a payment it received from a
+/// repayment on the borrower's line.
+pub fn notify_receivable_hook(
+    env: &Env,
+    assignee: &Address,
+    borrower: &Address,
+    principal: i128,
+    interest: i128,
+) {
+    if let Some(hook) = crate::receivables::hook(env, assignee) {
+        let args: Vec<Val> = (borrower.clone(), principal, interest).into_val(env);
+        try_notify(env, &hook, Symbol::new(env, "on_receivable_payment"), args);
+    }
+}
+
+/// Notify every lifecycle hook of `action` on the borrower's line.
+pub fn notify_lifecycle_hooks(env: &Env, action: Symbol, borrower: &Address, amount: i128) {
+    let hooks = lifecycle_hooks(env);
+    if hooks.is_empty() {
+        return;
+    }
+    let event = CreditHookEvent {
+        action,
+        borrower: borrower.clone(),
+        amount,
+        timestamp: env.ledger().timestamp(),
+    };
+    for hook in hooks.iter() {
+        let args: Vec<Val> = (event.clone(),).into_val(env);
+        try_notify(env, &hook, Symbol::new(env, "on_credit_event"), args);
+    }
+}
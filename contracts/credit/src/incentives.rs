@@ -0,0 +1,153 @@
+//! Incentive-funded interest rebates for growth campaigns.
+//!
+//! A campaign has a finite budget and targets a risk-score band. Lines enrolled in a campaign
+//! have interest accrued during their first cycle waived, drawing down the campaign budget.
+//! Origination fees charged on draws inside the first cycle are rebated pro rata to the part of
+//! the cycle left when the line is paid off or closed early, from the same budget.
+
+use soroban_sdk::{Address, Env};
+
+use crate::events::{
+    publish_interest_rebate, publish_origination_rebate, InterestRebateEvent,
+    OriginationRebateEvent,
+};
+
+use crate::types::{RebateCampaign, RebateEnrollment};
+use crate::{fees, DataKey};
+
+pub fn get_campaign(env: &Env, campaign_id: u32) -> Option<RebateCampaign> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RebateCampaign(campaign_id))
+}
+
+pub fn set_campaign(env: &Env, campaign_id: u32, campaign: &RebateCampaign) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::RebateCampaign(campaign_id), campaign);
+}
+
+pub fn get_enrollment(env: &Env, borrower: &Address) -> Option<RebateEnrollment> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RebateEnrollment(borrower.clone()))
+}
+
+pub fn set_enrollment(env: &Env, borrower: &Address, enrollment: &RebateEnrollment) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::RebateEnrollment(borrower.clone()), enrollment);
+}
+
+/// Record an origination `fee` charged on a draw now, if it falls inside the borrower's
+/// promotional window.
+pub fn record_origination_fee(env: &Env, borrower: &Address, fee: i128) {
+    if fee <= 0 {
+        return;
+    }
+    let now = env.ledger().timestamp();
+    let Some(mut enrollment) = get_enrollment(env, borrower).filter(|e| now < e.until_ts) else {
+        return;
+    };
+    enrollment.origination_fees += fee;
+    set_enrollment(env, borrower, &enrollment);
+}
+
+/// Take the rebate due on the origination fees recorded for the borrower's enrollment: the
+/// share of them matching the part of the promotional window still left, up to the remaining
+/// campaign budget and the treasury balance the fees were credited to. The rebate is deducted
+/// from both and the recorded fees are cleared. Returns the amount to pay the borrower.
+pub fn take_origination_rebate(env: &Env, borrower: &Address) -> i128 {
+    let now = env.ledger().timestamp();
+    let Some(mut enrollment) =
+        get_enrollment(env, borrower).filter(|e| e.origination_fees > 0 && now < e.until_ts)
+    else {
+        return 0;
+    };
+    let Some(mut campaign) = get_campaign(env, enrollment.campaign_id).filter(|c| c.active) else {
+        return 0;
+    };
+    let window = (enrollment.until_ts - enrollment.enrolled_at) as i128;
+    let unused = enrollment.origination_fees * (enrollment.until_ts - now) as i128 / window;
+    let treasury = fees::treasury_balance(env);
+    let rebate = unused.min(campaign.budget_remaining).min(treasury);
+    enrollment.origination_fees = 0;
+    set_enrollment(env, borrower, &enrollment);
+    if rebate <= 0 {
+        return 0;
+    }
+    campaign.budget_remaining -= rebate;
+    set_campaign(env, enrollment.campaign_id, &campaign);
+    fees::set_treasury_balance(env, treasury - rebate);
+    publish_origination_rebate(
+        env,
+        OriginationRebateEvent {
+            borrower: borrower.clone(),
+            campaign_id: enrollment.campaign_id,
+            amount: rebate,
+        },
+    );
+    rebate
+}
+
+/// The rebate `apply_interest_rebate` would grant, with `spent` of the campaign budget already
+/// used by earlier spans that were not written back. Read-only.
+pub fn preview_interest_rebate(
+    env: &Env,
+    borrower: &Address,
+    from: u64,
+    to: u64,
+    interest: i128,
+    spent: i128,
+) -> i128 {
+    rebate_for(env, borrower, from, to, interest, spent).map_or(0, |(_, _, rebate)| rebate)
+}
+
+fn rebate_for(
+    env: &Env,
+    borrower: &Address,
+    from: u64,
+    to: u64,
+    interest: i128,
+    spent: i128,
+) -> Option<(RebateEnrollment, RebateCampaign, i128)> {
+    if interest <= 0 || to <= from {
+        return None;
+    }
+    let enrollment = get_enrollment(env, borrower).filter(|e| from < e.until_ts)?;
+    let campaign = get_campaign(env, enrollment.campaign_id)
+        .filter(|c| c.active && c.budget_remaining - spent > 0)?;
+    let covered = enrollment.until_ts.min(to) - from;
+    let eligible = interest * covered as i128 / (to - from) as i128;
+    let rebate = eligible.min(campaign.budget_remaining - spent);
+    if rebate <= 0 {
+        return None;
+    }
+    Some((enrollment, campaign, rebate))
+}
+
+/// Waive the part of `interest` (accrued over `[from, to)`) that falls inside the borrower's
+/// promotional window, up to the remaining campaign budget. Returns the amount waived.
+pub fn apply_interest_rebate(
+    env: &Env,
+    borrower: &Address,
+    from: u64,
+    to: u64,
+    interest: i128,
+) -> i128 {
+    let Some((enrollment, mut campaign, rebate)) = rebate_for(env, borrower, from, to, interest, 0)
+    else {
+        return 0;
+    };
+    campaign.budget_remaining -= rebate;
+    set_campaign(env, enrollment.campaign_id, &campaign);
+    publish_interest_rebate(
+        env,
+        InterestRebateEvent {
+            borrower: borrower.clone(),
+            campaign_id: enrollment.campaign_id,
+            amount: rebate,
+        },
+    );
+    rebate
+}
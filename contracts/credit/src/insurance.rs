@@ -0,0 +1,13 @@
+//! Cross-contract interface to the insurance fund (`contracts/insurance`).
+
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Interface the credit contract requires from an insurance fund.
+#[allow(dead_code)]
+#[contractclient(name = "InsuranceFundClient")]
+pub trait InsuranceFundInterface {
+    /// Asset the fund holds and pays out.
+    fn asset(env: Env) -> Address;
+    /// Pay coverage for a written-off `loss` to `to`, returning the amount paid.
+    fn claim(env: Env, to: Address, loss: i128) -> i128;
+}
@@ -0,0 +1,69 @@
+//! Interest math shared by the accrual engine and views.
+
+use crate::CreditError;
+
+/// Seconds in a 365-day year, used to pro-rate annual rates.
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+/// Basis points denominator (10_000 = 100%).
+pub const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Simple interest on `principal` at `rate_bps` (annual) over `elapsed` seconds.
+/// Rounds down; returns 0 for non-positive principal.
+pub fn simple_interest(principal: i128, rate_bps: u32, elapsed: u64) -> Result<i128, CreditError> {
+    if principal <= 0 || rate_bps == 0 || elapsed == 0 {
+        return Ok(0);
+    }
+    Ok(principal
+        .checked_mul(rate_bps as i128)
+        .and_then(|v| v.checked_mul(elapsed as i128))
+        .ok_or(CreditError::Overflow)?
+        / (BPS_DENOMINATOR * SECONDS_PER_YEAR as i128))
+}
+
+/// Interest on `principal` for a movement of `index_delta` in a line's borrow index, which
+/// counts `rate_bps * seconds`. Rounds down; returns 0 for non-positive principal.
+pub fn index_interest(principal: i128, index_delta: i128) -> Result<i128, CreditError> {
+    if principal <= 0 || index_delta <= 0 {
+        return Ok(0);
+    }
+    Ok(principal
+        .checked_mul(index_delta)
+        .ok_or(CreditError::Overflow)?
+        / (BPS_DENOMINATOR * SECONDS_PER_YEAR as i128))
+}
+
+/// Fixed-point scale (10^12) for compounding math.
+const SCALE: i128 = 1_000_000_000_000;
+
+/// `e^x` with `x` and the result in `SCALE` units: `x` is halved until at most 1, expanded as a
+/// Taylor series, and the result squared back up.
+fn exp_scaled(x: i128) -> Result<i128, CreditError> {
+    let mut halvings = 0;
+    while (x >> halvings) > SCALE {
+        halvings += 1;
+    }
+    let y = x >> halvings;
+    let (mut sum, mut term, mut n) = (SCALE, SCALE, 1);
+    while term > 0 {
+        term = term * y / SCALE / n;
+        sum += term;
+        n += 1;
+    }
+    for _ in 0..halvings {
+        sum = sum.checked_mul(sum).ok_or(CreditError::Overflow)? / SCALE;
+    }
+    Ok(sum)
+}
+
+/// Continuously compounded equivalent of `simple` interest on `principal`:
+/// `principal * (e^(simple / principal) - 1)`. Never less than `simple`.
+pub fn continuous_interest(principal: i128, simple: i128) -> Result<i128, CreditError> {
+    if principal <= 0 || simple <= 0 {
+        return Ok(simple);
+    }
+    let x = simple.checked_mul(SCALE).ok_or(CreditError::Overflow)? / principal;
+    let growth = exp_scaled(x)? - SCALE;
+    let compounded = principal.checked_mul(growth).ok_or(CreditError::Overflow)? / SCALE;
+    Ok(compounded.max(simple))
+}
@@ -0,0 +1,61 @@
+//! Permissionless keeper upkeep: overdue thresholds for automatic suspension and default, the
+//! bounty paid to callers whose poke changes a line's status, and the end-of-day checkpoint.
+
+use soroban_sdk::{contracttype, Env};
+
+use crate::types::{CreditLineData, KeeperConfig};
+use crate::{accrual, maturity};
+
+/// Storage keys for keeper settings.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KeeperKey {
+    KeeperConfig,
+    /// Day index of the last completed end-of-day close.
+    LastClosedDay,
+}
+
+/// Length of a day for the end-of-day close, in seconds.
+pub const DAY_SECS: u64 = 86_400;
+
+pub fn config(env: &Env) -> KeeperConfig {
+    env.storage()
+        .instance()
+        .get(&KeeperKey::KeeperConfig)
+        .unwrap_or(KeeperConfig {
+            bounty: 0,
+            suspend_after: None,
+            default_after: None,
+        })
+}
+
+pub fn set_config(env: &Env, config: &KeeperConfig) {
+    env.storage()
+        .instance()
+        .set(&KeeperKey::KeeperConfig, config);
+}
+
+pub fn last_closed_day(env: &Env) -> Option<u64> {
+    env.storage().instance().get(&KeeperKey::LastClosedDay)
+}
+
+pub fn set_last_closed_day(env: &Env, day: u64) {
+    env.storage()
+        .instance()
+        .set(&KeeperKey::LastClosedDay, &day);
+}
+
+/// Seconds the line's payment due has been unpaid past its grace period, if it is. Dues waived
+/// by the grace amount do not count.
+pub fn seconds_overdue(env: &Env, line: &CreditLineData) -> Option<u64> {
+    let due =
+        maturity::get_due(env, &line.borrower, line.asset.as_ref()).filter(|due| due.amount > 0)?;
+    if maturity::below_grace_amount(env, line) {
+        return None;
+    }
+    let late_from = due.due_ts.saturating_add(accrual::grace_period(env));
+    env.ledger()
+        .timestamp()
+        .checked_sub(late_from)
+        .filter(|overdue| *overdue > 0)
+}
@@ -0,0 +1,65 @@
+//! KYC allowlist gating: when enabled, only allowlisted addresses can open or draw on a line.
+//! The allowlist is either kept in this contract's storage or delegated to an external
+//! registry contract.
+
+use soroban_sdk::{contractclient, contracttype, Address, Env};
+
+use crate::ttl;
+use crate::types::KycMode;
+
+/// Interface the credit contract requires from an external KYC registry.
+#[allow(dead_code)]
+#[contractclient(name = "KycRegistryClient")]
+pub trait KycRegistryInterface {
+    /// Whether `account` has passed KYC.
+    fn is_allowed(env: Env, account: Address) -> bool;
+}
+
+/// Storage keys for the allowlist.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KycKey {
+    Mode,
+    /// Address on the internal allowlist.
+    Allowed(Address),
+}
+
+pub fn mode(env: &Env) -> KycMode {
+    env.storage()
+        .instance()
+        .get(&KycKey::Mode)
+        .unwrap_or(KycMode::Disabled)
+}
+
+pub fn set_mode(env: &Env, mode: &KycMode) {
+    env.storage().instance().set(&KycKey::Mode, mode);
+}
+
+pub fn set_listed(env: &Env, account: &Address, allowed: bool) {
+    let key = KycKey::Allowed(account.clone());
+    if allowed {
+        env.storage().persistent().set(&key, &true);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ttl::LINE_BUMP_THRESHOLD,
+            ttl::LINE_BUMP_AMOUNT,
+        );
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+pub fn is_listed(env: &Env, account: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&KycKey::Allowed(account.clone()))
+}
+
+/// Whether `account` may hold and use credit under the current mode.
+pub fn is_allowed(env: &Env, account: &Address) -> bool {
+    match mode(env) {
+        KycMode::Disabled => true,
+        KycMode::Internal => is_listed(env, account),
+        KycMode::Registry(registry) => KycRegistryClient::new(env, &registry).is_allowed(account),
+    }
+}
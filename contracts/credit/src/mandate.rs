@@ -0,0 +1,56 @@
+//! Recurring draw mandates. A borrower sets a standing instruction to draw a fixed amount to a
+//! recipient every interval until an end date; keepers execute each installment while the line
+//! is healthy.
+
+use soroban_sdk::{contracttype, Env};
+
+use crate::types::DrawMandate;
+use crate::CreditError;
+
+/// Storage keys for draw mandates.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MandateKey {
+    /// Id assigned to the next mandate.
+    NextMandateId,
+    /// Live mandate.
+    Mandate(u32),
+}
+
+pub fn get(env: &Env, id: u32) -> Option<DrawMandate> {
+    env.storage().persistent().get(&MandateKey::Mandate(id))
+}
+
+pub fn set(env: &Env, id: u32, mandate: &DrawMandate) {
+    env.storage()
+        .persistent()
+        .set(&MandateKey::Mandate(id), mandate);
+}
+
+/// Store `mandate` under a fresh id and return the id.
+pub fn add(env: &Env, mandate: &DrawMandate) -> u32 {
+    let id: u32 = env
+        .storage()
+        .instance()
+        .get(&MandateKey::NextMandateId)
+        .unwrap_or(0);
+    env.storage()
+        .instance()
+        .set(&MandateKey::NextMandateId, &(id + 1));
+    set(env, id, mandate);
+    id
+}
+
+/// Remove and return a mandate.
+pub fn take(env: &Env, id: u32) -> Result<DrawMandate, CreditError> {
+    let mandate = get(env, id).ok_or(CreditError::NotFound)?;
+    env.storage().persistent().remove(&MandateKey::Mandate(id));
+    Ok(mandate)
+}
+
+/// First installment time after `now`. Installments missed while no keeper ran are skipped,
+/// not made up.
+pub fn next_after(mandate: &DrawMandate, now: u64) -> u64 {
+    let missed = (now - mandate.next_at) / mandate.interval_secs;
+    mandate.next_at + (missed + 1) * mandate.interval_secs
+}
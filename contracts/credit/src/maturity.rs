@@ -0,0 +1,175 @@
+//! Maturity ladder: scheduled repayments (posted payment dues) across the book, indexed by due
+//! date so upcoming inflows can be bucketed without scanning every line.
+//!
+//! All writes to a line's `PaymentDue` go through `set_due`, which keeps the index in step.
+//!
+//! Lines whose balance is under the grace amount owe no minimum payment: their payment due is
+//! not enforced (no penalty rate, no delinquency) while interest keeps accruing as usual.
+//!
+//! Term facilities also carry a maturity date. From that date the line takes no draws, and
+//! keeper upkeep marks it matured by posting the full balance as due on the maturity date.
+
+use soroban_sdk::{contracttype, Address, Env, Map, Vec};
+
+use crate::types::{CreditLineData, LineMaturity, PaymentDue};
+use crate::{term, ttl, DataKey};
+
+/// Storage keys for minimum-payment configuration.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MaturityKey {
+    /// Balance below which a line's payment due is not enforced.
+    GraceAmount,
+    /// Maturity date of a term facility.
+    LineMaturity(Address),
+}
+
+pub fn grace_amount(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&MaturityKey::GraceAmount)
+        .unwrap_or(0)
+}
+
+pub fn set_grace_amount(env: &Env, amount: i128) {
+    env.storage()
+        .instance()
+        .set(&MaturityKey::GraceAmount, &amount);
+}
+
+pub fn line_maturity(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+) -> Option<LineMaturity> {
+    let key = ttl::entry_key(env, MaturityKey::LineMaturity(borrower.clone()), asset);
+    let maturity = env.storage().persistent().get(&key);
+    if maturity.is_some() {
+        env.storage().persistent().extend_ttl(
+            &key,
+            ttl::LINE_BUMP_THRESHOLD,
+            ttl::LINE_BUMP_AMOUNT,
+        );
+    }
+    maturity
+}
+
+pub fn set_line_maturity(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+    maturity: Option<LineMaturity>,
+) {
+    let key = ttl::entry_key(env, MaturityKey::LineMaturity(borrower.clone()), asset);
+    match maturity {
+        Some(maturity) => {
+            env.storage().persistent().set(&key, &maturity);
+            env.storage().persistent().extend_ttl(
+                &key,
+                ttl::LINE_BUMP_THRESHOLD,
+                ttl::LINE_BUMP_AMOUNT,
+            );
+        }
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+/// Whether the borrower's line in `asset` has reached its maturity date, so it takes no more
+/// draws.
+pub fn is_past_maturity(env: &Env, borrower: &Address, asset: Option<&Address>) -> bool {
+    line_maturity(env, borrower, asset).is_some_and(|m| env.ledger().timestamp() >= m.maturity_ts)
+}
+
+/// Mark a line matured once its maturity date has passed: the full balance is posted as due on
+/// the maturity date, so the usual overdue handling applies from there. Returns the maturity
+/// if the line was marked by this call.
+pub fn mark_matured(env: &Env, line: &CreditLineData) -> Option<LineMaturity> {
+    let asset = line.asset.as_ref();
+    let mut maturity = line_maturity(env, &line.borrower, asset)?;
+    if maturity.matured || env.ledger().timestamp() < maturity.maturity_ts {
+        return None;
+    }
+    maturity.matured = true;
+    set_line_maturity(env, &line.borrower, asset, Some(maturity.clone()));
+    set_due(
+        env,
+        &line.borrower,
+        asset,
+        Some(PaymentDue {
+            amount: term::balance(line).max(0),
+            due_ts: maturity.maturity_ts,
+        }),
+    );
+    Some(maturity)
+}
+
+/// Whether the line's outstanding balance is under the grace amount, so its payment due is
+/// not enforced this cycle.
+pub fn below_grace_amount(env: &Env, line: &CreditLineData) -> bool {
+    term::balance(line) < grace_amount(env)
+}
+
+pub fn get_due(env: &Env, borrower: &Address, asset: Option<&Address>) -> Option<PaymentDue> {
+    env.storage().persistent().get(&ttl::entry_key(
+        env,
+        DataKey::PaymentDue(borrower.clone()),
+        asset,
+    ))
+}
+
+/// Replace a line's payment due and move its amount in the ladder. The ladder is kept in the
+/// liquidity token, so dues of lines in other assets are left out of it.
+pub fn set_due(env: &Env, borrower: &Address, asset: Option<&Address>, due: Option<PaymentDue>) {
+    let key = ttl::entry_key(env, DataKey::PaymentDue(borrower.clone()), asset);
+    let laddered = asset.is_none();
+    if let Some(old) = get_due(env, borrower, asset).filter(|_| laddered) {
+        adjust(env, old.due_ts, -old.amount);
+    }
+    match due {
+        Some(due) if due.amount > 0 => {
+            if laddered {
+                adjust(env, due.due_ts, due.amount);
+            }
+            env.storage().persistent().set(&key, &due);
+        }
+        _ => env.storage().persistent().remove(&key),
+    }
+}
+
+fn index(env: &Env) -> Map<u64, i128> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::MaturityLadder)
+        .unwrap_or(Map::new(env))
+}
+
+fn adjust(env: &Env, due_ts: u64, delta: i128) {
+    let mut index = index(env);
+    let amount = index.get(due_ts).unwrap_or(0) + delta;
+    if amount > 0 {
+        index.set(due_ts, amount);
+    } else {
+        index.remove(due_ts);
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::MaturityLadder, &index);
+}
+
+/// Amounts coming due in `num_buckets` consecutive windows of `bucket_size` seconds starting
+/// now. Past-due amounts are counted in the first bucket.
+pub fn ladder(env: &Env, bucket_size: u64, num_buckets: u32) -> Vec<i128> {
+    let now = env.ledger().timestamp();
+    let mut buckets = Vec::new(env);
+    for _ in 0..num_buckets {
+        buckets.push_back(0_i128);
+    }
+    for (due_ts, amount) in index(env).iter() {
+        let bucket = due_ts.saturating_sub(now) / bucket_size;
+        if bucket < num_buckets as u64 {
+            let i = bucket as u32;
+            buckets.set(i, buckets.get_unchecked(i) + amount);
+        }
+    }
+    buckets
+}
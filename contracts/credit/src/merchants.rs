@@ -0,0 +1,26 @@
+//! Merchant allowlist for `pay_merchant`, which spends from a credit line straight to a
+//! merchant's address.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+/// Storage keys for merchants.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MerchantKey {
+    MerchantAllowed(Address),
+}
+
+pub fn is_allowed(env: &Env, merchant: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&MerchantKey::MerchantAllowed(merchant.clone()))
+}
+
+pub fn set_allowed(env: &Env, merchant: &Address, allowed: bool) {
+    let key = MerchantKey::MerchantAllowed(merchant.clone());
+    if allowed {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
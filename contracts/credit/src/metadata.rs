@@ -0,0 +1,50 @@
+//! Off-chain metadata reference on a line: a 32-byte hash or content id pointing at the signed
+//! loan agreement, so an on-chain line can be tied to its legal documents.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env};
+
+use crate::events::{self, LineMetadataEvent};
+use crate::ttl;
+
+/// Storage keys for line metadata.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MetadataKey {
+    LineMetadata(Address),
+}
+
+pub fn get(env: &Env, borrower: &Address) -> Option<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&MetadataKey::LineMetadata(borrower.clone()))
+}
+
+/// Clear a line's metadata reference without an event, when a new line is opened.
+pub fn clear(env: &Env, borrower: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&MetadataKey::LineMetadata(borrower.clone()));
+}
+
+/// Set or clear (with `None`) a line's metadata reference and publish it.
+pub fn set(env: &Env, borrower: &Address, metadata: Option<BytesN<32>>) {
+    let key = MetadataKey::LineMetadata(borrower.clone());
+    match &metadata {
+        Some(metadata) => {
+            env.storage().persistent().set(&key, metadata);
+            env.storage().persistent().extend_ttl(
+                &key,
+                ttl::LINE_BUMP_THRESHOLD,
+                ttl::LINE_BUMP_AMOUNT,
+            );
+        }
+        None => env.storage().persistent().remove(&key),
+    }
+    events::publish_line_metadata(
+        env,
+        LineMetadataEvent {
+            borrower: borrower.clone(),
+            metadata,
+        },
+    );
+}
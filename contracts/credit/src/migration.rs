@@ -0,0 +1,97 @@
+//! Storage schema versioning. `STORAGE_VERSION` is bumped whenever a stored type changes
+//! shape. Credit lines written under an older layout are upgraded in memory whenever they are
+//! read and are written back in the current layout on their next write; `migrate` rewrites the
+//! registered lines eagerly, a batch per call, and records the new version once every line has
+//! been visited. Lines from before the borrower registry are backfilled into it by `migrate`
+//! from the borrowers the admin passes.
+
+use soroban_sdk::{contracttype, Address, Env, FromVal, Map, Symbol, Val};
+
+use crate::types::{CreditLineData, CreditStatus};
+
+/// Current storage schema. Version 1 is the original credit line layout, from before lines
+/// carried their asset, accrued interest, accrual timestamp and last draw ledger.
+pub const STORAGE_VERSION: u32 = 2;
+
+/// Lines rewritten per `migrate` call.
+pub const MIGRATION_BATCH: u32 = 50;
+
+/// Storage keys for migration state.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MigrationKey {
+    /// Schema version all registered lines are stored in; unset on deployments that predate
+    /// versioning, which count as version 1.
+    StorageVersion,
+    /// Registry slot the next `migrate` call starts from.
+    MigrationCursor,
+}
+
+/// Credit line as stored under storage version 1.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditLineV1 {
+    pub borrower: Address,
+    pub credit_limit: i128,
+    pub utilized_amount: i128,
+    pub interest_rate_bps: u32,
+    pub risk_score: u32,
+    pub status: CreditStatus,
+}
+
+/// Number of fields in a version 1 line.
+const V1_FIELDS: u32 = 6;
+
+pub fn version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&MigrationKey::StorageVersion)
+        .unwrap_or(1)
+}
+
+pub fn set_version(env: &Env, version: u32) {
+    env.storage()
+        .instance()
+        .set(&MigrationKey::StorageVersion, &version);
+}
+
+pub fn cursor(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&MigrationKey::MigrationCursor)
+        .unwrap_or(0)
+}
+
+pub fn set_cursor(env: &Env, slot: Option<u32>) {
+    match slot {
+        Some(slot) => env
+            .storage()
+            .instance()
+            .set(&MigrationKey::MigrationCursor, &slot),
+        None => env
+            .storage()
+            .instance()
+            .remove(&MigrationKey::MigrationCursor),
+    }
+}
+
+/// Decode a stored credit line, upgrading a version 1 layout. Version 1 lines accrued no
+/// interest, so accrual starts from the upgrade.
+pub fn decode_line(env: &Env, stored: Val) -> CreditLineData {
+    if Map::<Symbol, Val>::from_val(env, &stored).len() == V1_FIELDS {
+        let line = CreditLineV1::from_val(env, &stored);
+        return CreditLineData {
+            borrower: line.borrower,
+            credit_limit: line.credit_limit,
+            utilized_amount: line.utilized_amount,
+            interest_rate_bps: line.interest_rate_bps,
+            risk_score: line.risk_score,
+            status: line.status,
+            asset: None,
+            accrued_interest: 0,
+            last_accrual_ts: env.ledger().timestamp(),
+            last_draw_ledger: None,
+        };
+    }
+    CreditLineData::from_val(env, &stored)
+}
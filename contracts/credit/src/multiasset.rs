@@ -0,0 +1,148 @@
+//! Credit lines in an asset other than the liquidity token, stored next to the borrower's line
+//! in the liquidity token under `(borrower, asset)`.
+//!
+//! A borrower holds at most one line per asset. Per-borrower settings (the obligor freeze, the
+//! blocklist, KYC) apply to all of them. Per-line state (freezes, payment dues, maturities,
+//! velocity limits, history, summaries, fees, collateral, defaults) is kept for each line under
+//! `ttl::entry_key`, and aggregate statistics and exposure caps per asset. Features keyed by the
+//! borrower alone (schedules, promos, rebates, tiers and the open-line registry) belong to the
+//! line in the liquidity token. An asset line accrues simple interest at its own rate and is
+//! charged no protocol fees. Draws go through the same checks as `draw_credit`; the line is
+//! repaid, closed and administered through the `_in_asset` entrypoints.
+
+use soroban_sdk::{symbol_short, token, Address, Env};
+
+use crate::events::{
+    publish_interest_accrued, publish_repayment_event, CreditRepayEvent, InterestAccruedEvent,
+};
+use crate::types::{CreditLineData, CreditStatus};
+use crate::{
+    close_line, history, interest, pool, pool_for_token, publish_line_closed, require_admin,
+    require_direct_admin_action, stats, store_credit_line, summary, ttl, CreditError, DataKey,
+};
+
+fn load(env: &Env, borrower: &Address, asset: &Address) -> Result<CreditLineData, CreditError> {
+    ttl::load_line(env, borrower, Some(asset)).ok_or(CreditError::CreditLineNotFound)
+}
+
+/// Where draws on a line in `asset` are funded from: the liquidity pool if it lends `asset`, and
+/// the liquidity source, used otherwise.
+fn source(env: &Env, asset: &Address) -> (Option<Address>, Address) {
+    let reserve = env
+        .storage()
+        .instance()
+        .get(&DataKey::LiquiditySource)
+        .unwrap_or(env.current_contract_address());
+    (pool_for_token(env, asset), reserve)
+}
+
+/// Accrue simple interest on `line` at its rate up to now.
+pub fn accrue(env: &Env, line: &mut CreditLineData) -> Result<(), CreditError> {
+    let now = env.ledger().timestamp();
+    if now <= line.last_accrual_ts {
+        return Ok(());
+    }
+    let interest = interest::simple_interest(
+        line.utilized_amount,
+        line.interest_rate_bps,
+        now - line.last_accrual_ts,
+    )?;
+    line.accrued_interest = line
+        .accrued_interest
+        .checked_add(interest)
+        .ok_or(CreditError::Overflow)?;
+    line.last_accrual_ts = now;
+    if interest > 0 {
+        publish_interest_accrued(
+            env,
+            InterestAccruedEvent {
+                borrower: line.borrower.clone(),
+                interest,
+                accrued_interest: line.accrued_interest,
+                timestamp: now,
+            },
+        );
+    }
+    Ok(())
+}
+
+/// Repay up to `amount` on the borrower's line in `asset`, interest first, with the borrower's
+/// tokens going back to where draws are funded from. Returns the amount applied.
+pub fn repay(
+    env: &Env,
+    borrower: &Address,
+    asset: &Address,
+    amount: i128,
+) -> Result<i128, CreditError> {
+    if amount <= 0 {
+        return Err(CreditError::InvalidAmount);
+    }
+    let mut line = load(env, borrower, asset)?;
+    if line.status == CreditStatus::Closed {
+        return Err(CreditError::CreditLineClosed);
+    }
+    accrue(env, &mut line)?;
+    let interest_paid = amount.min(line.accrued_interest);
+    let principal_paid = (amount - interest_paid).min(line.utilized_amount);
+    let applied = interest_paid + principal_paid;
+    line.accrued_interest -= interest_paid;
+    line.utilized_amount -= principal_paid;
+    store_credit_line(env, &line);
+    history::record(env, &line, symbol_short!("repaid"), applied);
+    stats::record_repayment(env, Some(asset), applied)?;
+    summary::record_repayment(env, borrower, Some(asset), interest_paid, principal_paid);
+
+    if applied > 0 {
+        let token = token::Client::new(env, asset);
+        match source(env, asset) {
+            (Some(pool), _) => {
+                token.transfer(borrower, &pool, &applied);
+                pool::PoolClient::new(env, &pool).repay(&principal_paid, &interest_paid);
+            }
+            (None, reserve) => token.transfer(borrower, &reserve, &applied),
+        }
+    }
+    publish_repayment_event(
+        env,
+        CreditRepayEvent {
+            borrower: borrower.clone(),
+            amount,
+            new_utilized_amount: line.utilized_amount,
+            timestamp: env.ledger().timestamp(),
+            overpaid: amount - applied,
+            deposited: 0,
+            fees_paid: 0,
+            interest_paid,
+            principal_paid,
+            payer: borrower.clone(),
+        },
+    );
+    Ok(applied)
+}
+
+/// Close the borrower's line in `asset`, on the same terms as `close_credit_line`: the admin
+/// may close it at any time, the borrower only once nothing is drawn on it.
+pub fn close(
+    env: &Env,
+    borrower: &Address,
+    asset: &Address,
+    closer: &Address,
+) -> Result<(), CreditError> {
+    let admin = require_admin(env)?;
+    if *closer != admin && closer != borrower {
+        return Err(CreditError::Unauthorized);
+    }
+    let mut line = load(env, borrower, asset)?;
+    if line.status == CreditStatus::Closed {
+        return Ok(());
+    }
+    if *closer != admin && line.utilized_amount != 0 {
+        return Err(CreditError::OutstandingBalance);
+    }
+    if line.utilized_amount != 0 {
+        require_direct_admin_action(env)?;
+    }
+    close_line(env, &mut line)?;
+    publish_line_closed(env, &line);
+    Ok(())
+}
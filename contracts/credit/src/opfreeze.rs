@@ -0,0 +1,74 @@
+//! Per-operation freezes: flags that block one kind of operation (draws, repayments, limit
+//! increases) on a single line or protocol-wide, independently of the line's status and of the
+//! whole-line and obligor freezes.
+
+use soroban_sdk::{contracttype, Address, Env, IntoVal, Val};
+
+use crate::ttl;
+use crate::types::OperationFreezes;
+
+/// Storage keys for operation freezes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OpFreezeKey {
+    /// Freezes scoped to the borrower's current line; dropped when a new line is opened.
+    LineOpFreeze(Address),
+    GlobalOpFreeze,
+}
+
+fn key(env: &Env, borrower: Option<&Address>, asset: Option<&Address>) -> Val {
+    match borrower {
+        Some(borrower) => ttl::entry_key(env, OpFreezeKey::LineOpFreeze(borrower.clone()), asset),
+        None => OpFreezeKey::GlobalOpFreeze.into_val(env),
+    }
+}
+
+/// Flags set on the borrower's line in `asset`, or protocol-wide for a `None` borrower.
+pub fn get(env: &Env, borrower: Option<&Address>, asset: Option<&Address>) -> OperationFreezes {
+    let key = key(env, borrower, asset);
+    match borrower {
+        Some(_) => env.storage().persistent().get(&key),
+        None => env.storage().instance().get(&key),
+    }
+    .unwrap_or_default()
+}
+
+/// Store the flags for the borrower's line in `asset`, or protocol-wide for a `None` borrower.
+/// Clearing every flag removes the entry.
+pub fn set(
+    env: &Env,
+    borrower: Option<&Address>,
+    asset: Option<&Address>,
+    freezes: &OperationFreezes,
+) {
+    let key = key(env, borrower, asset);
+    let clear = *freezes == OperationFreezes::default();
+    if borrower.is_none() {
+        if clear {
+            env.storage().instance().remove(&key);
+        } else {
+            env.storage().instance().set(&key, freezes);
+        }
+    } else if clear {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, freezes);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ttl::LINE_BUMP_THRESHOLD,
+            ttl::LINE_BUMP_AMOUNT,
+        );
+    }
+}
+
+/// Flags in force for the borrower's line in `asset`: its own combined with the protocol-wide
+/// ones.
+pub fn effective(env: &Env, borrower: &Address, asset: Option<&Address>) -> OperationFreezes {
+    let line = get(env, Some(borrower), asset);
+    let global = get(env, None, None);
+    OperationFreezes {
+        draws: line.draws || global.draws,
+        repayments: line.repayments || global.repayments,
+        limit_increases: line.limit_increases || global.limit_increases,
+    }
+}
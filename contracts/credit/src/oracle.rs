@@ -0,0 +1,20 @@
+//! Cross-contract interface to the risk oracle (`contracts/risk-oracle`).
+
+use soroban_sdk::{contractclient, contracttype, Address, Env};
+
+/// Score record returned by the oracle's `get_score`. Field names and types must match the
+/// oracle's `ScoreRecord` so the XDR representation is shared.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleScore {
+    pub score: u32,
+    pub updated_at: u64,
+}
+
+/// Interface the credit contract requires from a risk oracle.
+#[allow(dead_code)]
+#[contractclient(name = "RiskOracleClient")]
+pub trait RiskOracleInterface {
+    /// Latest score for a borrower, or `None` if the oracle has never scored them.
+    fn get_score(env: Env, borrower: Address) -> Option<OracleScore>;
+}
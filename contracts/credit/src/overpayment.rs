@@ -0,0 +1,27 @@
+//! What happens to the part of a repayment that exceeds the line's outstanding balance. The
+//! policy is a deployment setting; by default the excess is refunded, i.e. never collected from
+//! the payer.
+
+use soroban_sdk::{contracttype, Env};
+
+use crate::types::OverpaymentPolicy;
+
+/// Storage keys for overpayment settings.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OverpaymentKey {
+    OverpaymentPolicy,
+}
+
+pub fn policy(env: &Env) -> OverpaymentPolicy {
+    env.storage()
+        .instance()
+        .get(&OverpaymentKey::OverpaymentPolicy)
+        .unwrap_or(OverpaymentPolicy::Refund)
+}
+
+pub fn set_policy(env: &Env, policy: OverpaymentPolicy) {
+    env.storage()
+        .instance()
+        .set(&OverpaymentKey::OverpaymentPolicy, &policy);
+}
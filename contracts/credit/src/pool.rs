@@ -0,0 +1,21 @@
+//! Cross-contract interface to the liquidity pool (`contracts/pool`).
+
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Interface the credit contract requires from a liquidity pool.
+#[allow(dead_code)]
+#[contractclient(name = "PoolClient")]
+pub trait PoolInterface {
+    /// Underlying asset lent by the pool.
+    fn asset(env: Env) -> Address;
+    /// Annual yield (bps) the pool targets for lenders.
+    fn target_yield_bps(env: Env) -> u32;
+    /// Idle cash available to borrow.
+    fn available_liquidity(env: Env) -> i128;
+    /// Transfer `amount` from the pool to `to`, recording it as borrowed.
+    fn borrow(env: Env, to: Address, amount: i128);
+    /// Record a repayment already transferred to the pool.
+    fn repay(env: Env, principal: i128, interest: i128);
+    /// Write off principal that will not be repaid.
+    fn write_off(env: Env, amount: i128);
+}
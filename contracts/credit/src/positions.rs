@@ -0,0 +1,68 @@
+//! Transferable credit positions. Every line gets a unique position id when it is opened; the
+//! holder of a position is the address the line is booked to, and moving the position re-keys
+//! the line to another address without closing it.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::ttl;
+
+/// Storage keys for positions.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PositionKey {
+    /// Id assigned to the next opened line.
+    NextPositionId,
+    /// Borrower -> position id of their current line.
+    PositionOf(Address),
+    /// Position id -> address holding it.
+    PositionHolder(u64),
+}
+
+pub fn position_of(env: &Env, holder: &Address) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&PositionKey::PositionOf(holder.clone()))
+}
+
+pub fn holder(env: &Env, position_id: u64) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&PositionKey::PositionHolder(position_id))
+}
+
+fn set(env: &Env, position_id: u64, holder: &Address) {
+    let storage = env.storage().persistent();
+    let id_key = PositionKey::PositionOf(holder.clone());
+    let holder_key = PositionKey::PositionHolder(position_id);
+    storage.set(&id_key, &position_id);
+    storage.set(&holder_key, holder);
+    storage.extend_ttl(&id_key, ttl::LINE_BUMP_THRESHOLD, ttl::LINE_BUMP_AMOUNT);
+    storage.extend_ttl(&holder_key, ttl::LINE_BUMP_THRESHOLD, ttl::LINE_BUMP_AMOUNT);
+}
+
+/// Assign a fresh position id to a newly opened line, retiring the id of the line it replaces.
+pub fn issue(env: &Env, borrower: &Address) -> u64 {
+    if let Some(old) = position_of(env, borrower) {
+        env.storage()
+            .persistent()
+            .remove(&PositionKey::PositionHolder(old));
+    }
+    let id: u64 = env
+        .storage()
+        .instance()
+        .get(&PositionKey::NextPositionId)
+        .unwrap_or(1);
+    env.storage()
+        .instance()
+        .set(&PositionKey::NextPositionId, &(id + 1));
+    set(env, id, borrower);
+    id
+}
+
+/// Record that `position_id` moved from `from` to `to`.
+pub fn move_to(env: &Env, position_id: u64, from: &Address, to: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&PositionKey::PositionOf(from.clone()));
+    set(env, position_id, to);
+}
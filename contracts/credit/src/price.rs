@@ -0,0 +1,66 @@
+//! Cross-contract interface to a price oracle, used for reporting conversions and for
+//! repayments in an alternate token.
+
+use soroban_sdk::{contractclient, contracttype, token, Address, Env};
+
+use crate::CreditError;
+
+/// Price record returned by the oracle's `lastprice`. Field names and types follow the
+/// SEP-40 `PriceData` so the XDR representation is shared.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// Interface the credit contract requires from a price oracle. All prices are quoted in the
+/// oracle's common base currency at the same precision.
+#[allow(dead_code)]
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracleInterface {
+    /// Latest price of `asset`, or `None` if the oracle has no price for it.
+    fn lastprice(env: Env, asset: Address) -> Option<PriceData>;
+}
+
+/// Exchange rate from `base` to `quote` at `oracle`'s latest prices, adjusted for the two
+/// tokens' decimals, as a `(numerator, denominator)` pair: an amount of `base` is worth
+/// `amount * numerator / denominator` of `quote`.
+///
+/// # Errors
+/// * `NotFound` if the oracle has no price for either token
+/// * `InvalidParameter` if a price is not positive
+pub fn rate(
+    env: &Env,
+    oracle: &Address,
+    base: &Address,
+    quote: &Address,
+) -> Result<(i128, i128), CreditError> {
+    let oracle = PriceOracleClient::new(env, oracle);
+    let base_price = oracle.lastprice(base).ok_or(CreditError::NotFound)?.price;
+    let quote_price = oracle.lastprice(quote).ok_or(CreditError::NotFound)?.price;
+    if base_price <= 0 || quote_price <= 0 {
+        return Err(CreditError::InvalidParameter);
+    }
+    let base_decimals = token::Client::new(env, base).decimals();
+    let quote_decimals = token::Client::new(env, quote).decimals();
+    if quote_decimals >= base_decimals {
+        let scale = 10_i128
+            .checked_pow(quote_decimals - base_decimals)
+            .ok_or(CreditError::Overflow)?;
+        Ok((
+            base_price.checked_mul(scale).ok_or(CreditError::Overflow)?,
+            quote_price,
+        ))
+    } else {
+        let scale = 10_i128
+            .checked_pow(base_decimals - quote_decimals)
+            .ok_or(CreditError::Overflow)?;
+        Ok((
+            base_price,
+            quote_price
+                .checked_mul(scale)
+                .ok_or(CreditError::Overflow)?,
+        ))
+    }
+}
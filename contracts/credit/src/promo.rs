@@ -0,0 +1,78 @@
+//! Deferred-interest promotions. Interest on a promo line accrues into a shadow balance instead
+//! of `accrued_interest`. At the promo end the shadow interest is waived if the line was paid in
+//! full by then, and otherwise charged to the line in one go.
+
+use soroban_sdk::{contracttype, symbol_short, Address, Env};
+
+use crate::events::{self, DeferredPromoEvent};
+use crate::types::{CreditLineData, DeferredInterestPromo};
+use crate::{summary, ttl, CreditError};
+
+/// Storage keys for deferred-interest promos.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PromoKey {
+    Deferred(Address),
+}
+
+pub fn get(env: &Env, borrower: &Address) -> Option<DeferredInterestPromo> {
+    env.storage()
+        .persistent()
+        .get(&PromoKey::Deferred(borrower.clone()))
+}
+
+pub fn set(env: &Env, borrower: &Address, promo: Option<DeferredInterestPromo>) {
+    let key = PromoKey::Deferred(borrower.clone());
+    match promo {
+        Some(promo) => {
+            env.storage().persistent().set(&key, &promo);
+            env.storage().persistent().extend_ttl(
+                &key,
+                ttl::LINE_BUMP_THRESHOLD,
+                ttl::LINE_BUMP_AMOUNT,
+            );
+        }
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+/// Deferred interest charged to `line` when `promo` ends: nothing if the line is paid in full.
+pub fn charged_interest(line: &CreditLineData, promo: &DeferredInterestPromo) -> i128 {
+    if line.utilized_amount <= 0 && line.accrued_interest <= 0 {
+        0
+    } else {
+        promo.deferred_interest
+    }
+}
+
+/// End the promo on `line`, accrued up to `promo.ends_at`: waive the deferred interest if
+/// nothing is owed, otherwise add it to `accrued_interest`. Caller persists the line.
+pub fn settle(
+    env: &Env,
+    borrower: &Address,
+    line: &mut CreditLineData,
+    promo: &DeferredInterestPromo,
+) -> Result<(), CreditError> {
+    let paid_in_full = line.utilized_amount <= 0 && line.accrued_interest <= 0;
+    let action = if paid_in_full {
+        symbol_short!("promo_wv")
+    } else {
+        line.accrued_interest = line
+            .accrued_interest
+            .checked_add(promo.deferred_interest)
+            .ok_or(CreditError::Overflow)?;
+        summary::record_accrual(env, borrower, None, promo.deferred_interest);
+        symbol_short!("promo_chg")
+    };
+    set(env, borrower, None);
+    events::publish_deferred_promo_event(
+        env,
+        action,
+        DeferredPromoEvent {
+            borrower: borrower.clone(),
+            ends_at: promo.ends_at,
+            deferred_interest: promo.deferred_interest,
+        },
+    );
+    Ok(())
+}
@@ -0,0 +1,138 @@
+//! Expected-loss provisioning. Risk managers (or the admin) set a probability of default and a
+//! loss given default per risk tier, with an optional book-wide default for lines opened
+//! without a tier. The required provision is the expected loss over the outstanding balances
+//! tracked by `rwa`; defaulted lines and balances left on force-closed lines count with a
+//! probability of default of 100%.
+//!
+//! Interest repaid on pool-funded lines tops the funded provision up to the required amount
+//! before the lenders' share is paid to the pool. The provision is held by this contract in the
+//! liquidity token and released to the pool to absorb principal written off after any
+//! insurance claim.
+
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol};
+
+use crate::events::{publish_fee_event, FeeEvent};
+use crate::interest::BPS_DENOMINATOR;
+use crate::rwa;
+use crate::types::{CreditStatus, LossParams, ProvisionCoverage};
+
+/// Storage keys for provisioning.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProvisionKey {
+    /// Address holding the risk manager role.
+    Manager(Address),
+    /// Loss parameters for a tier, or the book-wide default for `None`.
+    LossParams(Option<Symbol>),
+    /// Funded provision held by this contract.
+    Balance,
+}
+
+pub fn is_manager(env: &Env, account: &Address) -> bool {
+    env.storage()
+        .instance()
+        .has(&ProvisionKey::Manager(account.clone()))
+}
+
+pub fn set_manager(env: &Env, account: &Address, enabled: bool) {
+    let key = ProvisionKey::Manager(account.clone());
+    if enabled {
+        env.storage().instance().set(&key, &true);
+    } else {
+        env.storage().instance().remove(&key);
+    }
+}
+
+pub fn loss_params(env: &Env, tier: &Option<Symbol>) -> Option<LossParams> {
+    env.storage()
+        .instance()
+        .get(&ProvisionKey::LossParams(tier.clone()))
+}
+
+pub fn set_loss_params(env: &Env, tier: &Option<Symbol>, params: Option<LossParams>) {
+    let key = ProvisionKey::LossParams(tier.clone());
+    match params {
+        Some(params) => env.storage().instance().set(&key, &params),
+        None => env.storage().instance().remove(&key),
+    }
+}
+
+pub fn balance(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&ProvisionKey::Balance)
+        .unwrap_or(0)
+}
+
+fn set_balance(env: &Env, balance: i128) {
+    env.storage()
+        .instance()
+        .set(&ProvisionKey::Balance, &balance);
+}
+
+/// Expected loss over the book: each balance times its probability of default and loss given
+/// default. Tiers without parameters fall back to the book-wide default; without either, a
+/// balance needs no provision.
+pub fn expected_loss(env: &Env) -> i128 {
+    let mut total = 0;
+    for ((tier, status), balance) in rwa::balances(env).iter() {
+        let params = tier
+            .clone()
+            .and_then(|tier| loss_params(env, &Some(tier)))
+            .or_else(|| loss_params(env, &None));
+        let Some(params) = params else {
+            continue;
+        };
+        let pd_bps = match status {
+            CreditStatus::Defaulted | CreditStatus::Closed => BPS_DENOMINATOR,
+            CreditStatus::Active | CreditStatus::Suspended => params.pd_bps as i128,
+        };
+        total += balance * pd_bps * params.lgd_bps as i128 / (BPS_DENOMINATOR * BPS_DENOMINATOR);
+    }
+    total
+}
+
+pub fn coverage(env: &Env) -> ProvisionCoverage {
+    let expected_loss = expected_loss(env);
+    let funded = balance(env);
+    let coverage_bps = if expected_loss > 0 {
+        (funded * BPS_DENOMINATOR / expected_loss).min(u32::MAX as i128) as u32
+    } else {
+        BPS_DENOMINATOR as u32
+    };
+    ProvisionCoverage {
+        expected_loss,
+        funded,
+        coverage_bps,
+    }
+}
+
+/// Take up to `available` of repaid interest into the provision, as much as it is short of the
+/// expected loss. Returns the amount taken; the caller moves the tokens to this contract.
+pub fn fund(env: &Env, borrower: &Address, available: i128) -> i128 {
+    let funded = balance(env);
+    let amount = (expected_loss(env) - funded).clamp(0, available.max(0));
+    if amount > 0 {
+        set_balance(env, funded + amount);
+        publish_fee_event(
+            env,
+            FeeEvent {
+                borrower: borrower.clone(),
+                kind: symbol_short!("provision"),
+                amount,
+            },
+        );
+    }
+    amount
+}
+
+/// Release up to `loss` from the provision to absorb a write-off. Returns the amount released;
+/// the caller moves the tokens out of this contract.
+pub fn release(env: &Env, loss: i128) -> i128 {
+    let funded = balance(env);
+    let amount = funded.min(loss.max(0));
+    if amount > 0 {
+        set_balance(env, funded - amount);
+    }
+    amount
+}
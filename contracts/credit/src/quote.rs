@@ -0,0 +1,50 @@
+//! Soft-pull rate quotes. A quote records the rate offered for a draw and lives in temporary
+//! storage for a short validity window; drawing with it never takes a rate above the quote.
+
+use soroban_sdk::Env;
+
+use crate::types::RateQuote;
+use crate::{CreditError, DataKey};
+
+/// How long a quote can be used, in seconds.
+pub const QUOTE_VALIDITY_SECS: u64 = 300;
+
+/// Temporary storage TTL for quotes, in ledgers; covers the validity window at ~5s ledgers.
+const QUOTE_TTL_LEDGERS: u32 = 120;
+
+/// Store a quote under a fresh id and return it with the id filled in.
+pub fn issue(env: &Env, mut quote: RateQuote) -> RateQuote {
+    let id: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::QuoteNextId)
+        .unwrap_or(0);
+    env.storage()
+        .instance()
+        .set(&DataKey::QuoteNextId, &(id.wrapping_add(1)));
+    quote.quote_id = id;
+    let key = DataKey::RateQuote(id);
+    env.storage().temporary().set(&key, &quote);
+    env.storage()
+        .temporary()
+        .extend_ttl(&key, QUOTE_TTL_LEDGERS, QUOTE_TTL_LEDGERS);
+    quote
+}
+
+/// Remove and return a quote that is still valid.
+///
+/// # Errors
+/// * If the quote does not exist or has expired
+pub fn take(env: &Env, quote_id: u32) -> Result<RateQuote, CreditError> {
+    let key = DataKey::RateQuote(quote_id);
+    let quote: RateQuote = env
+        .storage()
+        .temporary()
+        .get(&key)
+        .ok_or(CreditError::NotFound)?;
+    env.storage().temporary().remove(&key);
+    if env.ledger().timestamp() >= quote.expires_at {
+        return Err(CreditError::Expired);
+    }
+    Ok(quote)
+}
@@ -0,0 +1,117 @@
+//! Receivable assignments: the lender side sells the right to a share of a line's repayments to
+//! another address. Each assignee receives its share of the principal and lender interest of
+//! every repayment instead of the pool.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::types::ReceivableAssignment;
+use crate::{ttl, CreditError};
+
+/// Maximum number of assignees per line, which bounds the transfers made on each repayment.
+pub const MAX_ASSIGNEES: u32 = 10;
+
+/// Storage keys for receivable assignments.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReceivableKey {
+    /// Borrower -> assignments of their line's receivable.
+    ReceivableAssignments(Address),
+    /// Assignee -> contract notified of the payments it receives.
+    ReceivableHook(Address),
+}
+
+pub fn assignments(env: &Env, borrower: &Address) -> Vec<ReceivableAssignment> {
+    env.storage()
+        .persistent()
+        .get(&ReceivableKey::ReceivableAssignments(borrower.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Basis points of the receivable assigned away in total.
+pub fn assigned_bps(env: &Env, borrower: &Address) -> u32 {
+    assignments(env, borrower)
+        .iter()
+        .map(|assignment| assignment.share_bps)
+        .sum()
+}
+
+fn set_assignments(env: &Env, borrower: &Address, assignments: &Vec<ReceivableAssignment>) {
+    let key = ReceivableKey::ReceivableAssignments(borrower.clone());
+    if assignments.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, assignments);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ttl::LINE_BUMP_THRESHOLD,
+            ttl::LINE_BUMP_AMOUNT,
+        );
+    }
+}
+
+/// Add `delta` (negative to take a share away) to `assignee`'s share, returning the new share.
+///
+/// # Errors
+/// * `InvalidParameter` if the share would go negative, the line's assigned total would exceed
+///   100%, or the line would have more than `MAX_ASSIGNEES` assignees
+pub fn adjust(
+    env: &Env,
+    borrower: &Address,
+    assignee: &Address,
+    delta: i64,
+) -> Result<u32, CreditError> {
+    let mut assignments = assignments(env, borrower);
+    let index = assignments
+        .iter()
+        .position(|assignment| assignment.assignee == *assignee);
+    let current = index.map_or(0, |i| assignments.get_unchecked(i as u32).share_bps);
+    let share = i64::from(current) + delta;
+    let total = i64::from(assigned_bps(env, borrower)) + delta;
+    if share < 0 || total > 10_000 {
+        return Err(CreditError::InvalidParameter);
+    }
+    let share = share as u32;
+    match (index, share) {
+        (Some(i), 0) => assignments.remove_unchecked(i as u32),
+        (Some(i), _) => assignments.set(
+            i as u32,
+            ReceivableAssignment {
+                assignee: assignee.clone(),
+                share_bps: share,
+            },
+        ),
+        (None, 0) => {}
+        (None, _) => {
+            if assignments.len() >= MAX_ASSIGNEES {
+                return Err(CreditError::InvalidParameter);
+            }
+            assignments.push_back(ReceivableAssignment {
+                assignee: assignee.clone(),
+                share_bps: share,
+            });
+        }
+    }
+    set_assignments(env, borrower, &assignments);
+    Ok(share)
+}
+
+pub fn hook(env: &Env, assignee: &Address) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&ReceivableKey::ReceivableHook(assignee.clone()))
+}
+
+pub fn set_hook(env: &Env, assignee: &Address, hook: Option<Address>) {
+    let key = ReceivableKey::ReceivableHook(assignee.clone());
+    match hook {
+        Some(hook) => env.storage().persistent().set(&key, &hook),
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+/// Drop all assignments of the borrower's receivable, as when a new line replaces a closed one.
+pub fn clear(env: &Env, borrower: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&ReceivableKey::ReceivableAssignments(borrower.clone()));
+}
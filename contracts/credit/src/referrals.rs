@@ -0,0 +1,121 @@
+//! Referral fee-sharing. A line can be opened with a referrer, which then earns a configurable
+//! share of the protocol fees the line pays (the reserve on interest, draw fees and late fees).
+//! Shares are held by this contract in the liquidity token until the referrer claims them.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::events::{self, ReferralFeeEvent};
+use crate::{fees, ttl};
+
+/// Storage keys for referrals.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReferralKey {
+    /// Share (bps) of a referred line's fees paid to its referrer.
+    ShareBps,
+    /// Referrer recorded when the line was opened.
+    Referrer(Address),
+    /// Unclaimed referral fees of a referrer.
+    Balance(Address),
+    /// Unclaimed referral fees of all referrers.
+    Total,
+}
+
+pub fn share_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&ReferralKey::ShareBps)
+        .unwrap_or(0)
+}
+
+pub fn set_share_bps(env: &Env, share_bps: u32) {
+    env.storage()
+        .instance()
+        .set(&ReferralKey::ShareBps, &share_bps);
+}
+
+pub fn referrer(env: &Env, borrower: &Address) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&ReferralKey::Referrer(borrower.clone()))
+}
+
+/// Record the line's referrer, or clear it with `None` when a new line is opened.
+pub fn set_referrer(env: &Env, borrower: &Address, referrer: Option<&Address>) {
+    let key = ReferralKey::Referrer(borrower.clone());
+    match referrer {
+        Some(referrer) => {
+            env.storage().persistent().set(&key, referrer);
+            env.storage().persistent().extend_ttl(
+                &key,
+                ttl::LINE_BUMP_THRESHOLD,
+                ttl::LINE_BUMP_AMOUNT,
+            );
+        }
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+pub fn balance(env: &Env, referrer: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&ReferralKey::Balance(referrer.clone()))
+        .unwrap_or(0)
+}
+
+/// Unclaimed referral fees of all referrers, held apart from the reserve.
+pub fn total(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&ReferralKey::Total)
+        .unwrap_or(0)
+}
+
+fn set_balance(env: &Env, referrer: &Address, balance: i128) {
+    let key = ReferralKey::Balance(referrer.clone());
+    if balance == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &balance);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ttl::LINE_BUMP_THRESHOLD,
+            ttl::LINE_BUMP_AMOUNT,
+        );
+    }
+}
+
+/// Pay the referrer's share of `fee`, charged on the borrower's line, into its balance and
+/// return it. Returns 0 for a line without a referrer.
+pub fn credit(env: &Env, borrower: &Address, fee: i128) -> i128 {
+    let Some(referrer) = referrer(env, borrower) else {
+        return 0;
+    };
+    let amount = fees::fee_for(fee, share_bps(env));
+    if amount <= 0 {
+        return 0;
+    }
+    set_balance(env, &referrer, balance(env, &referrer) + amount);
+    env.storage()
+        .instance()
+        .set(&ReferralKey::Total, &(total(env) + amount));
+    events::publish_referral_fee(
+        env,
+        ReferralFeeEvent {
+            referrer,
+            borrower: borrower.clone(),
+            amount,
+        },
+    );
+    amount
+}
+
+/// Zero the referrer's balance and return what it was.
+pub fn take(env: &Env, referrer: &Address) -> i128 {
+    let amount = balance(env, referrer);
+    set_balance(env, referrer, 0);
+    env.storage()
+        .instance()
+        .set(&ReferralKey::Total, &(total(env) - amount));
+    amount
+}
@@ -0,0 +1,81 @@
+//! Disbursement rounding. Draws are booked at full precision, but the amount paid out can be
+//! rounded down to a configured unit (e.g. the token's display precision). The remainder is
+//! carried per line and added to the line's next disbursement, so the amounts paid out plus the
+//! carry always equal the net amounts drawn.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::ttl;
+
+/// Storage keys for disbursement rounding.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RoundingKey {
+    /// Disbursements are paid in multiples of this amount; absent means 1 (no rounding).
+    Unit,
+    /// Rounding remainder owed to a line's borrower, always below the unit it was rounded to.
+    Carry(Address),
+    /// Sum of every line's carry.
+    TotalCarry,
+}
+
+pub fn unit(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&RoundingKey::Unit)
+        .unwrap_or(1)
+}
+
+pub fn set_unit(env: &Env, unit: i128) {
+    if unit == 1 {
+        env.storage().instance().remove(&RoundingKey::Unit);
+    } else {
+        env.storage().instance().set(&RoundingKey::Unit, &unit);
+    }
+}
+
+pub fn carry(env: &Env, borrower: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&RoundingKey::Carry(borrower.clone()))
+        .unwrap_or(0)
+}
+
+pub fn total_carry(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&RoundingKey::TotalCarry)
+        .unwrap_or(0)
+}
+
+/// Amount paid out for a disbursement of `net` and the carry left afterwards.
+pub fn split(env: &Env, borrower: &Address, net: i128) -> (i128, i128) {
+    let unit = unit(env);
+    let owed = net + carry(env, borrower);
+    let payout = owed - owed % unit;
+    (payout, owed - payout)
+}
+
+/// Round a disbursement of `net`, store the new carry and return the amount to pay out.
+pub fn apply(env: &Env, borrower: &Address, net: i128) -> i128 {
+    let (payout, remainder) = split(env, borrower, net);
+    let key = RoundingKey::Carry(borrower.clone());
+    let delta = remainder - carry(env, borrower);
+    if delta == 0 {
+        return payout;
+    }
+    if remainder == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &remainder);
+        env.storage().persistent().extend_ttl(
+            &key,
+            ttl::LINE_BUMP_THRESHOLD,
+            ttl::LINE_BUMP_AMOUNT,
+        );
+    }
+    env.storage()
+        .instance()
+        .set(&RoundingKey::TotalCarry, &(total_carry(env) + delta));
+    payout
+}
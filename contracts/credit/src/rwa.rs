@@ -0,0 +1,134 @@
+//! Risk-weighted assets: the book's outstanding balances weighted by line status and, for lines
+//! opened in a risk tier, by the tier's weight. Balances are kept per `(tier, status)` bucket and
+//! updated on every line write, so the view is cheap and weight changes apply at once.
+
+use soroban_sdk::{contracttype, Address, Env, Map, Symbol};
+
+use crate::interest::BPS_DENOMINATOR;
+use crate::types::{CreditLineData, CreditStatus, RiskWeightedAssets, RiskWeights};
+use crate::{tiers, ttl};
+
+/// Highest accepted risk weight (1250%), the Basel ceiling.
+pub const MAX_RISK_WEIGHT_BPS: u32 = 125_000;
+
+/// Storage keys for risk weights and bucketed balances.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RwaKey {
+    /// Weights per line status.
+    Weights,
+    /// Weight of a risk tier.
+    TierWeight(Symbol),
+    /// Outstanding balance per `(tier, status)`.
+    Balances,
+}
+
+pub fn weights(env: &Env) -> RiskWeights {
+    env.storage()
+        .instance()
+        .get(&RwaKey::Weights)
+        .unwrap_or(RiskWeights {
+            active_bps: BPS_DENOMINATOR as u32,
+            suspended_bps: BPS_DENOMINATOR as u32,
+            defaulted_bps: BPS_DENOMINATOR as u32,
+            closed_bps: BPS_DENOMINATOR as u32,
+        })
+}
+
+pub fn set_weights(env: &Env, weights: &RiskWeights) {
+    env.storage().instance().set(&RwaKey::Weights, weights);
+}
+
+pub fn tier_weight(env: &Env, tier: &Symbol) -> Option<u32> {
+    env.storage()
+        .instance()
+        .get(&RwaKey::TierWeight(tier.clone()))
+}
+
+pub fn set_tier_weight(env: &Env, tier: &Symbol, weight_bps: Option<u32>) {
+    let key = RwaKey::TierWeight(tier.clone());
+    match weight_bps {
+        Some(weight_bps) => env.storage().instance().set(&key, &weight_bps),
+        None => env.storage().instance().remove(&key),
+    }
+}
+
+/// Outstanding balance (principal plus accrued interest) per `(tier, status)`.
+pub fn balances(env: &Env) -> Map<(Option<Symbol>, CreditStatus), i128> {
+    env.storage()
+        .instance()
+        .get(&RwaKey::Balances)
+        .unwrap_or(Map::new(env))
+}
+
+/// Add (`sign = 1`) or remove (`sign = -1`) a line's balance from its bucket.
+fn shift(
+    balances: &mut Map<(Option<Symbol>, CreditStatus), i128>,
+    tier: Option<Symbol>,
+    line: &CreditLineData,
+    sign: i128,
+) {
+    let amount = line.utilized_amount + line.accrued_interest;
+    if amount == 0 {
+        return;
+    }
+    let key = (tier, line.status);
+    let balance = balances.get(key.clone()).unwrap_or(0) + sign * amount;
+    if balance == 0 {
+        balances.remove(key);
+    } else {
+        balances.set(key, balance);
+    }
+}
+
+/// Record the transition of a line from `before` (None when newly created) to `after`.
+pub fn record_line_change(env: &Env, before: Option<&CreditLineData>, after: &CreditLineData) {
+    // Tiers are assigned to the borrower's line in the liquidity token only.
+    let tier = match after.asset {
+        None => tiers::line_tier(env, &after.borrower),
+        Some(_) => None,
+    };
+    let mut balances = balances(env);
+    if let Some(before) = before {
+        shift(&mut balances, tier.clone(), before, -1);
+    }
+    shift(&mut balances, tier, after, 1);
+    env.storage().instance().set(&RwaKey::Balances, &balances);
+}
+
+/// Record the line's tier, moving its balance to the new tier's bucket.
+pub fn set_line_tier(env: &Env, borrower: &Address, tier: Option<&Symbol>) {
+    let Some(line) = ttl::read_line(env, borrower, None) else {
+        tiers::set_line_tier(env, borrower, tier);
+        return;
+    };
+    let mut balances = balances(env);
+    shift(&mut balances, tiers::line_tier(env, borrower), &line, -1);
+    shift(&mut balances, tier.cloned(), &line, 1);
+    env.storage().instance().set(&RwaKey::Balances, &balances);
+    tiers::set_line_tier(env, borrower, tier);
+}
+
+/// Outstanding balance of the book and its risk-weighted amount.
+pub fn risk_weighted_assets(env: &Env) -> RiskWeightedAssets {
+    let weights = weights(env);
+    let mut result = RiskWeightedAssets {
+        exposure: 0,
+        risk_weighted: 0,
+    };
+    for ((tier, status), balance) in balances(env).iter() {
+        let status_bps = match status {
+            CreditStatus::Active => weights.active_bps,
+            CreditStatus::Suspended => weights.suspended_bps,
+            CreditStatus::Defaulted => weights.defaulted_bps,
+            CreditStatus::Closed => weights.closed_bps,
+        };
+        let tier_bps = tier
+            .and_then(|tier| tier_weight(env, &tier))
+            .unwrap_or(BPS_DENOMINATOR as u32);
+        result.exposure += balance;
+        result.risk_weighted +=
+            balance * tier_bps as i128 * status_bps as i128 / (BPS_DENOMINATOR * BPS_DENOMINATOR);
+    }
+    result
+}
@@ -0,0 +1,42 @@
+//! Future-dated draws. A borrower schedules a draw for a ledger time; once it is due a keeper
+//! executes it, provided the line is still healthy.
+
+use soroban_sdk::{contracttype, Env};
+
+use crate::types::ScheduledDraw;
+use crate::CreditError;
+
+/// Storage keys for scheduled draws.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ScheduleKey {
+    /// Id assigned to the next scheduled draw.
+    NextScheduleId,
+    /// Draw waiting for its execution time.
+    Draw(u32),
+}
+
+pub fn get(env: &Env, id: u32) -> Option<ScheduledDraw> {
+    env.storage().persistent().get(&ScheduleKey::Draw(id))
+}
+
+/// Store `draw` under a fresh id and return the id.
+pub fn add(env: &Env, draw: &ScheduledDraw) -> u32 {
+    let id: u32 = env
+        .storage()
+        .instance()
+        .get(&ScheduleKey::NextScheduleId)
+        .unwrap_or(0);
+    env.storage()
+        .instance()
+        .set(&ScheduleKey::NextScheduleId, &(id + 1));
+    env.storage().persistent().set(&ScheduleKey::Draw(id), draw);
+    id
+}
+
+/// Remove and return a scheduled draw.
+pub fn take(env: &Env, id: u32) -> Result<ScheduledDraw, CreditError> {
+    let draw = get(env, id).ok_or(CreditError::NotFound)?;
+    env.storage().persistent().remove(&ScheduleKey::Draw(id));
+    Ok(draw)
+}
@@ -0,0 +1,85 @@
+//! Settlement delay for large draws. Draws above the configured threshold are queued instead of
+//! disbursed; the borrower or the guardian can cancel them during the delay window, after which
+//! a keeper releases them through the normal draw path.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::types::{DrawSettlementConfig, PendingDraw};
+use crate::CreditError;
+
+/// Storage keys for settlement state.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SettlementKey {
+    /// Threshold and delay for settling large draws.
+    Config,
+    /// Address allowed to cancel pending draws (fraud stop).
+    Guardian,
+    /// Id assigned to the next pending draw.
+    NextSettlementId,
+    /// Large draw waiting out its settlement delay.
+    Pending(u32),
+}
+
+pub fn config(env: &Env) -> Option<DrawSettlementConfig> {
+    env.storage().instance().get(&SettlementKey::Config)
+}
+
+pub fn set_config(env: &Env, config: Option<DrawSettlementConfig>) {
+    match config {
+        Some(config) => env
+            .storage()
+            .instance()
+            .set(&SettlementKey::Config, &config),
+        None => env.storage().instance().remove(&SettlementKey::Config),
+    }
+}
+
+/// Settlement delay a draw of `amount` must wait out, if any.
+pub fn requires_delay(env: &Env, amount: i128) -> Option<u64> {
+    let config = config(env)?;
+    (amount > config.threshold).then_some(config.delay_secs)
+}
+
+pub fn guardian(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&SettlementKey::Guardian)
+}
+
+pub fn set_guardian(env: &Env, guardian: Option<Address>) {
+    match guardian {
+        Some(guardian) => env
+            .storage()
+            .instance()
+            .set(&SettlementKey::Guardian, &guardian),
+        None => env.storage().instance().remove(&SettlementKey::Guardian),
+    }
+}
+
+pub fn get_pending(env: &Env, id: u32) -> Option<PendingDraw> {
+    env.storage().persistent().get(&SettlementKey::Pending(id))
+}
+
+/// Store `pending` under a fresh id and return the id.
+pub fn enqueue(env: &Env, pending: &PendingDraw) -> u32 {
+    let id: u32 = env
+        .storage()
+        .instance()
+        .get(&SettlementKey::NextSettlementId)
+        .unwrap_or(0);
+    env.storage()
+        .instance()
+        .set(&SettlementKey::NextSettlementId, &(id + 1));
+    env.storage()
+        .persistent()
+        .set(&SettlementKey::Pending(id), pending);
+    id
+}
+
+/// Remove and return a pending draw.
+pub fn take(env: &Env, id: u32) -> Result<PendingDraw, CreditError> {
+    let pending = get_pending(env, id).ok_or(CreditError::NotFound)?;
+    env.storage()
+        .persistent()
+        .remove(&SettlementKey::Pending(id));
+    Ok(pending)
+}
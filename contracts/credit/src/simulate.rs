@@ -0,0 +1,191 @@
+//! Dry-run views for draws, repayments and repayment plans. They mirror the checks of `draw_credit`
+//! and `repay_credit` without touching state, and report the first check that would fail as a short
+//! reason code instead of returning an error.
+//!
+//! Simulations work from the line as stored: interest accrued since `last_accrual_ts` (and any
+//! capitalization it would trigger) is not included.
+
+use soroban_sdk::{symbol_short, token, Address, Env};
+
+use crate::types::{CreditStatus, DrawSimulation, PlanSimulation, RepaySimulation};
+use crate::{
+    available_liquidity, blacklist, boost_extra, defaulted, draw_fee, exposure, interest, kyc,
+    line_frozen, line_token, maturity, obligor_frozen, opfreeze, pool_for_token, rounding,
+    settlement, staleness, term, ttl, velocity, waterfall,
+};
+
+/// A plan month: a twelfth of the 365-day year used for interest.
+const SECONDS_PER_MONTH: u64 = interest::SECONDS_PER_YEAR / 12;
+
+/// Plans longer than this (50 years) are reported as `too_long`.
+const MAX_PLAN_MONTHS: u32 = 600;
+
+/// Simulate `draw_credit(borrower, amount)`.
+pub fn draw(env: &Env, borrower: &Address, amount: i128) -> DrawSimulation {
+    let mut sim = DrawSimulation {
+        error: None,
+        new_utilized_amount: 0,
+        fee: 0,
+        disbursed: 0,
+        queued: false,
+    };
+    let Some(line) = ttl::read_line(env, borrower, None) else {
+        sim.error = Some(symbol_short!("no_line"));
+        return sim;
+    };
+    sim.new_utilized_amount = line.utilized_amount;
+    let boost = boost_extra(env, borrower, None);
+    let token_address = line_token(env, &line);
+    let (fee, capitalized_fee) = draw_fee(env, &line, amount);
+    let charged = amount.saturating_add(capitalized_fee);
+    let error = if amount <= 0 {
+        Some(symbol_short!("amount"))
+    } else if line.status == CreditStatus::Closed {
+        Some(symbol_short!("closed"))
+    } else if line.status == CreditStatus::Defaulted {
+        Some(symbol_short!("defaulted"))
+    } else if blacklist::is_blocked(env, borrower) {
+        Some(symbol_short!("blacklist"))
+    } else if !kyc::is_allowed(env, borrower) {
+        Some(symbol_short!("kyc"))
+    } else if obligor_frozen(env, borrower) {
+        Some(symbol_short!("obl_frz"))
+    } else if line_frozen(env, borrower, None) {
+        Some(symbol_short!("frozen"))
+    } else if opfreeze::effective(env, borrower, None).draws {
+        Some(symbol_short!("op_frz"))
+    } else if staleness::blocks_draw(env, borrower) {
+        Some(symbol_short!("stale"))
+    } else if term::get_term(env, borrower, None).is_some() {
+        Some(symbol_short!("term_loan"))
+    } else if maturity::is_past_maturity(env, borrower, None) {
+        Some(symbol_short!("matured"))
+    } else if velocity::cooldown_until(env, &line).is_some() {
+        Some(symbol_short!("cooldown"))
+    } else if line.utilized_amount.saturating_add(charged) > line.credit_limit + boost {
+        Some(symbol_short!("limit"))
+    } else if velocity::exceeds(env, borrower, None, amount) {
+        Some(symbol_short!("velocity"))
+    } else if exposure::draw_exceeds(env, None, charged) {
+        Some(symbol_short!("exp_cap"))
+    } else if settlement::requires_delay(env, amount).is_none()
+        && available_liquidity(env, &line).is_some_and(|available| available < charged)
+    {
+        Some(symbol_short!("liquidity"))
+    } else {
+        None
+    };
+    if error.is_some() {
+        sim.error = error;
+        return sim;
+    }
+    sim.queued = settlement::requires_delay(env, amount).is_some();
+    sim.fee = fee;
+    sim.new_utilized_amount = line.utilized_amount + charged;
+    sim.disbursed = match token_address {
+        Some(_) => rounding::split(env, borrower, charged - fee).0,
+        None => charged - fee,
+    };
+    sim
+}
+
+/// Simulate `repay_credit(borrower, amount)`.
+pub fn repay(env: &Env, borrower: &Address, amount: i128) -> RepaySimulation {
+    let mut sim = RepaySimulation {
+        error: None,
+        fees_paid: 0,
+        interest_paid: 0,
+        principal_paid: 0,
+        new_utilized_amount: 0,
+        closes_line: false,
+    };
+    let Some(line) = ttl::read_line(env, borrower, None) else {
+        sim.error = Some(symbol_short!("no_line"));
+        return sim;
+    };
+    sim.new_utilized_amount = line.utilized_amount;
+    if line.status == CreditStatus::Closed {
+        sim.error = Some(symbol_short!("closed"));
+        return sim;
+    }
+    if line.status == CreditStatus::Defaulted && !defaulted::accepts_repayments(env) {
+        sim.error = Some(symbol_short!("defaulted"));
+        return sim;
+    }
+    if opfreeze::effective(env, borrower, None).repayments {
+        sim.error = Some(symbol_short!("op_frz"));
+        return sim;
+    }
+    if amount <= 0 {
+        sim.error = Some(symbol_short!("amount"));
+        return sim;
+    }
+    let split = waterfall::split(env, &line, amount);
+    let interest_paid = split.interest;
+    let new_utilized = line.utilized_amount - split.utilized();
+    let paid = split.total();
+
+    // Pool-funded lines pull the applied amount from the borrower.
+    let token_address = line_token(env, &line);
+    if let Some(token_address) = token_address.filter(|t| pool_for_token(env, t).is_some()) {
+        if token::Client::new(env, &token_address).balance(borrower) < paid {
+            sim.error = Some(symbol_short!("balance"));
+            return sim;
+        }
+    }
+    sim.fees_paid = split.fees;
+    sim.interest_paid = interest_paid;
+    sim.principal_paid = split.principal;
+    sim.new_utilized_amount = new_utilized;
+    sim.closes_line = term::get_term(env, borrower, None).is_some()
+        && new_utilized + line.accrued_interest - interest_paid <= 0
+        && line.status.can_transition_to(CreditStatus::Closed);
+    sim
+}
+
+/// Project paying `monthly_payment` at the end of every month at the line's current rate.
+/// Each month accrues simple interest on the principal, and each payment goes to interest
+/// first, as in `repay_credit`. Penalty interest and rate changes are not projected.
+pub fn plan(env: &Env, borrower: &Address, monthly_payment: i128) -> PlanSimulation {
+    let mut sim = PlanSimulation {
+        error: None,
+        months: 0,
+        total_interest: 0,
+        total_paid: 0,
+    };
+    let Some(line) = ttl::read_line(env, borrower, None) else {
+        sim.error = Some(symbol_short!("no_line"));
+        return sim;
+    };
+    if monthly_payment <= 0 {
+        sim.error = Some(symbol_short!("amount"));
+        return sim;
+    }
+    let mut principal = line.utilized_amount;
+    let mut accrued = line.accrued_interest;
+    while principal > 0 || accrued > 0 {
+        if sim.months == MAX_PLAN_MONTHS {
+            sim.error = Some(symbol_short!("too_long"));
+            return sim;
+        }
+        let Ok(month_interest) =
+            interest::simple_interest(principal, line.interest_rate_bps, SECONDS_PER_MONTH)
+        else {
+            sim.error = Some(symbol_short!("overflow"));
+            return sim;
+        };
+        if month_interest >= monthly_payment {
+            sim.error = Some(symbol_short!("too_low"));
+            return sim;
+        }
+        accrued += month_interest;
+        let payment = monthly_payment.min(principal + accrued);
+        let interest_paid = payment.min(accrued);
+        accrued -= interest_paid;
+        principal -= payment - interest_paid;
+        sim.months += 1;
+        sim.total_interest += interest_paid;
+        sim.total_paid += payment;
+    }
+    sim
+}
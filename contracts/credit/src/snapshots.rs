@@ -0,0 +1,51 @@
+//! Portfolio snapshots: point-in-time summaries of the book, recorded on demand under
+//! sequential ids so period-end reports can be read straight from chain state.
+
+use soroban_sdk::{contracttype, Env};
+
+use crate::types::PortfolioSnapshot;
+use crate::{borrow_index, rwa, stats, ttl, CreditError};
+
+/// Storage keys for snapshots.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SnapshotKey {
+    /// Number of snapshots taken; also the id of the next one.
+    Count,
+    Snapshot(u32),
+}
+
+pub fn count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&SnapshotKey::Count)
+        .unwrap_or(0)
+}
+
+pub fn get(env: &Env, id: u32) -> Option<PortfolioSnapshot> {
+    env.storage().persistent().get(&SnapshotKey::Snapshot(id))
+}
+
+/// Record a snapshot of the current aggregates and return it. Accrued interest is taken from
+/// the outstanding balances tracked for risk-weighted assets, less the utilized principal, plus
+/// the interest the book index has accrued since the lines last settled, checkpointing it.
+pub fn take(env: &Env) -> Result<PortfolioSnapshot, CreditError> {
+    let stats = stats::get_stats(env, None);
+    let outstanding: i128 = rwa::balances(env).values().iter().sum();
+    let snapshot = PortfolioSnapshot {
+        id: count(env),
+        taken_at: env.ledger().timestamp(),
+        total_accrued_interest: outstanding - stats.total_utilized
+            + borrow_index::bump(env)?.pending_interest,
+        stats,
+    };
+    let key = SnapshotKey::Snapshot(snapshot.id);
+    env.storage().persistent().set(&key, &snapshot);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ttl::LINE_BUMP_THRESHOLD, ttl::LINE_BUMP_AMOUNT);
+    env.storage()
+        .instance()
+        .set(&SnapshotKey::Count, &(snapshot.id + 1));
+    Ok(snapshot)
+}
@@ -0,0 +1,45 @@
+//! Risk score staleness. Scores older than a configured maximum age are flagged as stale, and
+//! new draws can optionally be blocked until the risk engine refreshes the score.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::types::{RiskScoreRecord, ScoreAgePolicy};
+use crate::DataKey;
+
+/// Storage keys for the staleness policy.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StalenessKey {
+    Policy,
+}
+
+pub fn policy(env: &Env) -> Option<ScoreAgePolicy> {
+    env.storage().instance().get(&StalenessKey::Policy)
+}
+
+pub fn set_policy(env: &Env, policy: Option<ScoreAgePolicy>) {
+    match policy {
+        Some(policy) => env.storage().instance().set(&StalenessKey::Policy, &policy),
+        None => env.storage().instance().remove(&StalenessKey::Policy),
+    }
+}
+
+/// Whether the borrower's score is older than the policy's maximum age. A line without a
+/// recorded score is stale once a policy is set; without a policy no score is stale.
+pub fn is_stale(env: &Env, borrower: &Address) -> bool {
+    let Some(policy) = policy(env) else {
+        return false;
+    };
+    let record: Option<RiskScoreRecord> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::RiskScoreRecord(borrower.clone()));
+    record.is_none_or(|record| {
+        env.ledger().timestamp().saturating_sub(record.scored_at) > policy.max_age_secs
+    })
+}
+
+/// Whether draws on the borrower's line are blocked by a stale score.
+pub fn blocks_draw(env: &Env, borrower: &Address) -> bool {
+    policy(env).is_some_and(|policy| policy.block_draws) && is_stale(env, borrower)
+}
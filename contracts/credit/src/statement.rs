@@ -0,0 +1,131 @@
+//! Statements: per-cycle line figures rebuilt from the line history, optionally converted into
+//! a reference currency at current price-oracle rates.
+
+use soroban_sdk::{contracttype, symbol_short, Address, Env};
+
+use crate::types::{BalanceStatement, Statement};
+use crate::{history, keeper, line_token, maturity, price, promo, summary, ttl, CreditError};
+
+/// Length of a statement cycle, counted from the line's opening.
+pub const CYCLE_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Storage keys for statement reporting.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StatementKey {
+    /// Price oracle used by `get_statement_in` and `repay_credit_in`.
+    PriceOracle,
+}
+
+pub fn price_oracle(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&StatementKey::PriceOracle)
+}
+
+pub fn set_price_oracle(env: &Env, oracle: Option<Address>) {
+    match oracle {
+        Some(oracle) => env
+            .storage()
+            .instance()
+            .set(&StatementKey::PriceOracle, &oracle),
+        None => env.storage().instance().remove(&StatementKey::PriceOracle),
+    }
+}
+
+/// Statement for cycle `cycle` of the borrower's current line, in the line's asset. `None` if
+/// there is no line, its asset is unknown, or the cycle has not started.
+pub fn build(env: &Env, borrower: &Address, cycle: u32) -> Option<Statement> {
+    let line = ttl::load_line(env, borrower, None)?;
+    let asset = line_token(env, &line)?;
+    let opened_at = summary::get_summary(env, borrower, None)?.opened_at;
+    let now = env.ledger().timestamp();
+    let start_ts = opened_at.checked_add(CYCLE_SECS.checked_mul(cycle as u64)?)?;
+    if start_ts > now {
+        return None;
+    }
+    let end_ts = start_ts
+        .saturating_add(CYCLE_SECS)
+        .min(now.saturating_add(1));
+
+    let first = history::first_at_or_after(env, borrower, None, start_ts);
+    let opening_balance = match (cycle, first) {
+        (0, _) | (_, 0) => 0,
+        _ => history::get(env, borrower, None, first - 1).map_or(0, |entry| entry.utilized_amount),
+    };
+    let mut statement = Statement {
+        cycle,
+        asset,
+        start_ts,
+        end_ts,
+        opening_balance,
+        drawn: 0,
+        repaid: 0,
+        closing_balance: opening_balance,
+    };
+    let mut index = first;
+    while let Some(entry) = history::get(env, borrower, None, index) {
+        if entry.timestamp >= end_ts {
+            break;
+        }
+        if entry.action == symbol_short!("drawn") {
+            statement.drawn += entry.amount;
+        } else if entry.action == symbol_short!("repay") {
+            statement.repaid += entry.amount;
+        }
+        statement.closing_balance = entry.utilized_amount;
+        index += 1;
+    }
+    Some(statement)
+}
+
+/// Current balance breakdown of the borrower's line, from the figures the contract itself
+/// charges against. `None` if there is no line.
+pub fn balance(env: &Env, borrower: &Address) -> Option<BalanceStatement> {
+    let line = ttl::load_line(env, borrower, None)?;
+    let due = maturity::get_due(env, borrower, None)
+        .filter(|due| due.amount > 0 && !maturity::below_grace_amount(env, &line));
+    let now = env.ledger().timestamp();
+    let days_past_due = due
+        .as_ref()
+        .and_then(|due| now.checked_sub(due.due_ts))
+        .map_or(0, |late| (late / keeper::DAY_SECS) as u32);
+    Some(BalanceStatement {
+        status: line.status,
+        principal: line.utilized_amount,
+        accrued_interest: line.accrued_interest,
+        deferred_interest: promo::get(env, borrower).map_or(0, |promo| promo.deferred_interest),
+        fees_charged: summary::get_summary(env, borrower, None)
+            .map_or(0, |summary| summary.fees_paid),
+        total_outstanding: line.utilized_amount + line.accrued_interest,
+        next_due_ts: due.as_ref().map(|due| due.due_ts),
+        minimum_payment: due.map_or(0, |due| due.amount),
+        days_past_due,
+        as_of: line.last_accrual_ts,
+    })
+}
+
+/// `statement` with its figures converted into `quote_asset` at the oracle's latest prices,
+/// adjusting for the two tokens' decimals. Rounds down.
+pub fn convert(
+    env: &Env,
+    statement: Statement,
+    quote_asset: Address,
+) -> Result<Statement, CreditError> {
+    if statement.asset == quote_asset {
+        return Ok(statement);
+    }
+    let oracle = price_oracle(env).ok_or(CreditError::NotConfigured)?;
+    let (numerator, denominator) = price::rate(env, &oracle, &statement.asset, &quote_asset)?;
+    let at_rate = |amount: i128| -> Result<i128, CreditError> {
+        Ok(amount.checked_mul(numerator).ok_or(CreditError::Overflow)? / denominator)
+    };
+    Ok(Statement {
+        cycle: statement.cycle,
+        asset: quote_asset,
+        start_ts: statement.start_ts,
+        end_ts: statement.end_ts,
+        opening_balance: at_rate(statement.opening_balance)?,
+        drawn: at_rate(statement.drawn)?,
+        repaid: at_rate(statement.repaid)?,
+        closing_balance: at_rate(statement.closing_balance)?,
+    })
+}
@@ -0,0 +1,149 @@
+//! Protocol-wide aggregate statistics, maintained incrementally on every credit line write.
+//! Lines in the liquidity token make up the protocol's book; lines in each other asset are
+//! aggregated separately under that asset.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::types::{CreditLineData, CreditStatus, ProtocolStats};
+use crate::{ttl, CreditError, DataKey};
+
+/// Storage keys for the aggregates of lines in an asset other than the liquidity token.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StatsKey {
+    AssetStats(Address),
+}
+
+/// Aggregates of the lines in `asset`, or of the book in the liquidity token for `None`.
+pub fn get_stats(env: &Env, asset: Option<&Address>) -> ProtocolStats {
+    let stats = match asset {
+        None => env.storage().instance().get(&DataKey::ProtocolStats),
+        Some(asset) => env
+            .storage()
+            .persistent()
+            .get(&StatsKey::AssetStats(asset.clone())),
+    };
+    stats.unwrap_or(ProtocolStats {
+        total_credit_limit: 0,
+        total_utilized: 0,
+        total_drawn: 0,
+        total_repaid: 0,
+        active_count: 0,
+        suspended_count: 0,
+        defaulted_count: 0,
+        closed_count: 0,
+        cumulative_defaults: 0,
+        total_written_off: 0,
+        total_recovered: 0,
+    })
+}
+
+pub fn set_stats(env: &Env, asset: Option<&Address>, stats: &ProtocolStats) {
+    match asset {
+        None => env.storage().instance().set(&DataKey::ProtocolStats, stats),
+        Some(asset) => {
+            let key = StatsKey::AssetStats(asset.clone());
+            env.storage().persistent().set(&key, stats);
+            env.storage().persistent().extend_ttl(
+                &key,
+                ttl::LINE_BUMP_THRESHOLD,
+                ttl::LINE_BUMP_AMOUNT,
+            );
+        }
+    }
+}
+
+/// Add (`sign = 1`) or remove (`sign = -1`) a line's contribution to the aggregates.
+/// Closed lines count towards `closed_count` and `total_utilized` but not the committed limit.
+fn apply_line(stats: &mut ProtocolStats, line: &CreditLineData, sign: i32) {
+    let count = match line.status {
+        CreditStatus::Active => &mut stats.active_count,
+        CreditStatus::Suspended => &mut stats.suspended_count,
+        CreditStatus::Defaulted => &mut stats.defaulted_count,
+        CreditStatus::Closed => &mut stats.closed_count,
+    };
+    *count = count.saturating_add_signed(sign);
+    if line.status != CreditStatus::Closed {
+        stats.total_credit_limit += sign as i128 * line.credit_limit;
+    }
+    stats.total_utilized += sign as i128 * line.utilized_amount;
+}
+
+/// Apply the transition of a line from `before` (None when newly created) to `after` to
+/// `stats` in memory. Batch operations accumulate every entry this way and save once.
+pub fn apply_line_change(
+    stats: &mut ProtocolStats,
+    before: Option<&CreditLineData>,
+    after: &CreditLineData,
+) {
+    if let Some(before) = before {
+        apply_line(stats, before, -1);
+    }
+    apply_line(stats, after, 1);
+    if after.status == CreditStatus::Defaulted
+        && before.map(|b| b.status) != Some(CreditStatus::Defaulted)
+    {
+        stats.cumulative_defaults += 1;
+    }
+}
+
+/// Count a line that predates the aggregates, without recording its status as a new default.
+pub fn apply_existing_line(stats: &mut ProtocolStats, line: &CreditLineData) {
+    apply_line(stats, line, 1);
+}
+
+/// Record cumulative drawn volume.
+pub fn record_draw(env: &Env, asset: Option<&Address>, amount: i128) -> Result<(), CreditError> {
+    let mut stats = get_stats(env, asset);
+    stats.total_drawn = stats
+        .total_drawn
+        .checked_add(amount)
+        .ok_or(CreditError::Overflow)?;
+    set_stats(env, asset, &stats);
+    Ok(())
+}
+
+/// Record cumulative repaid volume (interest and principal).
+pub fn record_repayment(
+    env: &Env,
+    asset: Option<&Address>,
+    amount: i128,
+) -> Result<(), CreditError> {
+    let mut stats = get_stats(env, asset);
+    stats.total_repaid = stats
+        .total_repaid
+        .checked_add(amount)
+        .ok_or(CreditError::Overflow)?;
+    set_stats(env, asset, &stats);
+    Ok(())
+}
+
+/// Record cumulative written-off principal.
+pub fn record_write_off(
+    env: &Env,
+    asset: Option<&Address>,
+    principal: i128,
+) -> Result<(), CreditError> {
+    let mut stats = get_stats(env, asset);
+    stats.total_written_off = stats
+        .total_written_off
+        .checked_add(principal)
+        .ok_or(CreditError::Overflow)?;
+    set_stats(env, asset, &stats);
+    Ok(())
+}
+
+/// Record cumulative recovered written-off principal.
+pub fn record_recovery(
+    env: &Env,
+    asset: Option<&Address>,
+    amount: i128,
+) -> Result<(), CreditError> {
+    let mut stats = get_stats(env, asset);
+    stats.total_recovered = stats
+        .total_recovered
+        .checked_add(amount)
+        .ok_or(CreditError::Overflow)?;
+    set_stats(env, asset, &stats);
+    Ok(())
+}
@@ -0,0 +1,119 @@
+//! Per-line lifetime aggregates, published as a final `line_summary` event when a line closes
+//! so downstream warehouses need not replay the line's full event history.
+
+use soroban_sdk::{Address, Env, Val};
+
+use crate::events::{publish_line_summary, LineSummaryEvent};
+use crate::types::{CreditLineData, LineSummary};
+use crate::{ttl, DataKey};
+
+fn key(env: &Env, borrower: &Address, asset: Option<&Address>) -> Val {
+    ttl::entry_key(env, DataKey::LineSummary(borrower.clone()), asset)
+}
+
+pub fn get_summary(env: &Env, borrower: &Address, asset: Option<&Address>) -> Option<LineSummary> {
+    env.storage().persistent().get(&key(env, borrower, asset))
+}
+
+/// Summary for a line; lines opened before summaries were tracked start from an empty record.
+fn load(env: &Env, borrower: &Address, asset: Option<&Address>) -> LineSummary {
+    get_summary(env, borrower, asset).unwrap_or(LineSummary {
+        opened_at: 0,
+        total_drawn: 0,
+        total_repaid: 0,
+        interest_accrued: 0,
+        interest_paid: 0,
+        fees_paid: 0,
+        max_utilization: 0,
+        delinquency_count: 0,
+        written_off: 0,
+        recovered: 0,
+    })
+}
+
+fn update(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+    f: impl FnOnce(&mut LineSummary),
+) {
+    let mut summary = load(env, borrower, asset);
+    f(&mut summary);
+    env.storage()
+        .persistent()
+        .set(&key(env, borrower, asset), &summary);
+}
+
+/// Start a fresh summary for a newly opened line.
+pub fn start(env: &Env, borrower: &Address, asset: Option<&Address>) {
+    env.storage()
+        .persistent()
+        .remove(&key(env, borrower, asset));
+    let opened_at = env.ledger().timestamp();
+    update(env, borrower, asset, |s| s.opened_at = opened_at);
+}
+
+pub fn record_draw(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+    amount: i128,
+    utilized: i128,
+    fee: i128,
+) {
+    update(env, borrower, asset, |s| {
+        s.total_drawn += amount;
+        s.fees_paid += fee;
+        s.max_utilization = s.max_utilization.max(utilized);
+    });
+}
+
+pub fn record_repayment(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+    interest: i128,
+    principal: i128,
+) {
+    update(env, borrower, asset, |s| {
+        s.total_repaid += interest + principal;
+        s.interest_paid += interest;
+    });
+}
+
+pub fn record_accrual(env: &Env, borrower: &Address, asset: Option<&Address>, interest: i128) {
+    update(env, borrower, asset, |s| s.interest_accrued += interest);
+}
+
+/// Track peak utilization after principal grows without a draw (capitalization).
+pub fn record_utilization(env: &Env, borrower: &Address, asset: Option<&Address>, utilized: i128) {
+    update(env, borrower, asset, |s| {
+        s.max_utilization = s.max_utilization.max(utilized)
+    });
+}
+
+pub fn record_delinquency(env: &Env, borrower: &Address, asset: Option<&Address>) {
+    update(env, borrower, asset, |s| s.delinquency_count += 1);
+}
+
+pub fn record_write_off(env: &Env, borrower: &Address, asset: Option<&Address>, principal: i128) {
+    update(env, borrower, asset, |s| s.written_off += principal);
+}
+
+pub fn record_recovery(env: &Env, borrower: &Address, asset: Option<&Address>, amount: i128) {
+    update(env, borrower, asset, |s| s.recovered += amount);
+}
+
+/// Publish the final `(credit, line_summary)` event for a line that has just closed.
+pub fn publish_final(env: &Env, line: &CreditLineData) {
+    publish_line_summary(
+        env,
+        LineSummaryEvent {
+            borrower: line.borrower.clone(),
+            closed_at: env.ledger().timestamp(),
+            outstanding_principal: line.utilized_amount,
+            outstanding_interest: line.accrued_interest,
+            summary: load(env, &line.borrower, line.asset.as_ref()),
+        },
+    );
+}
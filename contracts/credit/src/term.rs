@@ -0,0 +1,58 @@
+//! Term loans: a restructured line repaid in fixed installments instead of revolving.
+//!
+//! Each installment is posted as the line's `PaymentDue`, so late installments accrue at the
+//! penalty rate like any other overdue payment. Once an installment is paid the next one is
+//! posted; when the balance reaches zero the line is closed automatically.
+
+use soroban_sdk::{Address, Env, Val};
+
+use crate::types::{CreditLineData, PaymentDue, TermLoan};
+use crate::{maturity, ttl, DataKey};
+
+fn key(env: &Env, borrower: &Address, asset: Option<&Address>) -> Val {
+    ttl::entry_key(env, DataKey::TermLoan(borrower.clone()), asset)
+}
+
+pub fn get_term(env: &Env, borrower: &Address, asset: Option<&Address>) -> Option<TermLoan> {
+    env.storage().persistent().get(&key(env, borrower, asset))
+}
+
+pub fn set_term(env: &Env, borrower: &Address, asset: Option<&Address>, term: Option<TermLoan>) {
+    let key = key(env, borrower, asset);
+    match term {
+        Some(term) => env.storage().persistent().set(&key, &term),
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+/// Outstanding principal and interest on a line.
+pub fn balance(line: &CreditLineData) -> i128 {
+    line.utilized_amount + line.accrued_interest
+}
+
+/// Post the next installment once the current one is paid. Returns true when the loan is
+/// fully repaid and the line should be closed. No-op for revolving lines.
+pub fn advance(env: &Env, line: &CreditLineData) -> bool {
+    let asset = line.asset.as_ref();
+    let Some(mut term) = get_term(env, &line.borrower, asset) else {
+        return false;
+    };
+    let remaining = balance(line);
+    if remaining <= 0 {
+        return true;
+    }
+    if maturity::get_due(env, &line.borrower, asset).is_none() {
+        term.next_due_ts = term.next_due_ts.saturating_add(term.interval_secs);
+        maturity::set_due(
+            env,
+            &line.borrower,
+            asset,
+            Some(PaymentDue {
+                amount: term.installment_amount.min(remaining),
+                due_ts: term.next_due_ts,
+            }),
+        );
+        set_term(env, &line.borrower, asset, Some(term));
+    }
+    false
+}
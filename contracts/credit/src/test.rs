@@ -13,7 +13,7 @@ fn test_init_and_open_credit_line() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.open_credit_line(&borrower, &1000_i128, &300_u32, &70_u32);
 
     // Verify credit line was created
@@ -39,7 +39,7 @@ fn test_suspend_credit_line() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.open_credit_line(&borrower, &1000_i128, &300_u32, &70_u32);
     client.suspend_credit_line(&borrower);
 
@@ -59,7 +59,7 @@ fn test_close_credit_line() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.open_credit_line(&borrower, &1000_i128, &300_u32, &70_u32);
     client.close_credit_line(&borrower);
 
@@ -79,7 +79,7 @@ fn test_default_credit_line() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.open_credit_line(&borrower, &1000_i128, &300_u32, &70_u32);
     client.default_credit_line(&borrower);
 
@@ -99,7 +99,7 @@ fn test_full_lifecycle() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
 
     // Open credit line
     client.open_credit_line(&borrower, &5000_i128, &500_u32, &80_u32);
@@ -128,7 +128,7 @@ fn test_event_data_integrity() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.open_credit_line(&borrower, &2000_i128, &400_u32, &75_u32);
 
     // Verify credit line data matches what was passed
@@ -150,7 +150,7 @@ fn test_suspend_nonexistent_credit_line() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.suspend_credit_line(&borrower);
 }
 
@@ -164,7 +164,7 @@ fn test_close_nonexistent_credit_line() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.close_credit_line(&borrower);
 }
 
@@ -178,7 +178,7 @@ fn test_default_nonexistent_credit_line() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.default_credit_line(&borrower);
 }
 
@@ -194,7 +194,7 @@ fn test_multiple_borrowers() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.open_credit_line(&borrower1, &1000_i128, &300_u32, &70_u32);
     client.open_credit_line(&borrower2, &2000_i128, &400_u32, &80_u32);
 
@@ -218,7 +218,7 @@ fn test_lifecycle_transitions() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
 
     // Test Active -> Defaulted
     client.open_credit_line(&borrower, &1000_i128, &300_u32, &70_u32);
@@ -245,7 +245,7 @@ fn test_open_credit_line_success() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.open_credit_line(&borrower, &1000_i128, &300_u32, &70_u32);
 
     let credit_line = client.get_credit_line(&borrower).unwrap();
@@ -268,7 +268,7 @@ fn test_open_credit_line_utilized_amount_starts_at_zero() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.open_credit_line(&borrower, &9999_i128, &500_u32, &50_u32);
 
     let credit_line = client.get_credit_line(&borrower).unwrap();
@@ -287,7 +287,7 @@ fn test_open_credit_line_boundary_interest_rate() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     // interest_rate_bps = 10000 (100%) is the max allowed
     client.open_credit_line(&borrower, &1000_i128, &10_000_u32, &50_u32);
 
@@ -306,7 +306,7 @@ fn test_open_credit_line_boundary_risk_score() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     // risk_score = 100 is the max allowed
     client.open_credit_line(&borrower, &1000_i128, &300_u32, &100_u32);
 
@@ -325,7 +325,7 @@ fn test_open_credit_line_minimum_credit_limit() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     // credit_limit = 1 is the minimum allowed
     client.open_credit_line(&borrower, &1_i128, &300_u32, &50_u32);
 
@@ -346,7 +346,7 @@ fn test_open_credit_line_rejects_zero_credit_limit() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     // credit_limit = 0 must be rejected
     client.open_credit_line(&borrower, &0_i128, &300_u32, &50_u32);
 }
@@ -363,7 +363,7 @@ fn test_open_credit_line_rejects_negative_credit_limit() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     // negative credit_limit must be rejected
     client.open_credit_line(&borrower, &-1_i128, &300_u32, &50_u32);
 }
@@ -380,7 +380,7 @@ fn test_open_credit_line_rejects_interest_rate_above_max() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     // interest_rate_bps = 10001 exceeds the 10000 cap
     client.open_credit_line(&borrower, &1000_i128, &10_001_u32, &50_u32);
 }
@@ -397,7 +397,7 @@ fn test_open_credit_line_rejects_risk_score_above_max() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     // risk_score = 101 exceeds the 100 cap
     client.open_credit_line(&borrower, &1000_i128, &300_u32, &101_u32);
 }
@@ -414,7 +414,7 @@ fn test_open_credit_line_rejects_duplicate_active_borrower() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.open_credit_line(&borrower, &1000_i128, &300_u32, &70_u32);
     // second call for same borrower while Active must panic
     client.open_credit_line(&borrower, &2000_i128, &400_u32, &60_u32);
@@ -431,7 +431,7 @@ fn test_open_credit_line_allowed_after_closed() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.open_credit_line(&borrower, &1000_i128, &300_u32, &70_u32);
     client.close_credit_line(&borrower);
 
@@ -454,7 +454,7 @@ fn test_open_credit_line_allowed_after_defaulted() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.open_credit_line(&borrower, &1000_i128, &300_u32, &70_u32);
     client.default_credit_line(&borrower);
 
@@ -477,7 +477,7 @@ fn test_open_credit_line_allowed_after_suspended() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.open_credit_line(&borrower, &1000_i128, &300_u32, &70_u32);
     client.suspend_credit_line(&borrower);
 
@@ -502,7 +502,7 @@ fn test_open_credit_line_multiple_independent_borrowers() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     client.open_credit_line(&borrower_a, &1000_i128, &300_u32, &70_u32);
     client.open_credit_line(&borrower_b, &2000_i128, &400_u32, &80_u32);
     client.open_credit_line(&borrower_c, &3000_i128, &500_u32, &90_u32);
@@ -533,7 +533,7 @@ fn test_get_credit_line_returns_none_for_unknown_borrower() {
     let contract_id = env.register(Credit, ());
     let client = CreditClient::new(&env, &contract_id);
 
-    client.init(&admin);
+    client.init(&admin, &InitConfig::default());
     // No credit line opened for this address
     assert!(client.get_credit_line(&unknown).is_none());
 }
\ No newline at end of file
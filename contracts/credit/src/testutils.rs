@@ -0,0 +1,212 @@
+//! Test scaffolding for contracts built on top of Creditra, enabled with the `testutils`
+//! feature: a mock risk oracle, a mock liquidity pool, and a fixture that deploys the credit
+//! contract wired to both, with helpers to open funded lines, accrue over time and force
+//! delinquency.
+
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::token::{self, StellarAssetClient};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+
+use crate::accrual;
+use crate::types::{CreditLineData, InitConfig};
+use crate::{Credit, CreditClient};
+
+pub use crate::oracle::OracleScore;
+
+/// Risk oracle whose scores are set directly by the test.
+#[contract]
+pub struct MockRiskOracle;
+
+#[contractimpl]
+impl MockRiskOracle {
+    /// Record `score` for `borrower`, timestamped with the current ledger time.
+    pub fn set_score(env: Env, borrower: Address, score: u32) {
+        let record = OracleScore {
+            score,
+            updated_at: env.ledger().timestamp(),
+        };
+        env.storage().persistent().set(&borrower, &record);
+    }
+
+    pub fn get_score(env: Env, borrower: Address) -> Option<OracleScore> {
+        env.storage().persistent().get(&borrower)
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum MockPoolKey {
+    Asset,
+    TargetYieldBps,
+    Borrowed,
+    InterestRepaid,
+    WrittenOff,
+}
+
+/// Liquidity pool lending its own balance of `asset` without access control, and tracking what
+/// the credit contract borrowed, repaid and wrote off.
+#[contract]
+pub struct MockPool;
+
+#[contractimpl]
+impl MockPool {
+    pub fn __constructor(env: Env, asset: Address) {
+        env.storage().instance().set(&MockPoolKey::Asset, &asset);
+    }
+
+    pub fn set_target_yield(env: Env, target_yield_bps: u32) {
+        env.storage()
+            .instance()
+            .set(&MockPoolKey::TargetYieldBps, &target_yield_bps);
+    }
+
+    pub fn asset(env: Env) -> Address {
+        env.storage().instance().get(&MockPoolKey::Asset).unwrap()
+    }
+
+    pub fn target_yield_bps(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&MockPoolKey::TargetYieldBps)
+            .unwrap_or(0)
+    }
+
+    pub fn available_liquidity(env: Env) -> i128 {
+        token::Client::new(&env, &Self::asset(env.clone())).balance(&env.current_contract_address())
+    }
+
+    pub fn borrow(env: Env, to: Address, amount: i128) {
+        token::Client::new(&env, &Self::asset(env.clone())).transfer(
+            &env.current_contract_address(),
+            &to,
+            &amount,
+        );
+        add(&env, MockPoolKey::Borrowed, amount);
+    }
+
+    pub fn repay(env: Env, principal: i128, interest: i128) {
+        add(&env, MockPoolKey::Borrowed, -principal);
+        add(&env, MockPoolKey::InterestRepaid, interest);
+    }
+
+    pub fn write_off(env: Env, amount: i128) {
+        add(&env, MockPoolKey::Borrowed, -amount);
+        add(&env, MockPoolKey::WrittenOff, amount);
+    }
+
+    /// Principal currently lent out.
+    pub fn borrowed(env: Env) -> i128 {
+        get(&env, MockPoolKey::Borrowed)
+    }
+
+    pub fn interest_repaid(env: Env) -> i128 {
+        get(&env, MockPoolKey::InterestRepaid)
+    }
+
+    pub fn written_off(env: Env) -> i128 {
+        get(&env, MockPoolKey::WrittenOff)
+    }
+}
+
+fn get(env: &Env, key: MockPoolKey) -> i128 {
+    env.storage().instance().get(&key).unwrap_or(0)
+}
+
+fn add(env: &Env, key: MockPoolKey, amount: i128) {
+    let total = get(env, key.clone()) + amount;
+    env.storage().instance().set(&key, &total);
+}
+
+/// Deploy a Stellar asset contract, returning its token and admin clients.
+pub fn create_token(env: &Env) -> (token::Client<'_>, StellarAssetClient<'_>) {
+    let asset = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    (
+        token::Client::new(env, &asset),
+        StellarAssetClient::new(env, &asset),
+    )
+}
+
+/// The credit contract deployed and initialized, lending from a funded `MockPool` and reading
+/// scores from a `MockRiskOracle`. All authorizations in `env` are mocked.
+pub struct CreditFixture<'a> {
+    pub env: &'a Env,
+    pub admin: Address,
+    pub credit: CreditClient<'a>,
+    pub token: token::Client<'a>,
+    pub token_admin: StellarAssetClient<'a>,
+    pub pool: MockPoolClient<'a>,
+    pub risk_oracle: MockRiskOracleClient<'a>,
+}
+
+impl<'a> CreditFixture<'a> {
+    /// Deploy the fixture with `liquidity` of a fresh token in the pool.
+    pub fn new(env: &'a Env, liquidity: i128) -> Self {
+        env.mock_all_auths();
+        let admin = Address::generate(env);
+        let credit = CreditClient::new(env, &env.register(Credit, ()));
+        credit.init(&admin, &InitConfig::default());
+
+        let (token, token_admin) = create_token(env);
+        let pool = MockPoolClient::new(env, &env.register(MockPool, (token.address.clone(),)));
+        token_admin.mint(&pool.address, &liquidity);
+        credit.set_liquidity_pool(&pool.address);
+
+        let risk_oracle = MockRiskOracleClient::new(env, &env.register(MockRiskOracle, ()));
+        credit.set_risk_oracle(&risk_oracle.address);
+
+        Self {
+            env,
+            admin,
+            credit,
+            token,
+            token_admin,
+            pool,
+            risk_oracle,
+        }
+    }
+
+    /// Open a line for a new borrower with a risk score of 70 and draw `drawn` of it from the
+    /// pool. Returns the borrower.
+    pub fn open_funded_line(
+        &self,
+        credit_limit: i128,
+        interest_rate_bps: u32,
+        drawn: i128,
+    ) -> Address {
+        let borrower = Address::generate(self.env);
+        self.credit
+            .open_credit_line(&borrower, &credit_limit, &interest_rate_bps, &70_u32);
+        if drawn > 0 {
+            self.credit.draw_credit(&borrower, &drawn);
+        }
+        borrower
+    }
+
+    /// Advance the ledger clock by `seconds`.
+    pub fn advance_time(&self, seconds: u64) {
+        self.env
+            .ledger()
+            .with_mut(|ledger| ledger.timestamp += seconds);
+    }
+
+    /// Advance the ledger clock by `seconds`, then run keeper upkeep on the borrower's line so
+    /// its interest is accrued into storage. Returns the updated line.
+    pub fn advance_and_accrue(&self, borrower: &Address, seconds: u64) -> CreditLineData {
+        self.advance_time(seconds);
+        self.credit.poke(&self.admin, borrower);
+        self.credit.get_credit_line(borrower).unwrap()
+    }
+
+    /// Put a payment of `amount` due now on the borrower's line, then advance the clock past
+    /// the grace period by `overdue_seconds`, leaving the payment delinquent.
+    pub fn force_delinquency(&self, borrower: &Address, amount: i128, overdue_seconds: u64) {
+        let due_ts = self.env.ledger().timestamp();
+        self.credit.set_payment_due(borrower, &amount, &due_ts);
+        let grace = self
+            .env
+            .as_contract(&self.credit.address, || accrual::grace_period(self.env));
+        self.advance_time(grace + overdue_seconds);
+    }
+}
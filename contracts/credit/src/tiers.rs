@@ -0,0 +1,73 @@
+//! Risk-tier presets: named bundles of terms (rate, maximum limit, LTV and penalty rate) that
+//! `open_credit_line_with_tier` applies instead of raw parameters. A tier's terms are copied
+//! onto a line when it is opened; changing a tier later only affects lines opened after.
+
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+use crate::types::{LimitUnlock, RiskTier};
+use crate::{collateral, rwa, ttl, DataKey};
+
+/// Storage keys for risk tiers.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TierKey {
+    Tier(Symbol),
+    /// Tier a line was opened under.
+    LineTier(Address),
+}
+
+pub fn get(env: &Env, tier: &Symbol) -> Option<RiskTier> {
+    env.storage().instance().get(&TierKey::Tier(tier.clone()))
+}
+
+pub fn set(env: &Env, tier: &Symbol, terms: Option<&RiskTier>) {
+    let key = TierKey::Tier(tier.clone());
+    match terms {
+        Some(terms) => env.storage().instance().set(&key, terms),
+        None => env.storage().instance().remove(&key),
+    }
+}
+
+pub fn line_tier(env: &Env, borrower: &Address) -> Option<Symbol> {
+    env.storage()
+        .persistent()
+        .get(&TierKey::LineTier(borrower.clone()))
+}
+
+pub fn set_line_tier(env: &Env, borrower: &Address, tier: Option<&Symbol>) {
+    let key = TierKey::LineTier(borrower.clone());
+    match tier {
+        Some(tier) => {
+            env.storage().persistent().set(&key, tier);
+            env.storage().persistent().extend_ttl(
+                &key,
+                ttl::LINE_BUMP_THRESHOLD,
+                ttl::LINE_BUMP_AMOUNT,
+            );
+        }
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+/// Apply the per-line parts of `terms` to a line just opened under `tier`: its tier (for risk
+/// weighting), penalty rate and, with a non-zero LTV, collateral unlocking up to the tier's
+/// maximum limit.
+pub fn apply(env: &Env, borrower: &Address, tier: &Symbol, terms: &RiskTier, credit_limit: i128) {
+    rwa::set_line_tier(env, borrower, Some(tier));
+    env.storage().persistent().set(
+        &DataKey::LinePenaltyRate(borrower.clone()),
+        &terms.penalty_rate_bps,
+    );
+    if terms.ltv_bps > 0 {
+        collateral::set_unlock(
+            env,
+            borrower,
+            None,
+            Some(LimitUnlock {
+                base_limit: credit_limit,
+                multiplier_bps: terms.ltv_bps,
+                max_limit: terms.max_limit,
+            }),
+        );
+    }
+}
@@ -0,0 +1,79 @@
+//! Timelock for sensitive admin actions.
+//!
+//! Every action with a `TimelockAction` variant is gated: once the admin configures a non-zero
+//! delay, its direct entrypoint fails `require_direct_admin_action`, and the action is instead
+//! queued with an ETA of now plus the delay, may be cancelled, and runs through
+//! `execute_admin_action` once the ETA passes. Contract upgrades and estate transfers have no
+//! direct entrypoint and always go through the queue.
+
+use soroban_sdk::Env;
+
+use crate::types::{QueuedAction, TimelockAction};
+use crate::{estate, CreditError, DataKey};
+
+/// Upper bound on the delay, so the admin cannot lock actions away indefinitely.
+pub const MAX_TIMELOCK_DELAY: u64 = 30 * 24 * 60 * 60;
+
+pub fn delay(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DataKey::TimelockDelay)
+        .unwrap_or(0)
+}
+
+pub fn set_delay(env: &Env, seconds: u64) -> Result<(), CreditError> {
+    if seconds > MAX_TIMELOCK_DELAY {
+        return Err(CreditError::InvalidParameter);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::TimelockDelay, &seconds);
+    Ok(())
+}
+
+/// Delay applied when queueing `action`: the configured delay, or the action's mandatory
+/// minimum if longer.
+pub fn delay_for(env: &Env, action: &TimelockAction) -> u64 {
+    let minimum = match action {
+        TimelockAction::TransferToEstate(..) => estate::MIN_ESTATE_DELAY,
+        _ => 0,
+    };
+    delay(env).max(minimum)
+}
+
+/// Reject a direct call to an action that must go through the queue.
+pub fn require_unlocked(env: &Env) -> Result<(), CreditError> {
+    if delay(env) > 0 {
+        return Err(CreditError::Timelocked);
+    }
+    Ok(())
+}
+
+pub fn get_queued(env: &Env, id: u32) -> Option<QueuedAction> {
+    env.storage().persistent().get(&DataKey::QueuedAction(id))
+}
+
+/// Store `queued` under a fresh id and return the id.
+pub fn enqueue(env: &Env, queued: &QueuedAction) -> u32 {
+    let id: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::TimelockNextId)
+        .unwrap_or(0);
+    env.storage()
+        .instance()
+        .set(&DataKey::TimelockNextId, &(id + 1));
+    env.storage()
+        .persistent()
+        .set(&DataKey::QueuedAction(id), queued);
+    id
+}
+
+/// Remove and return a queued action.
+pub fn take(env: &Env, id: u32) -> Result<QueuedAction, CreditError> {
+    let queued = get_queued(env, id).ok_or(CreditError::NotFound)?;
+    env.storage()
+        .persistent()
+        .remove(&DataKey::QueuedAction(id));
+    Ok(queued)
+}
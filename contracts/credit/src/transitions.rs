@@ -0,0 +1,127 @@
+//! Introspection of the line status machine: every transition the contract currently allows,
+//! with the entrypoint that performs it and the role that must authorize it. Rows depend on
+//! configuration (timelock, council, risk oracle, auto-suspension threshold), so clients can
+//! render valid next actions without hardcoding them.
+
+use soroban_sdk::{symbol_short, Env, Symbol, Vec};
+
+use crate::types::{CreditStatus, StatusTransition};
+use crate::{autosuspend, council, timelock, DataKey};
+
+const STATUSES: [CreditStatus; 4] = [
+    CreditStatus::Active,
+    CreditStatus::Suspended,
+    CreditStatus::Defaulted,
+    CreditStatus::Closed,
+];
+
+/// Entrypoint, role and condition for each transition out of `from` into `to`.
+fn routes(
+    env: &Env,
+    from: CreditStatus,
+    to: CreditStatus,
+) -> Vec<(Symbol, Symbol, Option<Symbol>)> {
+    let admin = symbol_short!("admin");
+    // Governance-gated actions are proposed by the council, queued by the admin when a
+    // timelock is set, and called directly otherwise.
+    let (gated_method, gated_role) = if council::get_council(env).is_some() {
+        (
+            Symbol::new(env, "propose_admin_action"),
+            symbol_short!("council"),
+        )
+    } else if timelock::delay(env) > 0 {
+        (Symbol::new(env, "queue_admin_action"), admin.clone())
+    } else {
+        (Symbol::new(env, "default_credit_line"), admin.clone())
+    };
+    let mut routes = Vec::new(env);
+    match (from, to) {
+        (CreditStatus::Defaulted, CreditStatus::Suspended) => {
+            routes.push_back((
+                Symbol::new(env, "resolve_default_dispute"),
+                symbol_short!("arbiter"),
+                Some(symbol_short!("disputed")),
+            ));
+        }
+        (_, CreditStatus::Suspended) => {
+            routes.push_back((Symbol::new(env, "suspend_credit_line"), admin.clone(), None));
+            routes.push_back((Symbol::new(env, "batch_suspend"), admin.clone(), None));
+            let estate_method = if council::get_council(env).is_some() {
+                gated_method.clone()
+            } else {
+                Symbol::new(env, "queue_admin_action")
+            };
+            routes.push_back((
+                estate_method,
+                gated_role.clone(),
+                Some(symbol_short!("estate")),
+            ));
+            if autosuspend::min_risk_score(env).is_some() {
+                let low = Some(symbol_short!("low_score"));
+                routes.push_back((
+                    Symbol::new(env, "update_risk_parameters"),
+                    admin.clone(),
+                    low.clone(),
+                ));
+                if env.storage().instance().has(&DataKey::RiskOracle) {
+                    routes.push_back((
+                        Symbol::new(env, "push_risk_score"),
+                        symbol_short!("oracle"),
+                        low.clone(),
+                    ));
+                    routes.push_back((
+                        Symbol::new(env, "sync_risk_score"),
+                        symbol_short!("anyone"),
+                        low,
+                    ));
+                }
+            }
+        }
+        (CreditStatus::Defaulted, CreditStatus::Active) => {
+            routes.push_back((Symbol::new(env, "restructure_defaulted_line"), admin, None));
+        }
+        (_, CreditStatus::Active) => {
+            routes.push_back((Symbol::new(env, "reactivate_credit_line"), admin, None));
+        }
+        (_, CreditStatus::Defaulted) => {
+            routes.push_back((gated_method, gated_role, None));
+        }
+        (_, CreditStatus::Closed) => {
+            let close = Symbol::new(env, "close_credit_line");
+            routes.push_back((close.clone(), admin, None));
+            routes.push_back((
+                close,
+                symbol_short!("borrower"),
+                Some(symbol_short!("zero_bal")),
+            ));
+            routes.push_back((
+                Symbol::new(env, "repay_credit"),
+                symbol_short!("borrower"),
+                Some(symbol_short!("term_paid")),
+            ));
+        }
+    }
+    routes
+}
+
+/// Every allowed status transition under the current configuration, in status order.
+pub fn matrix(env: &Env) -> Vec<StatusTransition> {
+    let mut rows = Vec::new(env);
+    for from in STATUSES {
+        for to in STATUSES {
+            if !from.can_transition_to(to) {
+                continue;
+            }
+            for (method, role, condition) in routes(env, from, to).iter() {
+                rows.push_back(StatusTransition {
+                    from,
+                    to,
+                    method,
+                    role,
+                    condition,
+                });
+            }
+        }
+    }
+    rows
+}
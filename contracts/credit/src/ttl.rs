@@ -0,0 +1,203 @@
+//! Storage TTL management. Persistent entries that are not bumped are eventually archived, and
+//! an archived credit line makes every call on it fail until the entry is restored. Every read
+//! of a credit line extends its TTL, every write also extends its per-line entries, and line
+//! activity keeps the contract's instance storage (admin and configuration) alive.
+//!
+//! Lines are keyed by `(borrower, asset)`. The borrower's line in the liquidity token is stored
+//! under the borrower's address, as it always has been, and a line in any other asset under
+//! `LineKey::AssetLine`. Per-line entries follow the same scheme through `entry_key`: the line
+//! in the liquidity token keeps the entry's own key, and a line in another asset stores it
+//! paired with `LineKey::AssetEntry`.
+
+use soroban_sdk::{contracttype, vec, Address, Env, IntoVal, Val, Vec};
+
+use crate::alerts::AlertKey;
+use crate::borrow_index::BorrowIndexKey;
+use crate::defaulted::DefaultedKey;
+use crate::fees::FeeKey;
+use crate::maturity::MaturityKey;
+use crate::metadata::MetadataKey;
+use crate::migration;
+use crate::opfreeze::OpFreezeKey;
+use crate::promo::PromoKey;
+use crate::receivables::ReceivableKey;
+use crate::referrals::ReferralKey;
+use crate::rounding::RoundingKey;
+use crate::tiers::TierKey;
+use crate::types::CreditLineData;
+use crate::velocity::VelocityKey;
+use crate::waterfall::WaterfallKey;
+use crate::DataKey;
+
+/// Ledgers per day at ~5s ledger close time.
+const DAY_IN_LEDGERS: u32 = 17_280;
+
+/// Instance storage (and the contract code) is bumped to 150 days once fewer than 90 remain,
+/// so it outlives any credit line kept alive by activity.
+pub const INSTANCE_BUMP_THRESHOLD: u32 = 90 * DAY_IN_LEDGERS;
+pub const INSTANCE_BUMP_AMOUNT: u32 = 150 * DAY_IN_LEDGERS;
+
+/// Credit lines and their per-line entries are bumped to 150 days once fewer than 60 remain.
+pub const LINE_BUMP_THRESHOLD: u32 = 60 * DAY_IN_LEDGERS;
+pub const LINE_BUMP_AMOUNT: u32 = 150 * DAY_IN_LEDGERS;
+
+/// Storage keys for credit lines in an asset other than the liquidity token.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LineKey {
+    /// The borrower's line denominated in the asset.
+    AssetLine(Address, Address),
+    /// Prefix of the per-line entries of a line in the asset, stored under the pair of this
+    /// and the entry's key for the line in the liquidity token.
+    AssetEntry(Address),
+    /// Assets other than the liquidity token the borrower has had a line in.
+    Assets(Address),
+}
+
+/// Storage key of the borrower's line in `asset`, where `None` is the liquidity token.
+pub fn line_key(env: &Env, borrower: &Address, asset: Option<&Address>) -> Val {
+    match asset {
+        None => borrower.to_val(),
+        Some(asset) => LineKey::AssetLine(borrower.clone(), asset.clone()).into_val(env),
+    }
+}
+
+/// Storage key of a per-line entry of the line in `asset`, given the entry's key for the line
+/// in the liquidity token (`None`), which is used as is.
+pub fn entry_key(env: &Env, key: impl IntoVal<Env, Val>, asset: Option<&Address>) -> Val {
+    match asset {
+        None => key.into_val(env),
+        Some(asset) => (LineKey::AssetEntry(asset.clone()), key.into_val(env)).into_val(env),
+    }
+}
+
+pub fn bump_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_BUMP_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+/// Extend the TTL of the borrower's credit line entry in `asset`.
+pub fn bump_line(env: &Env, borrower: &Address, asset: Option<&Address>) {
+    env.storage().persistent().extend_ttl(
+        &line_key(env, borrower, asset),
+        LINE_BUMP_THRESHOLD,
+        LINE_BUMP_AMOUNT,
+    );
+    bump_instance(env);
+}
+
+/// Read the borrower's credit line in `asset` without extending its TTL. Lines stored under an
+/// older schema come back in the current layout (see `migration`).
+pub fn read_line(env: &Env, borrower: &Address, asset: Option<&Address>) -> Option<CreditLineData> {
+    env.storage()
+        .persistent()
+        .get::<_, Val>(&line_key(env, borrower, asset))
+        .map(|stored| migration::decode_line(env, stored))
+}
+
+/// Read the borrower's credit line in `asset`, extending its TTL when it exists.
+pub fn load_line(env: &Env, borrower: &Address, asset: Option<&Address>) -> Option<CreditLineData> {
+    let line = read_line(env, borrower, asset);
+    if line.is_some() {
+        bump_line(env, borrower, asset);
+    }
+    line
+}
+
+/// Keys of the per-line entries of the borrower's line in the liquidity token, whichever
+/// module owns them; `entry_key` gives those of a line in another asset. TTL bumps and estate
+/// transfers both walk this list, so a new per-line entry only has to be registered here.
+pub fn line_entry_keys(env: &Env, borrower: &Address) -> Vec<Val> {
+    let b = borrower.clone();
+    vec![
+        env,
+        DataKey::PaymentDue(b.clone()).into_val(env),
+        DataKey::LinePenaltyRate(b.clone()).into_val(env),
+        DataKey::LineFrozen(b.clone()).into_val(env),
+        DataKey::RebateEnrollment(b.clone()).into_val(env),
+        DataKey::Capitalization(b.clone()).into_val(env),
+        DataKey::RateHistory(b.clone()).into_val(env),
+        DataKey::LineNotes(b.clone()).into_val(env),
+        DataKey::LineSummary(b.clone()).into_val(env),
+        DataKey::Collateral(b.clone()).into_val(env),
+        DataKey::LimitUnlock(b.clone()).into_val(env),
+        DataKey::LimitRequest(b.clone()).into_val(env),
+        DataKey::LimitBoost(b.clone()).into_val(env),
+        DataKey::TermLoan(b.clone()).into_val(env),
+        OpFreezeKey::LineOpFreeze(b.clone()).into_val(env),
+        MetadataKey::LineMetadata(b.clone()).into_val(env),
+        DefaultedKey::DefaultedAt(b.clone()).into_val(env),
+        DefaultedKey::Dispute(b.clone()).into_val(env),
+        MaturityKey::LineMaturity(b.clone()).into_val(env),
+        WaterfallKey::FeesOutstanding(b.clone()).into_val(env),
+        FeeKey::LateFeeAssessed(b.clone()).into_val(env),
+        AlertKey::UtilizationAlerts(b.clone()).into_val(env),
+        ReferralKey::Referrer(b.clone()).into_val(env),
+        ReceivableKey::ReceivableAssignments(b.clone()).into_val(env),
+        PromoKey::Deferred(b.clone()).into_val(env),
+        RoundingKey::Carry(b.clone()).into_val(env),
+        BorrowIndexKey::Line(b.clone()).into_val(env),
+        VelocityKey::Limit(b.clone()).into_val(env),
+        VelocityKey::Log(b.clone()).into_val(env),
+        TierKey::LineTier(b).into_val(env),
+    ]
+}
+
+/// Assets other than the liquidity token the borrower has ever had a line in.
+pub fn line_assets(env: &Env, borrower: &Address) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&LineKey::Assets(borrower.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Record that the borrower has a line in `asset`, if not already recorded.
+pub fn register_line_asset(env: &Env, borrower: &Address, asset: &Address) {
+    let mut assets = line_assets(env, borrower);
+    if assets.contains(asset) {
+        return;
+    }
+    assets.push_back(asset.clone());
+    let key = LineKey::Assets(borrower.clone());
+    env.storage().persistent().set(&key, &assets);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LINE_BUMP_THRESHOLD, LINE_BUMP_AMOUNT);
+}
+
+/// Extend the borrower's line in `asset`, every per-line entry of it that exists and the
+/// borrower's own entries, so a line brought back from archival does not lose its schedule,
+/// limits or history to a later expiry.
+pub fn bump_line_entries(env: &Env, borrower: &Address, asset: Option<&Address>) {
+    bump_line(env, borrower, asset);
+    let b = borrower.clone();
+    let borrower_keys = [
+        DataKey::ObligorFrozen(b.clone()).into_val(env),
+        DataKey::BorrowerSlot(b.clone()).into_val(env),
+        DataKey::RiskScoreRecord(b.clone()).into_val(env),
+        DataKey::BorrowerHook(b.clone()).into_val(env),
+        LineKey::Assets(b).into_val(env),
+    ];
+    let slot: Option<u32> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::BorrowerSlot(borrower.clone()));
+    let keys = line_entry_keys(env, borrower)
+        .into_iter()
+        .map(|key| entry_key(env, key, asset))
+        .chain(borrower_keys)
+        .chain(
+            slot.map(DataKey::BorrowerAt)
+                .into_iter()
+                .chain([DataKey::BorrowerCount])
+                .map(|key| key.into_val(env)),
+        );
+    for key in keys {
+        if env.storage().persistent().has(&key) {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, LINE_BUMP_THRESHOLD, LINE_BUMP_AMOUNT);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! Draw pacing controls, as fraud and abuse controls independent of the credit limit:
+//!
+//! * Velocity limits: an optional per-line cap on the amount drawn within a rolling window of
+//!   ledger time or ledgers. Each line with a limit keeps a short log of its recent draws;
+//!   entries older than the window are dropped whenever the log is read for a draw.
+//! * Cooldown: a protocol-wide minimum number of ledgers between draws on the same line,
+//!   measured from the line's `last_draw_ledger`.
+
+use soroban_sdk::{contracttype, Address, Env, Val, Vec};
+
+use crate::types::{CreditLineData, DrawVelocityLimit, VelocityWindow};
+use crate::{ttl, CreditError};
+
+/// Most draws kept in a line's velocity log. A draw that would need more entries inside the
+/// window is rejected.
+pub const MAX_WINDOW_DRAWS: u32 = 32;
+
+/// Storage keys for velocity limits.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VelocityKey {
+    /// Minimum ledgers between draws on a line.
+    Cooldown,
+    Limit(Address),
+    /// Recent draws as `(timestamp, ledger, amount)`.
+    Log(Address),
+}
+
+pub fn cooldown_ledgers(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&VelocityKey::Cooldown)
+        .unwrap_or(0)
+}
+
+pub fn set_cooldown_ledgers(env: &Env, ledgers: u32) {
+    env.storage()
+        .instance()
+        .set(&VelocityKey::Cooldown, &ledgers);
+}
+
+/// First ledger at which the line may draw again, if it is still cooling down.
+pub fn cooldown_until(env: &Env, line: &CreditLineData) -> Option<u32> {
+    let next = line.last_draw_ledger?.saturating_add(cooldown_ledgers(env));
+    (env.ledger().sequence() < next).then_some(next)
+}
+
+fn limit_key(env: &Env, borrower: &Address, asset: Option<&Address>) -> Val {
+    ttl::entry_key(env, VelocityKey::Limit(borrower.clone()), asset)
+}
+
+fn log_key(env: &Env, borrower: &Address, asset: Option<&Address>) -> Val {
+    ttl::entry_key(env, VelocityKey::Log(borrower.clone()), asset)
+}
+
+pub fn limit(env: &Env, borrower: &Address, asset: Option<&Address>) -> Option<DrawVelocityLimit> {
+    env.storage()
+        .persistent()
+        .get(&limit_key(env, borrower, asset))
+}
+
+pub fn set_limit(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+    limit: Option<DrawVelocityLimit>,
+) {
+    let key = limit_key(env, borrower, asset);
+    match limit {
+        Some(limit) => {
+            env.storage().persistent().set(&key, &limit);
+            env.storage().persistent().extend_ttl(
+                &key,
+                ttl::LINE_BUMP_THRESHOLD,
+                ttl::LINE_BUMP_AMOUNT,
+            );
+        }
+        None => {
+            env.storage().persistent().remove(&key);
+            env.storage()
+                .persistent()
+                .remove(&log_key(env, borrower, asset));
+        }
+    }
+}
+
+/// Whether a draw made at `(timestamp, ledger)` still falls inside the window.
+fn in_window(env: &Env, window: &VelocityWindow, timestamp: u64, ledger: u32) -> bool {
+    match window {
+        VelocityWindow::Seconds(secs) => env.ledger().timestamp().saturating_sub(timestamp) < *secs,
+        VelocityWindow::Ledgers(count) => env.ledger().sequence().saturating_sub(ledger) < *count,
+    }
+}
+
+/// Draws still inside the window.
+fn recent(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+    window: &VelocityWindow,
+) -> Vec<(u64, u32, i128)> {
+    let log: Vec<(u64, u32, i128)> = env
+        .storage()
+        .persistent()
+        .get(&log_key(env, borrower, asset))
+        .unwrap_or(Vec::new(env));
+    let mut recent = Vec::new(env);
+    for entry in log.iter() {
+        if in_window(env, window, entry.0, entry.1) {
+            recent.push_back(entry);
+        }
+    }
+    recent
+}
+
+/// Amount drawn inside the current window; zero without a limit.
+pub fn drawn_in_window(env: &Env, borrower: &Address, asset: Option<&Address>) -> i128 {
+    limit(env, borrower, asset).map_or(0, |limit| {
+        recent(env, borrower, asset, &limit.window)
+            .iter()
+            .map(|entry| entry.2)
+            .sum()
+    })
+}
+
+/// Amount that can still be drawn in the current window, or `None` without a limit.
+pub fn remaining(env: &Env, borrower: &Address, asset: Option<&Address>) -> Option<i128> {
+    let limit = limit(env, borrower, asset)?;
+    Some((limit.max_amount - drawn_in_window(env, borrower, asset)).max(0))
+}
+
+/// Whether drawing `amount` now would exceed the line's velocity limit.
+pub fn exceeds(env: &Env, borrower: &Address, asset: Option<&Address>, amount: i128) -> bool {
+    let Some(limit) = limit(env, borrower, asset) else {
+        return false;
+    };
+    let recent = recent(env, borrower, asset, &limit.window);
+    let drawn: i128 = recent.iter().map(|entry| entry.2).sum();
+    recent.len() >= MAX_WINDOW_DRAWS || drawn.saturating_add(amount) > limit.max_amount
+}
+
+/// Log a draw against the line's window. No-op without a limit.
+pub fn record_draw(
+    env: &Env,
+    borrower: &Address,
+    asset: Option<&Address>,
+    amount: i128,
+) -> Result<(), CreditError> {
+    let Some(limit) = limit(env, borrower, asset) else {
+        return Ok(());
+    };
+    let mut recent = recent(env, borrower, asset, &limit.window);
+    if recent.len() >= MAX_WINDOW_DRAWS {
+        return Err(CreditError::DrawVelocityExceeded);
+    }
+    recent.push_back((env.ledger().timestamp(), env.ledger().sequence(), amount));
+    let key = log_key(env, borrower, asset);
+    env.storage().persistent().set(&key, &recent);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ttl::LINE_BUMP_THRESHOLD, ttl::LINE_BUMP_AMOUNT);
+    Ok(())
+}
@@ -0,0 +1,65 @@
+//! Pre-signed draw vouchers. A borrower registers an ed25519 key and signs one-time vouchers
+//! off-chain; any relayer can redeem a voucher, which disburses the signed amount to the signed
+//! recipient and burns the voucher's nonce.
+
+use soroban_sdk::{contracttype, xdr::ToXdr, Address, BytesN, Env};
+
+use crate::ttl;
+use crate::types::DrawVoucher;
+use crate::CreditError;
+
+/// Storage keys for voucher state.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VoucherKey {
+    /// Ed25519 public key the borrower signs vouchers with.
+    Signer(Address),
+    /// Burned voucher nonce of a borrower.
+    Nonce(Address, u64),
+}
+
+pub fn signer(env: &Env, borrower: &Address) -> Option<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&VoucherKey::Signer(borrower.clone()))
+}
+
+pub fn set_signer(env: &Env, borrower: &Address, public_key: Option<BytesN<32>>) {
+    let key = VoucherKey::Signer(borrower.clone());
+    match public_key {
+        Some(public_key) => env.storage().persistent().set(&key, &public_key),
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+pub fn is_redeemed(env: &Env, borrower: &Address, nonce: u64) -> bool {
+    env.storage()
+        .persistent()
+        .has(&VoucherKey::Nonce(borrower.clone(), nonce))
+}
+
+/// Check the voucher's signature, expiry and nonce, then burn the nonce.
+///
+/// The signed message is the XDR encoding of `(contract, voucher)`, where `contract` is this
+/// contract's address, so a voucher cannot be replayed against another deployment. A signature
+/// that does not verify traps in the host's `ed25519_verify` instead of returning an error, so
+/// the whole invocation fails with no `CreditError` to match on.
+pub fn redeem(env: &Env, voucher: &DrawVoucher, signature: &BytesN<64>) -> Result<(), CreditError> {
+    let public_key = signer(env, &voucher.borrower).ok_or(CreditError::NotConfigured)?;
+    if env.ledger().timestamp() >= voucher.expires_at {
+        return Err(CreditError::Expired);
+    }
+    if is_redeemed(env, &voucher.borrower, voucher.nonce) {
+        return Err(CreditError::AlreadyProcessed);
+    }
+    let message = (env.current_contract_address(), voucher.clone()).to_xdr(env);
+    env.crypto()
+        .ed25519_verify(&public_key, &message, signature);
+
+    let key = VoucherKey::Nonce(voucher.borrower.clone(), voucher.nonce);
+    env.storage().persistent().set(&key, &true);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ttl::LINE_BUMP_THRESHOLD, ttl::LINE_BUMP_AMOUNT);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! Goodwill waivers: operators forgive accrued interest and penalties on a line, within a
+//! per-operator budget that resets every 30-day period.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::{ttl, CreditError};
+
+/// Length of a waiver budget period.
+pub const PERIOD_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Storage keys for waiver budgets.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WaiverKey {
+    /// Operator -> amount it may waive per period.
+    Budget(Address),
+    /// (operator, period index) -> amount waived in that period.
+    WaivedInPeriod(Address, u64),
+}
+
+pub fn budget(env: &Env, operator: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&WaiverKey::Budget(operator.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_budget(env: &Env, operator: &Address, budget: i128) {
+    let key = WaiverKey::Budget(operator.clone());
+    if budget > 0 {
+        env.storage().persistent().set(&key, &budget);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+fn used_key(env: &Env, operator: &Address) -> WaiverKey {
+    WaiverKey::WaivedInPeriod(operator.clone(), env.ledger().timestamp() / PERIOD_SECS)
+}
+
+fn used(env: &Env, operator: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&used_key(env, operator))
+        .unwrap_or(0)
+}
+
+/// Budget the operator has left in the current period.
+pub fn remaining(env: &Env, operator: &Address) -> i128 {
+    (budget(env, operator) - used(env, operator)).max(0)
+}
+
+/// Charge `amount` to the operator's budget for the current period; returns what is left.
+pub fn consume(env: &Env, operator: &Address, amount: i128) -> Result<i128, CreditError> {
+    let left = remaining(env, operator);
+    if amount > left {
+        return Err(CreditError::WaiverBudgetExceeded);
+    }
+    let key = used_key(env, operator);
+    env.storage()
+        .persistent()
+        .set(&key, &(used(env, operator) + amount));
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ttl::LINE_BUMP_THRESHOLD, ttl::LINE_BUMP_AMOUNT);
+    Ok(left - amount)
+}
@@ -0,0 +1,127 @@
+//! The repayment waterfall: the order in which a repayment is applied to a line's outstanding
+//! fees, accrued interest and principal. Fees are capitalized into `utilized_amount` when they
+//! are charged, so the fee balance kept here is the part of the utilization that came from fees;
+//! the rest is principal.
+
+use soroban_sdk::{contracttype, Address, Env, Val};
+
+use crate::ttl;
+use crate::types::{CreditLineData, RepaymentWaterfall};
+
+/// Storage keys for the waterfall.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WaterfallKey {
+    Waterfall,
+    FeesOutstanding(Address),
+}
+
+/// How one repayment splits across the line's balances.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Split {
+    pub fees: i128,
+    pub interest: i128,
+    pub principal: i128,
+}
+
+impl Split {
+    /// The part of the split that reduces `utilized_amount`.
+    pub fn utilized(&self) -> i128 {
+        self.fees + self.principal
+    }
+
+    pub fn total(&self) -> i128 {
+        self.fees + self.interest + self.principal
+    }
+}
+
+pub fn order(env: &Env) -> RepaymentWaterfall {
+    env.storage()
+        .instance()
+        .get(&WaterfallKey::Waterfall)
+        .unwrap_or(RepaymentWaterfall::InterestFeesPrincipal)
+}
+
+pub fn set_order(env: &Env, order: RepaymentWaterfall) {
+    env.storage()
+        .instance()
+        .set(&WaterfallKey::Waterfall, &order);
+}
+
+fn fees_key(env: &Env, borrower: &Address, asset: Option<&Address>) -> Val {
+    ttl::entry_key(env, WaterfallKey::FeesOutstanding(borrower.clone()), asset)
+}
+
+/// Fees charged to the line and not yet repaid, capped at its utilization (write-downs and
+/// recoveries reduce the utilization without going through the waterfall).
+pub fn fees_outstanding(env: &Env, line: &CreditLineData) -> i128 {
+    let key = fees_key(env, &line.borrower, line.asset.as_ref());
+    let fees: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+    fees.min(line.utilized_amount).max(0)
+}
+
+fn set_fees(env: &Env, line: &CreditLineData, fees: i128) {
+    let key = fees_key(env, &line.borrower, line.asset.as_ref());
+    if fees <= 0 {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    env.storage().persistent().set(&key, &fees);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, ttl::LINE_BUMP_THRESHOLD, ttl::LINE_BUMP_AMOUNT);
+}
+
+/// Start a freshly opened line's fee balance at its opening fee.
+pub fn reset_fees(env: &Env, line: &CreditLineData, opening_fee: i128) {
+    set_fees(env, line, opening_fee);
+}
+
+/// Record a fee capitalized into the line's utilization.
+pub fn add_fee(env: &Env, line: &CreditLineData, fee: i128) {
+    if fee > 0 {
+        let fees = fees_outstanding(env, line).saturating_add(fee);
+        set_fees(env, line, fees.min(line.utilized_amount));
+    }
+}
+
+/// Split `amount` across the line's fees, accrued interest and principal in the configured
+/// order. Anything left over is an overpayment and is not part of the split.
+pub fn split(env: &Env, line: &CreditLineData, amount: i128) -> Split {
+    let fees = fees_outstanding(env, line);
+    let interest = line.accrued_interest.max(0);
+    let principal = (line.utilized_amount - fees).max(0);
+    let mut remaining = amount.max(0);
+    let mut take = |due: i128| {
+        let paid = remaining.min(due);
+        remaining -= paid;
+        paid
+    };
+    let mut split = Split::default();
+    match order(env) {
+        RepaymentWaterfall::InterestFeesPrincipal => {
+            split.interest = take(interest);
+            split.fees = take(fees);
+            split.principal = take(principal);
+        }
+        RepaymentWaterfall::FeesInterestPrincipal => {
+            split.fees = take(fees);
+            split.interest = take(interest);
+            split.principal = take(principal);
+        }
+        RepaymentWaterfall::InterestPrincipalFees => {
+            split.interest = take(interest);
+            split.principal = take(principal);
+            split.fees = take(fees);
+        }
+    }
+    split
+}
+
+/// Take a repayment's fee share off the line's fee balance. Call before the line's utilization
+/// is reduced.
+pub fn apply(env: &Env, line: &CreditLineData, split: &Split) {
+    if split.fees > 0 {
+        set_fees(env, line, fees_outstanding(env, line) - split.fees);
+    }
+}
@@ -0,0 +1,399 @@
+#![no_std]
+
+//! Creditra governance: protocol parameter changes (reserve factor, exposure caps, risk tier
+//! presets, penalty rate) are proposed, voted on and executed against the credit contract.
+//!
+//! Votes come either from a council, one vote per member, or from the lenders of a liquidity
+//! pool. A lender's voting power on a proposal is their pool shares at the end of the ledger
+//! before it was made, read from the pool's `balance_at` checkpoints, so shares deposited or
+//! transferred once a proposal is up cannot vote on it. A proposal passes when, once its voting
+//! period is over, more votes were cast for it than against and the votes for it reach the
+//! quorum. Anyone can then execute it, which calls the credit contract's `apply_param_change`;
+//! the credit contract only accepts that call from the governance contract its admin
+//! registered.
+//!
+//! The configuration is set by the constructor when the contract is deployed, so there is no
+//! initialization call to front-run.
+
+mod pool;
+
+use creditra_interface::types::ParamChange;
+use creditra_interface::CreditClient;
+use pool::PoolClient;
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env, Vec};
+
+/// Who votes on proposals.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Electorate {
+    /// Lenders of this liquidity pool, weighted by their shares when a proposal is made.
+    Pool(Address),
+    /// These members, one vote each.
+    Council(Vec<Address>),
+}
+
+/// A proposed parameter change and its tally.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub proposer: Address,
+    pub change: ParamChange,
+    /// Ledger whose closing pool shares weigh the votes.
+    pub snapshot_ledger: u32,
+    pub voting_ends_at: u64,
+    pub votes_for: i128,
+    pub votes_against: i128,
+    pub executed: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    CreditContract,
+    Electorate,
+    /// Seconds a proposal is open for voting.
+    VotingPeriod,
+    /// Votes for a proposal needed for it to pass.
+    Quorum,
+    ProposalCount,
+    Proposal(u32),
+    Voted(u32, Address),
+}
+
+fn electorate(env: &Env) -> Electorate {
+    env.storage()
+        .instance()
+        .get(&DataKey::Electorate)
+        .expect("not initialized")
+}
+
+/// Votes `voter` casts on a proposal snapshotted at `snapshot_ledger`.
+fn voting_power(env: &Env, voter: &Address, snapshot_ledger: u32) -> i128 {
+    match electorate(env) {
+        Electorate::Pool(pool) => PoolClient::new(env, &pool).balance_at(voter, &snapshot_ledger),
+        Electorate::Council(members) => i128::from(members.contains(voter)),
+    }
+}
+
+fn proposal(env: &Env, id: u32) -> Proposal {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Proposal(id))
+        .expect("proposal not found")
+}
+
+fn set_proposal(env: &Env, id: u32, proposal: &Proposal) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Proposal(id), proposal);
+}
+
+#[contract]
+pub struct Governance;
+
+#[contractimpl]
+impl Governance {
+    /// Configures the contract at deployment with the credit contract it governs, its
+    /// electorate, how long proposals are open for voting and the quorum of votes for a
+    /// proposal to pass.
+    ///
+    /// # Panics
+    /// * If `voting_period` or `quorum` is zero, or a council has no members
+    pub fn __constructor(
+        env: Env,
+        credit_contract: Address,
+        electorate: Electorate,
+        voting_period: u64,
+        quorum: i128,
+    ) {
+        if voting_period == 0
+            || quorum <= 0
+            || matches!(&electorate, Electorate::Council(members) if members.is_empty())
+        {
+            panic!("invalid governance config");
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::CreditContract, &credit_contract);
+        env.storage()
+            .instance()
+            .set(&DataKey::Electorate, &electorate);
+        env.storage()
+            .instance()
+            .set(&DataKey::VotingPeriod, &voting_period);
+        env.storage().instance().set(&DataKey::Quorum, &quorum);
+    }
+
+    /// Propose a parameter change, open for voting from now. Pool voting power is snapshotted
+    /// at the end of the previous ledger. Returns the proposal id.
+    ///
+    /// # Panics
+    /// * If the proposer has no voting power at the snapshot
+    ///
+    /// # Events
+    /// Emits `(gov, propose)` with `(id, proposer, change)`.
+    pub fn propose(env: Env, proposer: Address, change: ParamChange) -> u32 {
+        proposer.require_auth();
+        let snapshot_ledger = env.ledger().sequence().saturating_sub(1);
+        if voting_power(&env, &proposer, snapshot_ledger) <= 0 {
+            panic!("no voting power");
+        }
+        let id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ProposalCount)
+            .unwrap_or(0);
+        let voting_period: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::VotingPeriod)
+            .expect("not initialized");
+        set_proposal(
+            &env,
+            id,
+            &Proposal {
+                proposer: proposer.clone(),
+                change: change.clone(),
+                snapshot_ledger,
+                voting_ends_at: env.ledger().timestamp() + voting_period,
+                votes_for: 0,
+                votes_against: 0,
+                executed: false,
+            },
+        );
+        env.storage()
+            .instance()
+            .set(&DataKey::ProposalCount, &(id + 1));
+        env.events().publish(
+            (symbol_short!("gov"), symbol_short!("propose")),
+            (id, proposer, change),
+        );
+        id
+    }
+
+    /// Vote for or against a proposal with the voter's full voting power at its snapshot.
+    /// Returns the votes cast.
+    ///
+    /// # Panics
+    /// * If the proposal does not exist or its voting period is over
+    /// * If the voter already voted on it or has no voting power
+    ///
+    /// # Events
+    /// Emits `(gov, vote)` with `(id, voter, support, votes)`.
+    pub fn vote(env: Env, voter: Address, id: u32, support: bool) -> i128 {
+        voter.require_auth();
+        let mut proposal = proposal(&env, id);
+        if env.ledger().timestamp() >= proposal.voting_ends_at {
+            panic!("voting closed");
+        }
+        let voted = DataKey::Voted(id, voter.clone());
+        if env.storage().persistent().has(&voted) {
+            panic!("already voted");
+        }
+        let votes = voting_power(&env, &voter, proposal.snapshot_ledger);
+        if votes <= 0 {
+            panic!("no voting power");
+        }
+        if support {
+            proposal.votes_for += votes;
+        } else {
+            proposal.votes_against += votes;
+        }
+        set_proposal(&env, id, &proposal);
+        env.storage().persistent().set(&voted, &support);
+        env.events().publish(
+            (symbol_short!("gov"), symbol_short!("vote")),
+            (id, voter, support, votes),
+        );
+        votes
+    }
+
+    /// Apply a passed proposal to the credit contract. Callable by anyone once voting has
+    /// ended.
+    ///
+    /// # Panics
+    /// * If voting is still open, or the proposal was already executed
+    /// * If the proposal did not pass (no more votes for than against, or below quorum)
+    /// * If the credit contract rejects the change
+    ///
+    /// # Events
+    /// Emits `(gov, execute)` with `id`.
+    pub fn execute(env: Env, id: u32) {
+        let mut proposal = proposal(&env, id);
+        if env.ledger().timestamp() < proposal.voting_ends_at {
+            panic!("voting still open");
+        }
+        if proposal.executed {
+            panic!("already executed");
+        }
+        let quorum: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::Quorum)
+            .expect("not initialized");
+        if proposal.votes_for <= proposal.votes_against || proposal.votes_for < quorum {
+            panic!("proposal did not pass");
+        }
+        proposal.executed = true;
+        set_proposal(&env, id, &proposal);
+        let credit: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::CreditContract)
+            .expect("not initialized");
+        CreditClient::new(&env, &credit).apply_param_change(&proposal.change);
+        env.events()
+            .publish((symbol_short!("gov"), symbol_short!("execute")), id);
+    }
+
+    /// A proposal and its tally, if it exists (view function).
+    pub fn proposal(env: Env, id: u32) -> Option<Proposal> {
+        env.storage().persistent().get(&DataKey::Proposal(id))
+    }
+
+    /// Number of proposals made so far; ids run from 0 (view function).
+    pub fn proposal_count(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::ProposalCount)
+            .unwrap_or(0)
+    }
+
+    /// Votes `voter` casts on proposal `id` (view function).
+    ///
+    /// # Panics
+    /// * If the proposal does not exist
+    pub fn voting_power(env: Env, voter: Address, id: u32) -> i128 {
+        voting_power(&env, &voter, proposal(&env, id).snapshot_ledger)
+    }
+
+    /// Who votes on proposals (view function).
+    pub fn electorate(env: Env) -> Electorate {
+        electorate(&env)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use creditra_credit::{Credit, CreditClient as CreditContractClient};
+    use creditra_interface::types::{InitConfig, RiskTier};
+    use creditra_pool::{Pool, PoolClient as PoolContractClient};
+    use soroban_sdk::testutils::{Address as _, Ledger};
+    use soroban_sdk::token::StellarAssetClient;
+    use soroban_sdk::vec;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    /// A credit contract governed by a fresh governance contract with a one-day voting period.
+    fn setup<'a>(
+        env: &'a Env,
+        electorate: &Electorate,
+        quorum: i128,
+    ) -> (GovernanceClient<'a>, CreditContractClient<'a>) {
+        env.mock_all_auths();
+        let credit = CreditContractClient::new(env, &env.register(Credit, ()));
+        credit.init(&Address::generate(env), &InitConfig::default());
+        let gov = GovernanceClient::new(
+            env,
+            &env.register(
+                Governance,
+                (credit.address.clone(), electorate.clone(), DAY, quorum),
+            ),
+        );
+        credit.set_governance(&Some(gov.address.clone()));
+        (gov, credit)
+    }
+
+    fn pass_time(env: &Env, secs: u64) {
+        env.ledger().with_mut(|li| li.timestamp += secs);
+    }
+
+    #[test]
+    fn test_council_vote_applies_param_change() {
+        let env = Env::default();
+        let members = [
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ];
+        let council = Electorate::Council(vec![
+            &env,
+            members[0].clone(),
+            members[1].clone(),
+            members[2].clone(),
+        ]);
+        let (gov, credit) = setup(&env, &council, 2);
+
+        let tier = RiskTier {
+            interest_rate_bps: 800,
+            max_limit: 50_000,
+            ltv_bps: 0,
+            penalty_rate_bps: 1_200,
+        };
+        let id = gov.propose(
+            &members[0],
+            &ParamChange::RiskTier(symbol_short!("B"), tier.clone()),
+        );
+        assert_eq!(gov.vote(&members[0], &id, &true), 1);
+        gov.vote(&members[1], &id, &true);
+        gov.vote(&members[2], &id, &false);
+        assert!(gov.try_execute(&id).is_err());
+
+        pass_time(&env, DAY);
+        gov.execute(&id);
+        assert_eq!(credit.get_risk_tier(&symbol_short!("B")), Some(tier));
+        assert!(gov.proposal(&id).unwrap().executed);
+        assert!(gov.try_execute(&id).is_err());
+    }
+
+    #[test]
+    fn test_pool_votes_weighed_by_shares_before_proposal() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let asset = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        let pool = PoolContractClient::new(&env, &env.register(Pool, ()));
+        pool.init(&Address::generate(&env), &asset, &Address::generate(&env));
+        let minter = StellarAssetClient::new(&env, &asset);
+        let (whale, minnow, latecomer) = (
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        );
+        minter.mint(&whale, &1_500_i128);
+        minter.mint(&minnow, &400_i128);
+        minter.mint(&latecomer, &5_000_i128);
+        env.ledger().with_mut(|li| li.sequence_number = 10);
+        pool.deposit(&whale, &1_500_i128);
+        pool.deposit(&minnow, &400_i128);
+
+        env.ledger().with_mut(|li| li.sequence_number = 11);
+        let (gov, credit) = setup(&env, &Electorate::Pool(pool.address.clone()), 1_000);
+        let id = gov.propose(&minnow, &ParamChange::ReserveFactor(1_000));
+        pool.deposit(&latecomer, &5_000_i128);
+        pool.transfer_shares(&whale, &latecomer, &1_500_i128);
+        assert_eq!(gov.voting_power(&latecomer, &id), 0);
+        assert!(gov.try_vote(&latecomer, &id, &false).is_err());
+        assert_eq!(gov.vote(&whale, &id, &true), 1_500);
+        gov.vote(&minnow, &id, &false);
+
+        pass_time(&env, DAY);
+        gov.execute(&id);
+        assert_eq!(credit.get_fee_config().0, 1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "proposal did not pass")]
+    fn test_proposal_below_quorum_cannot_execute() {
+        let env = Env::default();
+        let member = Address::generate(&env);
+        let council = Electorate::Council(vec![&env, member.clone(), Address::generate(&env)]);
+        let (gov, _credit) = setup(&env, &council, 2);
+        let id = gov.propose(&member, &ParamChange::PenaltyRate(2_000));
+        gov.vote(&member, &id, &true);
+        pass_time(&env, DAY);
+        gov.execute(&id);
+    }
+}
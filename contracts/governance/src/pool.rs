@@ -0,0 +1,11 @@
+//! Cross-contract interface to the liquidity pool (`contracts/pool`) whose lenders vote.
+
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Interface governance requires from a liquidity pool.
+#[allow(dead_code)]
+#[contractclient(name = "PoolClient")]
+pub trait PoolInterface {
+    /// Shares held by `lender` at the end of `ledger`.
+    fn balance_at(env: Env, lender: Address, ledger: u32) -> i128;
+}
@@ -0,0 +1,576 @@
+#![no_std]
+
+//! Creditra insurance fund: accumulates a share of repaid interest and covers liquidity pool
+//! losses when the credit contract writes off a defaulted line.
+//!
+//! The credit contract transfers its interest contributions directly to this contract, so the
+//! fund's idle reserves are its token balance. The admin can deploy idle reserves into
+//! whitelisted share vaults (such as the liquidity pool itself) to earn yield, but only above
+//! `max_claim`: the idle balance never falls below one full claim through a deployment, so the
+//! next claim is always paid at face value. Deployed reserves count at the current value of the
+//! fund's shares and are redeemed when a claim needs more than the idle balance, as far as the
+//! vaults can pay out at the time; what they cannot pay is left uncovered. Claims are capped by a
+//! coverage ratio of the loss and by a maximum payout per claim.
+
+mod vault;
+
+use soroban_sdk::auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, token, vec, Address, Env, IntoVal, Symbol,
+    Vec,
+};
+use vault::VaultClient;
+
+/// Basis point denominator for the coverage ratio.
+const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Coverage limits applied to every claim.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Coverage {
+    /// Share of a claimed loss the fund pays, in basis points.
+    pub coverage_bps: u32,
+    /// Maximum payout for a single claim.
+    pub max_claim: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Admin,
+    Asset,
+    CreditContract,
+    Coverage,
+    TotalClaimed,
+    /// Vaults the admin allows reserves to be deployed into.
+    AllowedVault(Address),
+    /// Vaults the fund holds shares in.
+    Vaults,
+    /// Reserves deployed into a vault and not yet redeemed, at face value.
+    Deployed(Address),
+}
+
+fn require_admin_auth(env: &Env) {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .expect("not initialized");
+    admin.require_auth();
+}
+
+fn require_credit_auth(env: &Env) {
+    let credit: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::CreditContract)
+        .expect("not initialized");
+    credit.require_auth();
+}
+
+fn asset(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::Asset)
+        .expect("not initialized")
+}
+
+fn coverage(env: &Env) -> Coverage {
+    env.storage()
+        .instance()
+        .get(&DataKey::Coverage)
+        .unwrap_or(Coverage {
+            coverage_bps: 0,
+            max_claim: 0,
+        })
+}
+
+fn idle_reserves(env: &Env) -> i128 {
+    token::Client::new(env, &asset(env)).balance(&env.current_contract_address())
+}
+
+fn vaults(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Vaults)
+        .unwrap_or(Vec::new(env))
+}
+
+fn deployed(env: &Env, vault: &Address) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::Deployed(vault.clone()))
+        .unwrap_or(0)
+}
+
+/// Current value of the fund's shares in `vault`.
+fn vault_value(env: &Env, vault: &Address) -> i128 {
+    let client = VaultClient::new(env, vault);
+    let shares = client.shares_of(&env.current_contract_address());
+    if shares == 0 {
+        return 0;
+    }
+    mul_div(shares, client.total_assets(), client.total_shares())
+}
+
+/// `a * b / c`, panicking on overflow.
+fn mul_div(a: i128, b: i128, c: i128) -> i128 {
+    a.checked_mul(b).expect("overflow") / c
+}
+
+fn reserves(env: &Env) -> i128 {
+    vaults(env).iter().fold(idle_reserves(env), |total, vault| {
+        total + vault_value(env, &vault)
+    })
+}
+
+/// Redeem `shares` of `vault`, reducing its deployed face value pro rata, and return the amount
+/// paid out.
+fn redeem(env: &Env, vault: &Address, shares: i128) -> i128 {
+    let client = VaultClient::new(env, vault);
+    let fund = env.current_contract_address();
+    let held = client.shares_of(&fund);
+    let face = deployed(env, vault);
+    let amount = client.withdraw(&fund, &shares);
+    let key = DataKey::Deployed(vault.clone());
+    if shares == held {
+        env.storage().instance().remove(&key);
+        let mut vaults = vaults(env);
+        if let Some(index) = vaults.first_index_of(vault) {
+            vaults.remove(index);
+        }
+        env.storage().instance().set(&DataKey::Vaults, &vaults);
+    } else {
+        env.storage()
+            .instance()
+            .set(&key, &(face - mul_div(face, shares, held)));
+    }
+    env.events().publish(
+        (symbol_short!("ins"), symbol_short!("recall")),
+        (vault.clone(), amount, shares),
+    );
+    amount
+}
+
+/// Redeem vault shares, in deployment order, until idle reserves reach `needed` or the vaults
+/// cannot pay out more.
+fn raise_idle(env: &Env, needed: i128) {
+    for vault in vaults(env).iter() {
+        let shortfall = needed - idle_reserves(env);
+        if shortfall <= 0 {
+            return;
+        }
+        let client = VaultClient::new(env, &vault);
+        let total_assets = client.total_assets();
+        let total_shares = client.total_shares();
+        if total_assets <= 0 {
+            continue;
+        }
+        let wanted = mul_div(shortfall, total_shares, 1)
+            .checked_add(total_assets - 1)
+            .expect("overflow")
+            / total_assets;
+        let payable = mul_div(client.available_liquidity(), total_shares, total_assets);
+        let shares = wanted
+            .min(payable)
+            .min(client.shares_of(&env.current_contract_address()));
+        if shares > 0 {
+            redeem(env, &vault, shares);
+        }
+    }
+}
+
+#[contract]
+pub struct InsuranceFund;
+
+#[contractimpl]
+impl InsuranceFund {
+    /// Initializes the fund with its asset and the credit contract allowed to claim.
+    /// Coverage starts at zero until the admin configures it. `admin` must authorize, so the call
+    /// cannot be front-run with another credit contract.
+    pub fn init(env: Env, admin: Address, asset: Address, credit_contract: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic!("already initialized");
+        }
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Asset, &asset);
+        env.storage()
+            .instance()
+            .set(&DataKey::CreditContract, &credit_contract);
+    }
+
+    /// Set the coverage ratio and per-claim cap (admin only).
+    ///
+    /// # Panics
+    /// * If `coverage_bps` > 10000 or `max_claim` < 0
+    /// * If reserves are deployed and `max_claim` exceeds idle reserves
+    pub fn set_coverage(env: Env, coverage_bps: u32, max_claim: i128) {
+        require_admin_auth(&env);
+        if coverage_bps as i128 > BPS_DENOMINATOR {
+            panic!("coverage_bps exceeds maximum");
+        }
+        if max_claim < 0 {
+            panic!("max_claim must be non-negative");
+        }
+        if !vaults(&env).is_empty() && max_claim > idle_reserves(&env) {
+            panic!("idle reserves below max_claim");
+        }
+        env.storage().instance().set(
+            &DataKey::Coverage,
+            &Coverage {
+                coverage_bps,
+                max_claim,
+            },
+        );
+    }
+
+    /// Pay coverage for a written-off `loss` to `to` (the liquidity pool). Credit contract only.
+    /// Pays `min(loss * coverage_bps / 10000, max_claim, reserves)` and returns the amount paid.
+    /// Deployed reserves are redeemed as needed when the idle balance falls short. Deployments
+    /// keep one full claim idle, but once earlier claims have drawn the idle balance down, the
+    /// payout is limited to what the vaults can pay out at the time and the rest of the loss is
+    /// uncovered.
+    ///
+    /// # Panics
+    /// * If `loss` < 0
+    ///
+    /// # Events
+    /// Emits `(ins, claim)` with `(to, loss, paid)`.
+    pub fn claim(env: Env, to: Address, loss: i128) -> i128 {
+        require_credit_auth(&env);
+        if loss < 0 {
+            panic!("loss must be non-negative");
+        }
+        let coverage = coverage(&env);
+        let mut paid = mul_div(loss, coverage.coverage_bps as i128, BPS_DENOMINATOR)
+            .min(coverage.max_claim)
+            .min(reserves(&env));
+        if paid > idle_reserves(&env) {
+            raise_idle(&env, paid);
+            paid = paid.min(idle_reserves(&env));
+        }
+        if paid > 0 {
+            let total: i128 = env
+                .storage()
+                .instance()
+                .get(&DataKey::TotalClaimed)
+                .unwrap_or(0);
+            env.storage()
+                .instance()
+                .set(&DataKey::TotalClaimed, &(total + paid));
+            token::Client::new(&env, &asset(&env)).transfer(
+                &env.current_contract_address(),
+                &to,
+                &paid,
+            );
+        }
+        env.events().publish(
+            (symbol_short!("ins"), symbol_short!("claim")),
+            (to, loss, paid),
+        );
+        paid
+    }
+
+    /// Allow or disallow deploying reserves into `vault` (admin only). Disallowing a vault does
+    /// not redeem reserves already deployed there.
+    pub fn set_vault_allowed(env: Env, vault: Address, allowed: bool) {
+        require_admin_auth(&env);
+        let key = DataKey::AllowedVault(vault);
+        if allowed {
+            env.storage().instance().set(&key, &true);
+        } else {
+            env.storage().instance().remove(&key);
+        }
+    }
+
+    /// Whether reserves may be deployed into `vault` (view function).
+    pub fn is_vault_allowed(env: Env, vault: Address) -> bool {
+        env.storage().instance().has(&DataKey::AllowedVault(vault))
+    }
+
+    /// Deposit `amount` of idle reserves into `vault` (admin only). Returns the shares minted.
+    /// At least `max_claim` stays idle, so the next claim does not depend on the vault's
+    /// liquidity or share price.
+    ///
+    /// # Panics
+    /// * If `vault` is not allowed or holds a different asset
+    /// * If `amount` <= 0 or exceeds idle reserves
+    /// * If the deployment would leave idle reserves below `max_claim`
+    ///
+    /// # Events
+    /// Emits `(ins, deploy)` with `(vault, amount, shares)`.
+    pub fn deploy_reserves(env: Env, vault: Address, amount: i128) -> i128 {
+        require_admin_auth(&env);
+        if !Self::is_vault_allowed(env.clone(), vault.clone()) {
+            panic!("vault not allowed");
+        }
+        let client = VaultClient::new(&env, &vault);
+        if client.asset() != asset(&env) {
+            panic!("vault asset mismatch");
+        }
+        if amount <= 0 || amount > idle_reserves(&env) {
+            panic!("invalid deploy amount");
+        }
+        if idle_reserves(&env) - amount < coverage(&env).max_claim {
+            panic!("idle reserves below max_claim");
+        }
+        let fund = env.current_contract_address();
+        // The vault pulls the deposit from the fund, one call below this one.
+        env.authorize_as_current_contract(vec![
+            &env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: asset(&env),
+                    fn_name: Symbol::new(&env, "transfer"),
+                    args: (fund.clone(), vault.clone(), amount).into_val(&env),
+                },
+                sub_invocations: Vec::new(&env),
+            }),
+        ]);
+        let shares = client.deposit(&fund, &amount);
+        let mut vaults = vaults(&env);
+        if !vaults.contains(&vault) {
+            vaults.push_back(vault.clone());
+            env.storage().instance().set(&DataKey::Vaults, &vaults);
+        }
+        env.storage().instance().set(
+            &DataKey::Deployed(vault.clone()),
+            &(deployed(&env, &vault) + amount),
+        );
+        env.events().publish(
+            (symbol_short!("ins"), symbol_short!("deploy")),
+            (vault, amount, shares),
+        );
+        shares
+    }
+
+    /// Redeem `shares` of the fund's position in `vault` back to idle reserves (admin only).
+    /// Returns the amount paid out.
+    ///
+    /// # Panics
+    /// * If `shares` <= 0 or exceeds the fund's shares in `vault`
+    ///
+    /// # Events
+    /// Emits `(ins, recall)` with `(vault, amount, shares)`.
+    pub fn recall_reserves(env: Env, vault: Address, shares: i128) -> i128 {
+        require_admin_auth(&env);
+        let held = VaultClient::new(&env, &vault).shares_of(&env.current_contract_address());
+        if shares <= 0 || shares > held {
+            panic!("invalid share amount");
+        }
+        redeem(&env, &vault, shares)
+    }
+
+    /// Asset the fund holds and pays out (view function).
+    pub fn asset(env: Env) -> Address {
+        asset(&env)
+    }
+
+    /// Reserves available for claims: idle reserves plus the current value of deployed
+    /// reserves (view function).
+    pub fn reserves(env: Env) -> i128 {
+        reserves(&env)
+    }
+
+    /// Reserves held by the fund in its asset (view function).
+    pub fn idle_reserves(env: Env) -> i128 {
+        idle_reserves(&env)
+    }
+
+    /// Reserves deployed into `vault` and not yet redeemed, at face value (view function).
+    pub fn deployed_reserves(env: Env, vault: Address) -> i128 {
+        deployed(&env, &vault)
+    }
+
+    /// Vaults the fund holds shares in (view function).
+    pub fn vaults(env: Env) -> Vec<Address> {
+        vaults(&env)
+    }
+
+    /// Current coverage limits (view function).
+    pub fn coverage(env: Env) -> Coverage {
+        coverage(&env)
+    }
+
+    /// Total coverage paid out over the fund's lifetime (view function).
+    pub fn total_claimed(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalClaimed)
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use creditra_pool::{Pool, PoolClient};
+    use soroban_sdk::testutils::Address as _;
+    use soroban_sdk::token::StellarAssetClient;
+
+    fn setup(env: &Env) -> (InsuranceFundClient<'_>, token::Client<'_>, Address) {
+        env.mock_all_auths();
+        let asset = env
+            .register_stellar_asset_contract_v2(Address::generate(env))
+            .address();
+        let fund_id = env.register(InsuranceFund, ());
+        let fund = InsuranceFundClient::new(env, &fund_id);
+        let credit = Address::generate(env);
+        fund.init(&Address::generate(env), &asset, &credit);
+        StellarAssetClient::new(env, &asset).mint(&fund_id, &1_000);
+        (fund, token::Client::new(env, &asset), credit)
+    }
+
+    #[test]
+    fn test_claim_pays_coverage_ratio() {
+        let env = Env::default();
+        let (fund, token, _credit) = setup(&env);
+        fund.set_coverage(&5_000_u32, &10_000_i128);
+        let pool = Address::generate(&env);
+
+        assert_eq!(fund.claim(&pool, &600_i128), 300);
+        assert_eq!(token.balance(&pool), 300);
+        assert_eq!(fund.reserves(), 700);
+        assert_eq!(fund.total_claimed(), 300);
+    }
+
+    #[test]
+    fn test_claim_capped_by_max_claim_and_reserves() {
+        let env = Env::default();
+        let (fund, _token, _credit) = setup(&env);
+        fund.set_coverage(&10_000_u32, &400_i128);
+        let pool = Address::generate(&env);
+
+        assert_eq!(fund.claim(&pool, &1_000_i128), 400);
+        fund.set_coverage(&10_000_u32, &10_000_i128);
+        assert_eq!(fund.claim(&pool, &1_000_i128), 600);
+        assert_eq!(fund.claim(&pool, &1_000_i128), 0);
+    }
+
+    #[test]
+    fn test_claim_without_coverage_pays_nothing() {
+        let env = Env::default();
+        let (fund, _token, _credit) = setup(&env);
+        assert_eq!(fund.claim(&Address::generate(&env), &500_i128), 0);
+    }
+
+    fn setup_vault(
+        env: &Env,
+        fund: &InsuranceFundClient,
+        token: &token::Client,
+    ) -> PoolClient<'static> {
+        let pool_id = env.register(Pool, ());
+        let pool = PoolClient::new(env, &pool_id);
+        pool.init(
+            &Address::generate(env),
+            &token.address,
+            &Address::generate(env),
+        );
+        fund.set_vault_allowed(&pool_id, &true);
+        pool
+    }
+
+    #[test]
+    fn test_deployed_reserves_count_towards_claims() {
+        let env = Env::default();
+        let (fund, token, _credit) = setup(&env);
+        let pool = setup_vault(&env, &fund, &token);
+        fund.set_coverage(&10_000_u32, &400_i128);
+
+        assert_eq!(fund.deploy_reserves(&pool.address, &600_i128), 600);
+        assert_eq!(fund.idle_reserves(), 400);
+        assert_eq!(fund.reserves(), 1_000);
+
+        // Interest repaid to the pool accrues to the fund's shares.
+        StellarAssetClient::new(&env, &token.address).mint(&pool.address, &60);
+        pool.repay(&0_i128, &60_i128);
+        assert_eq!(fund.reserves(), 1_060);
+        assert_eq!(fund.deployed_reserves(&pool.address), 600);
+
+        // The first claim is paid from idle reserves, the next one from the vault.
+        let to = Address::generate(&env);
+        assert_eq!(fund.claim(&to, &730_i128), 400);
+        assert_eq!(fund.idle_reserves(), 0);
+        assert_eq!(fund.claim(&to, &330_i128), 330);
+        assert_eq!(token.balance(&to), 730);
+        assert_eq!(fund.idle_reserves(), 0);
+        assert_eq!(pool.shares_of(&fund.address), 300);
+        assert_eq!(fund.deployed_reserves(&pool.address), 300);
+
+        assert_eq!(fund.recall_reserves(&pool.address, &300_i128), 330);
+        assert_eq!(fund.deployed_reserves(&pool.address), 0);
+        assert_eq!(fund.vaults().len(), 0);
+    }
+
+    #[test]
+    fn test_claim_paid_at_face_value_when_vault_is_illiquid() {
+        let env = Env::default();
+        let (fund, token, _credit) = setup(&env);
+        let pool = setup_vault(&env, &fund, &token);
+        fund.set_coverage(&10_000_u32, &400_i128);
+        fund.deploy_reserves(&pool.address, &600_i128);
+        pool.borrow(&Address::generate(&env), &600_i128);
+
+        let to = Address::generate(&env);
+        assert_eq!(fund.claim(&to, &1_000_i128), 400);
+        assert_eq!(token.balance(&to), 400);
+
+        // Once the idle balance is spent, the illiquid vault leaves the next claim uncovered.
+        assert_eq!(fund.reserves(), 600);
+        assert_eq!(fund.claim(&to, &1_000_i128), 0);
+        assert_eq!(pool.shares_of(&fund.address), 600);
+    }
+
+    #[test]
+    #[should_panic(expected = "idle reserves below max_claim")]
+    fn test_deploy_keeps_max_claim_idle() {
+        let env = Env::default();
+        let (fund, token, _credit) = setup(&env);
+        let pool = setup_vault(&env, &fund, &token);
+        fund.set_coverage(&10_000_u32, &400_i128);
+        fund.deploy_reserves(&pool.address, &601_i128);
+    }
+
+    #[test]
+    #[should_panic(expected = "idle reserves below max_claim")]
+    fn test_max_claim_cannot_exceed_idle_reserves_while_deployed() {
+        let env = Env::default();
+        let (fund, token, _credit) = setup(&env);
+        let pool = setup_vault(&env, &fund, &token);
+        fund.set_coverage(&10_000_u32, &400_i128);
+        fund.deploy_reserves(&pool.address, &600_i128);
+        fund.set_coverage(&10_000_u32, &401_i128);
+    }
+
+    #[test]
+    #[should_panic(expected = "vault not allowed")]
+    fn test_deploy_requires_allowed_vault() {
+        let env = Env::default();
+        let (fund, token, _credit) = setup(&env);
+        let pool = setup_vault(&env, &fund, &token);
+        fund.set_vault_allowed(&pool.address, &false);
+        fund.deploy_reserves(&pool.address, &100_i128);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_claim_requires_credit_contract_auth() {
+        let env = Env::default();
+        let (fund, _token, _credit) = setup(&env);
+        fund.set_coverage(&10_000_u32, &10_000_i128);
+        env.set_auths(&[]);
+        fund.claim(&Address::generate(&env), &100_i128);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_init_requires_admin_auth() {
+        let env = Env::default();
+        let asset = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        let fund = InsuranceFundClient::new(&env, &env.register(InsuranceFund, ()));
+        fund.init(&Address::generate(&env), &asset, &Address::generate(&env));
+    }
+}
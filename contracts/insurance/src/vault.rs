@@ -0,0 +1,24 @@
+//! Cross-contract interface to a share vault the fund deploys reserves into. The liquidity pool
+//! (`contracts/pool`) implements it.
+
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Interface the fund requires from a vault.
+#[allow(dead_code)]
+#[contractclient(name = "VaultClient")]
+pub trait VaultInterface {
+    /// Asset the vault accepts and pays out.
+    fn asset(env: Env) -> Address;
+    /// Deposit `amount` from `lender`, returning the shares minted.
+    fn deposit(env: Env, lender: Address, amount: i128) -> i128;
+    /// Burn `shares` from `lender`, returning the amount paid out.
+    fn withdraw(env: Env, lender: Address, shares: i128) -> i128;
+    /// Cash the vault can pay out right now.
+    fn available_liquidity(env: Env) -> i128;
+    /// Assets backing all shares.
+    fn total_assets(env: Env) -> i128;
+    /// Total shares outstanding.
+    fn total_shares(env: Env) -> i128;
+    /// Shares held by `lender`.
+    fn shares_of(env: Env, lender: Address) -> i128;
+}
@@ -0,0 +1,974 @@
+//! Client interface of the Credit contract. The contract implements `CreditInterface`
+//! directly, so the two cannot drift; each entrypoint is documented on that implementation.
+
+use soroban_sdk::{contractclient, Address, BytesN, Env, Symbol, Val, Vec};
+
+use crate::types::*;
+use crate::CreditError;
+
+/// Entrypoints of the Credit contract. Call them through the generated `CreditClient`.
+#[allow(dead_code)]
+#[contractclient(name = "CreditClient")]
+pub trait CreditInterface {
+    fn init(env: Env, admin: Address, config: InitConfig) -> Result<(), CreditError>;
+
+    fn get_admin(env: Env) -> Option<Address>;
+
+    fn propose_admin(env: Env, new_admin: Address) -> Result<(), CreditError>;
+
+    fn accept_admin(env: Env) -> Result<(), CreditError>;
+
+    fn migrate(env: Env, borrowers: Vec<Address>) -> Result<u32, CreditError>;
+
+    fn get_storage_version(env: Env) -> u32;
+
+    fn get_pending_admin(env: Env) -> Option<Address>;
+
+    fn set_liquidity_token(env: Env, token_address: Address) -> Result<(), CreditError>;
+
+    fn set_liquidity_source(env: Env, reserve_address: Address) -> Result<(), CreditError>;
+
+    fn set_high_frequency_events(env: Env, enabled: bool) -> Result<(), CreditError>;
+
+    fn set_liquidity_pool(env: Env, pool: Address) -> Result<(), CreditError>;
+
+    fn open_credit_line(
+        env: Env,
+        borrower: Address,
+        credit_limit: i128,
+        interest_rate_bps: u32,
+        risk_score: u32,
+    ) -> Result<(), CreditError>;
+
+    fn open_credit_line_in_asset(
+        env: Env,
+        borrower: Address,
+        asset: Address,
+        credit_limit: i128,
+        interest_rate_bps: u32,
+        risk_score: u32,
+    ) -> Result<(), CreditError>;
+
+    fn get_credit_line_in_asset(
+        env: Env,
+        borrower: Address,
+        asset: Address,
+    ) -> Option<CreditLineData>;
+
+    fn draw_credit_in_asset(
+        env: Env,
+        borrower: Address,
+        asset: Address,
+        amount: i128,
+    ) -> Result<(), CreditError>;
+
+    fn repay_credit_in_asset(
+        env: Env,
+        borrower: Address,
+        asset: Address,
+        amount: i128,
+    ) -> Result<i128, CreditError>;
+
+    fn close_credit_line_in_asset(
+        env: Env,
+        borrower: Address,
+        asset: Address,
+        closer: Address,
+    ) -> Result<(), CreditError>;
+
+    fn suspend_credit_line_in_asset(
+        env: Env,
+        borrower: Address,
+        asset: Address,
+    ) -> Result<(), CreditError>;
+
+    fn reactivate_credit_line_in_asset(
+        env: Env,
+        borrower: Address,
+        asset: Address,
+    ) -> Result<(), CreditError>;
+
+    fn default_credit_line_in_asset(
+        env: Env,
+        borrower: Address,
+        asset: Address,
+    ) -> Result<(), CreditError>;
+
+    fn freeze_credit_line_in_asset(
+        env: Env,
+        borrower: Address,
+        asset: Address,
+    ) -> Result<(), CreditError>;
+
+    fn unfreeze_credit_line_in_asset(
+        env: Env,
+        borrower: Address,
+        asset: Address,
+    ) -> Result<(), CreditError>;
+
+    fn write_off_credit_line_in_asset(
+        env: Env,
+        borrower: Address,
+        asset: Address,
+    ) -> Result<(), CreditError>;
+
+    fn set_exposure_caps_in_asset(
+        env: Env,
+        asset: Address,
+        caps: ExposureCaps,
+    ) -> Result<(), CreditError>;
+
+    fn get_exposure_caps_in_asset(env: Env, asset: Address) -> ExposureCaps;
+
+    fn get_protocol_stats_in_asset(env: Env, asset: Address) -> ProtocolStats;
+
+    fn open_credit_line_with_tier(
+        env: Env,
+        borrower: Address,
+        tier: Symbol,
+        credit_limit: i128,
+        risk_score: u32,
+    ) -> Result<(), CreditError>;
+
+    fn open_credit_line_with_metadata(
+        env: Env,
+        borrower: Address,
+        credit_limit: i128,
+        interest_rate_bps: u32,
+        risk_score: u32,
+        metadata: BytesN<32>,
+    ) -> Result<(), CreditError>;
+
+    fn open_credit_line_with_referrer(
+        env: Env,
+        borrower: Address,
+        credit_limit: i128,
+        interest_rate_bps: u32,
+        risk_score: u32,
+        referrer: Address,
+    ) -> Result<(), CreditError>;
+
+    fn set_risk_tier(env: Env, tier: Symbol, terms: RiskTier) -> Result<(), CreditError>;
+
+    fn remove_risk_tier(env: Env, tier: Symbol) -> Result<(), CreditError>;
+
+    fn get_risk_tier(env: Env, tier: Symbol) -> Option<RiskTier>;
+
+    fn get_line_tier(env: Env, borrower: Address) -> Option<Symbol>;
+
+    fn add_supported_asset(env: Env, asset: Address) -> Result<(), CreditError>;
+
+    fn remove_supported_asset(env: Env, asset: Address) -> Result<(), CreditError>;
+
+    fn is_supported_asset(env: Env, asset: Address) -> bool;
+
+    fn draw_credit(env: Env, borrower: Address, amount: i128) -> Result<(), CreditError>;
+
+    fn pay_merchant(
+        env: Env,
+        borrower: Address,
+        merchant: Address,
+        amount: i128,
+    ) -> Result<(), CreditError>;
+
+    fn set_merchant_allowed(env: Env, merchant: Address, allowed: bool) -> Result<(), CreditError>;
+
+    fn is_merchant_allowed(env: Env, merchant: Address) -> bool;
+
+    fn quote_rate(env: Env, borrower: Address, amount: i128) -> Result<RateQuote, CreditError>;
+
+    fn draw_credit_with_quote(
+        env: Env,
+        borrower: Address,
+        amount: i128,
+        quote_id: u32,
+    ) -> Result<(), CreditError>;
+
+    fn set_draw_settlement(env: Env, threshold: i128, delay_secs: u64) -> Result<(), CreditError>;
+
+    fn set_guardian(env: Env, guardian: Option<Address>) -> Result<(), CreditError>;
+
+    fn get_draw_settlement(env: Env) -> Option<DrawSettlementConfig>;
+
+    fn cancel_pending_draw(env: Env, caller: Address, id: u32) -> Result<(), CreditError>;
+
+    fn release_pending_draw(env: Env, id: u32) -> Result<(), CreditError>;
+
+    fn schedule_draw(
+        env: Env,
+        borrower: Address,
+        amount: i128,
+        at: u64,
+    ) -> Result<u32, CreditError>;
+
+    fn cancel_scheduled_draw(env: Env, borrower: Address, id: u32) -> Result<(), CreditError>;
+
+    fn execute_scheduled_draw(env: Env, id: u32) -> Result<(), CreditError>;
+
+    fn get_scheduled_draw(env: Env, id: u32) -> Option<ScheduledDraw>;
+
+    fn create_draw_mandate(
+        env: Env,
+        borrower: Address,
+        recipient: Address,
+        amount: i128,
+        interval_secs: u64,
+        start_at: u64,
+        end_at: u64,
+    ) -> Result<u32, CreditError>;
+
+    fn cancel_draw_mandate(env: Env, borrower: Address, id: u32) -> Result<(), CreditError>;
+
+    fn execute_draw_mandate(env: Env, id: u32) -> Result<(), CreditError>;
+
+    fn get_draw_mandate(env: Env, id: u32) -> Option<DrawMandate>;
+
+    fn get_pending_draw(env: Env, id: u32) -> Option<PendingDraw>;
+
+    fn set_collateral_token(env: Env, token_address: Address) -> Result<(), CreditError>;
+
+    fn set_limit_unlock(
+        env: Env,
+        borrower: Address,
+        multiplier_bps: u32,
+        max_limit: i128,
+    ) -> Result<(), CreditError>;
+
+    fn get_limit_unlock(env: Env, borrower: Address) -> Option<LimitUnlock>;
+
+    fn get_collateral(env: Env, borrower: Address) -> i128;
+
+    fn get_health_factor(env: Env, borrower: Address) -> Result<Option<HealthFactor>, CreditError>;
+
+    fn set_margin_call_threshold(env: Env, threshold_bps: Option<u32>) -> Result<(), CreditError>;
+
+    fn get_margin_call_threshold(env: Env) -> Option<u32>;
+
+    fn post_collateral(env: Env, borrower: Address, amount: i128) -> Result<(), CreditError>;
+
+    fn withdraw_collateral(env: Env, borrower: Address, amount: i128) -> Result<(), CreditError>;
+
+    fn approve_delegate(
+        env: Env,
+        borrower: Address,
+        delegate: Address,
+        max_amount: i128,
+    ) -> Result<(), CreditError>;
+
+    fn delegate_capacity(
+        env: Env,
+        borrower: Address,
+        to: Address,
+        amount: i128,
+        until: u64,
+    ) -> Result<(), CreditError>;
+
+    fn revoke_delegate(env: Env, borrower: Address, delegate: Address);
+
+    fn draw_credit_as_delegate(
+        env: Env,
+        delegate: Address,
+        borrower: Address,
+        amount: i128,
+    ) -> Result<(), CreditError>;
+
+    fn set_voucher_key(env: Env, borrower: Address, public_key: Option<BytesN<32>>);
+
+    fn get_voucher_key(env: Env, borrower: Address) -> Option<BytesN<32>>;
+
+    fn draw_with_voucher(
+        env: Env,
+        voucher: DrawVoucher,
+        signature: BytesN<64>,
+    ) -> Result<(), CreditError>;
+
+    fn is_voucher_redeemed(env: Env, borrower: Address, nonce: u64) -> bool;
+
+    fn get_delegation(env: Env, borrower: Address, delegate: Address) -> Option<Delegation>;
+
+    fn set_borrower_hook(env: Env, borrower: Address, hook: Option<Address>);
+
+    fn add_lifecycle_hook(env: Env, hook: Address) -> Result<(), CreditError>;
+
+    fn remove_lifecycle_hook(env: Env, hook: Address) -> Result<(), CreditError>;
+
+    fn get_lifecycle_hooks(env: Env) -> Vec<Address>;
+
+    fn repay_credit(env: Env, borrower: Address, amount: i128) -> Result<(), CreditError>;
+
+    fn repay_on_behalf(
+        env: Env,
+        payer: Address,
+        borrower: Address,
+        amount: i128,
+    ) -> Result<(), CreditError>;
+
+    fn update_risk_parameters(
+        env: Env,
+        borrower: Address,
+        credit_limit: i128,
+        interest_rate_bps: u32,
+        risk_score: u32,
+    ) -> Result<(), CreditError>;
+
+    fn set_draw_velocity_limit(
+        env: Env,
+        borrower: Address,
+        limit: Option<DrawVelocityLimit>,
+    ) -> Result<(), CreditError>;
+
+    fn set_kyc_mode(env: Env, mode: KycMode) -> Result<(), CreditError>;
+
+    fn get_kyc_mode(env: Env) -> KycMode;
+
+    fn set_kyc_allowed(env: Env, account: Address, allowed: bool) -> Result<(), CreditError>;
+
+    fn is_kyc_allowed(env: Env, account: Address) -> bool;
+
+    fn set_compliance_officer(env: Env, officer: Address, enabled: bool)
+        -> Result<(), CreditError>;
+
+    fn is_compliance_officer(env: Env, account: Address) -> bool;
+
+    fn set_blacklisted(
+        env: Env,
+        actor: Address,
+        account: Address,
+        blocked: bool,
+    ) -> Result<(), CreditError>;
+
+    fn is_blacklisted(env: Env, account: Address) -> bool;
+
+    fn set_risk_manager(env: Env, manager: Address, enabled: bool) -> Result<(), CreditError>;
+
+    fn is_risk_manager(env: Env, account: Address) -> bool;
+
+    fn set_risk_engine(env: Env, engine: Option<Address>) -> Result<(), CreditError>;
+
+    fn get_risk_engine(env: Env) -> Option<Address>;
+
+    fn set_governance(env: Env, governance: Option<Address>) -> Result<(), CreditError>;
+
+    fn get_governance(env: Env) -> Option<Address>;
+
+    fn apply_param_change(env: Env, change: ParamChange) -> Result<(), CreditError>;
+
+    fn set_loss_params(
+        env: Env,
+        actor: Address,
+        tier: Option<Symbol>,
+        params: Option<LossParams>,
+    ) -> Result<(), CreditError>;
+
+    fn get_loss_params(env: Env, tier: Option<Symbol>) -> Option<LossParams>;
+
+    fn get_provision_coverage(env: Env) -> ProvisionCoverage;
+
+    fn set_defaulted_repayments(env: Env, enabled: bool) -> Result<(), CreditError>;
+
+    fn get_defaulted_repayments(env: Env) -> bool;
+
+    fn set_default_dispute_window(env: Env, secs: u64) -> Result<(), CreditError>;
+
+    fn get_default_dispute_window(env: Env) -> u64;
+
+    fn dispute_default(
+        env: Env,
+        borrower: Address,
+        reason_hash: BytesN<32>,
+    ) -> Result<(), CreditError>;
+
+    fn resolve_default_dispute(
+        env: Env,
+        arbiter: Address,
+        borrower: Address,
+        uphold: bool,
+    ) -> Result<(), CreditError>;
+
+    fn get_default_dispute(env: Env, borrower: Address) -> Option<DefaultDispute>;
+
+    fn set_overpayment_policy(env: Env, policy: OverpaymentPolicy) -> Result<(), CreditError>;
+
+    fn get_overpayment_policy(env: Env) -> OverpaymentPolicy;
+
+    fn set_repayment_waterfall(env: Env, order: RepaymentWaterfall) -> Result<(), CreditError>;
+
+    fn get_repayment_waterfall(env: Env) -> RepaymentWaterfall;
+
+    fn set_draw_cooldown(env: Env, ledgers: u32) -> Result<(), CreditError>;
+
+    fn get_draw_cooldown(env: Env) -> u32;
+
+    fn set_exposure_caps(env: Env, caps: ExposureCaps) -> Result<(), CreditError>;
+
+    fn get_exposure_caps(env: Env) -> ExposureCaps;
+
+    fn set_circuit_breaker(
+        env: Env,
+        config: Option<CircuitBreakerConfig>,
+    ) -> Result<(), CreditError>;
+
+    fn get_circuit_breaker(env: Env) -> Option<CircuitBreakerConfig>;
+
+    fn get_circuit_breaker_status(env: Env) -> CircuitBreakerStatus;
+
+    fn reset_circuit_breaker(env: Env) -> Result<(), CreditError>;
+
+    fn set_line_bounds(env: Env, bounds: LineBounds) -> Result<(), CreditError>;
+
+    fn get_line_bounds(env: Env) -> LineBounds;
+
+    fn get_draw_velocity_limit(env: Env, borrower: Address) -> Option<DrawVelocityLimit>;
+
+    fn get_draw_velocity(env: Env, borrower: Address) -> (i128, Option<i128>);
+
+    fn boost_limit(env: Env, borrower: Address, extra: i128, until: u64)
+        -> Result<(), CreditError>;
+
+    fn get_limit_boost(env: Env, borrower: Address) -> Option<LimitBoost>;
+
+    fn reduce_limit(env: Env, borrower: Address, amount: i128) -> Result<(), CreditError>;
+
+    fn modify_credit_line(
+        env: Env,
+        borrower: Address,
+        credit_limit: i128,
+        interest_rate_bps: u32,
+    ) -> Result<(), CreditError>;
+
+    fn request_limit_increase(
+        env: Env,
+        borrower: Address,
+        new_limit: i128,
+    ) -> Result<(), CreditError>;
+
+    fn approve_limit_increase(env: Env, borrower: Address) -> Result<(), CreditError>;
+
+    fn reject_limit_increase(env: Env, borrower: Address) -> Result<(), CreditError>;
+
+    fn get_limit_request(env: Env, borrower: Address) -> Option<LimitIncreaseRequest>;
+
+    fn restructure_credit_line(
+        env: Env,
+        borrower: Address,
+        installment_count: u32,
+        interval_secs: u64,
+    ) -> Result<(), CreditError>;
+
+    fn get_term_loan(env: Env, borrower: Address) -> Option<TermLoan>;
+
+    fn set_line_maturity(
+        env: Env,
+        borrower: Address,
+        maturity_ts: Option<u64>,
+    ) -> Result<(), CreditError>;
+
+    fn get_line_maturity(env: Env, borrower: Address) -> Option<LineMaturity>;
+
+    fn renew_credit_line(
+        env: Env,
+        borrower: Address,
+        maturity_ts: u64,
+        interest_rate_bps: u32,
+    ) -> Result<(), CreditError>;
+
+    fn set_risk_oracle(env: Env, oracle: Address) -> Result<(), CreditError>;
+
+    fn sync_risk_score(env: Env, borrower: Address) -> Result<(), CreditError>;
+
+    fn push_risk_score(env: Env, borrower: Address, risk_score: u32) -> Result<(), CreditError>;
+
+    fn set_score_age_policy(env: Env, policy: Option<ScoreAgePolicy>) -> Result<(), CreditError>;
+
+    fn get_score_age_policy(env: Env) -> Option<ScoreAgePolicy>;
+
+    fn is_score_stale(env: Env, borrower: Address) -> bool;
+
+    fn get_risk_score_record(env: Env, borrower: Address) -> Option<RiskScoreRecord>;
+
+    fn suspend_credit_line(env: Env, borrower: Address) -> Result<(), CreditError>;
+
+    fn batch_open_credit_lines(env: Env, entries: Vec<CreditLineTerms>) -> Result<(), CreditError>;
+
+    fn batch_open_partial(env: Env, entries: Vec<CreditLineTerms>)
+        -> Result<Vec<u32>, CreditError>;
+
+    fn batch_update_risk_parameters(
+        env: Env,
+        entries: Vec<CreditLineTerms>,
+    ) -> Result<(), CreditError>;
+
+    fn batch_update_risk_partial(
+        env: Env,
+        entries: Vec<CreditLineTerms>,
+    ) -> Result<Vec<u32>, CreditError>;
+
+    fn batch_suspend(env: Env, borrowers: Vec<Address>) -> Result<(), CreditError>;
+
+    fn batch_suspend_partial(env: Env, borrowers: Vec<Address>) -> Result<Vec<u32>, CreditError>;
+
+    fn reactivate_credit_line(env: Env, borrower: Address) -> Result<(), CreditError>;
+
+    fn set_min_risk_score(env: Env, min_risk_score: Option<u32>) -> Result<(), CreditError>;
+
+    fn get_min_risk_score(env: Env) -> Option<u32>;
+
+    fn set_rate_floor_spread(env: Env, spread_bps: u32) -> Result<(), CreditError>;
+
+    fn get_rate_floor(env: Env) -> u32;
+
+    fn set_grace_period(env: Env, seconds: u64) -> Result<(), CreditError>;
+
+    fn set_grace_amount(env: Env, amount: i128) -> Result<(), CreditError>;
+
+    fn get_grace_amount(env: Env) -> i128;
+
+    fn set_penalty_rate(env: Env, penalty_rate_bps: u32) -> Result<(), CreditError>;
+
+    fn set_line_penalty_rate(
+        env: Env,
+        borrower: Address,
+        penalty_rate_bps: u32,
+    ) -> Result<(), CreditError>;
+
+    fn set_late_fee(env: Env, config: Option<LateFeeConfig>) -> Result<(), CreditError>;
+
+    fn get_late_fee(env: Env) -> Option<LateFeeConfig>;
+
+    fn assess_late_fee(env: Env, borrower: Address) -> Result<i128, CreditError>;
+
+    fn set_keeper_config(env: Env, config: KeeperConfig) -> Result<(), CreditError>;
+
+    fn get_keeper_config(env: Env) -> KeeperConfig;
+
+    fn poke(env: Env, caller: Address, borrower: Address) -> Result<CreditStatus, CreditError>;
+
+    fn daily_close(env: Env, cursor: u32, limit: u32) -> Result<DailyCloseProgress, CreditError>;
+
+    fn get_last_closed_day(env: Env) -> Option<u64>;
+
+    fn snapshot(env: Env, caller: Address) -> Result<PortfolioSnapshot, CreditError>;
+
+    fn get_snapshot(env: Env, id: u32) -> Option<PortfolioSnapshot>;
+
+    fn get_snapshot_count(env: Env) -> u32;
+
+    fn get_borrow_index(env: Env) -> Result<BorrowIndex, CreditError>;
+
+    fn bump_borrow_index(env: Env) -> Result<BorrowIndex, CreditError>;
+
+    fn deposit(env: Env, borrower: Address, amount: i128) -> Result<i128, CreditError>;
+
+    fn withdraw(env: Env, borrower: Address, amount: i128) -> Result<i128, CreditError>;
+
+    fn set_auto_repay(env: Env, borrower: Address, enabled: bool) -> Result<(), CreditError>;
+
+    fn is_auto_repay_enabled(env: Env, borrower: Address) -> bool;
+
+    fn get_deposit_balance(env: Env, borrower: Address) -> i128;
+
+    fn repay_from_deposit(env: Env, borrower: Address, amount: i128) -> Result<i128, CreditError>;
+
+    fn set_repayment_asset(
+        env: Env,
+        asset: Address,
+        config: Option<RepaymentAsset>,
+    ) -> Result<(), CreditError>;
+
+    fn get_repayment_asset(env: Env, asset: Address) -> Option<RepaymentAsset>;
+
+    fn repay_credit_in(
+        env: Env,
+        borrower: Address,
+        asset: Address,
+        amount: i128,
+    ) -> Result<i128, CreditError>;
+
+    fn sweep_auto_repay(env: Env, borrower: Address) -> Result<i128, CreditError>;
+
+    fn get_position_id(env: Env, borrower: Address) -> Option<u64>;
+
+    fn get_position_holder(env: Env, position_id: u64) -> Option<Address>;
+
+    fn transfer_position(env: Env, position_id: u64, to: Address) -> Result<(), CreditError>;
+
+    fn assign_receivable(
+        env: Env,
+        borrower: Address,
+        assignee: Address,
+        share_bps: u32,
+        price: i128,
+    ) -> Result<u32, CreditError>;
+
+    fn transfer_receivable(
+        env: Env,
+        assignee: Address,
+        borrower: Address,
+        to: Address,
+        share_bps: u32,
+    ) -> Result<u32, CreditError>;
+
+    fn set_receivable_hook(env: Env, assignee: Address, hook: Option<Address>);
+
+    fn get_receivable_assignments(env: Env, borrower: Address) -> Vec<ReceivableAssignment>;
+
+    fn set_payment_due(
+        env: Env,
+        borrower: Address,
+        amount: i128,
+        due_ts: u64,
+    ) -> Result<(), CreditError>;
+
+    fn set_capitalization_schedule(
+        env: Env,
+        borrower: Address,
+        period: CapitalizationPeriod,
+    ) -> Result<(), CreditError>;
+
+    fn set_default_capitalization(
+        env: Env,
+        period: CapitalizationPeriod,
+    ) -> Result<(), CreditError>;
+
+    fn get_default_capitalization(env: Env) -> CapitalizationPeriod;
+
+    fn get_effective_annual_rate(env: Env, borrower: Address) -> Result<u32, CreditError>;
+
+    fn get_effective_rates(
+        env: Env,
+        borrower: Address,
+        amount: i128,
+        term_secs: u64,
+    ) -> Result<EffectiveRates, CreditError>;
+
+    fn get_capitalization_schedule(env: Env, borrower: Address) -> Option<CapitalizationSchedule>;
+
+    fn start_deferred_interest_promo(
+        env: Env,
+        borrower: Address,
+        ends_at: u64,
+    ) -> Result<(), CreditError>;
+
+    fn get_deferred_interest_promo(env: Env, borrower: Address) -> Option<DeferredInterestPromo>;
+
+    fn settle_deferred_interest_promo(env: Env, borrower: Address) -> Result<(), CreditError>;
+
+    fn get_payment_due(env: Env, borrower: Address) -> Option<PaymentDue>;
+
+    fn maturities(env: Env, bucket_size: u64, num_buckets: u32) -> Result<Vec<i128>, CreditError>;
+
+    fn set_accrual_correction_policy(
+        env: Env,
+        auditors: Vec<Address>,
+        threshold: u32,
+        max_correction: i128,
+    ) -> Result<(), CreditError>;
+
+    fn correct_accrual(
+        env: Env,
+        approvers: Vec<Address>,
+        borrower: Address,
+        delta: i128,
+        reason_hash: BytesN<32>,
+    ) -> Result<(), CreditError>;
+
+    fn set_rebate_campaign(
+        env: Env,
+        campaign_id: u32,
+        budget: i128,
+        min_risk_score: u32,
+        max_risk_score: u32,
+        first_cycle_secs: u64,
+    ) -> Result<(), CreditError>;
+
+    fn end_rebate_campaign(env: Env, campaign_id: u32) -> Result<(), CreditError>;
+
+    fn enroll_in_rebate_campaign(
+        env: Env,
+        borrower: Address,
+        campaign_id: u32,
+    ) -> Result<(), CreditError>;
+
+    fn get_rebate_campaign(env: Env, campaign_id: u32) -> Option<RebateCampaign>;
+
+    fn freeze_credit_line(env: Env, borrower: Address) -> Result<(), CreditError>;
+
+    fn unfreeze_credit_line(env: Env, borrower: Address) -> Result<(), CreditError>;
+
+    fn freeze_obligor(env: Env, borrower: Address) -> Result<(), CreditError>;
+
+    fn unfreeze_obligor(env: Env, borrower: Address) -> Result<(), CreditError>;
+
+    fn set_operation_freeze(
+        env: Env,
+        actor: Address,
+        borrower: Option<Address>,
+        freezes: OperationFreezes,
+    ) -> Result<(), CreditError>;
+
+    fn get_operation_freeze(env: Env, borrower: Option<Address>) -> OperationFreezes;
+
+    fn is_credit_line_frozen(env: Env, borrower: Address) -> bool;
+
+    fn is_obligor_frozen(env: Env, borrower: Address) -> bool;
+
+    fn close_credit_line(env: Env, borrower: Address, closer: Address) -> Result<(), CreditError>;
+
+    fn default_credit_line(env: Env, borrower: Address) -> Result<(), CreditError>;
+
+    fn set_reserve_factor(env: Env, reserve_factor_bps: u32) -> Result<(), CreditError>;
+
+    fn set_origination_fee(env: Env, origination_fee_bps: u32) -> Result<(), CreditError>;
+
+    fn set_draw_fee_mode(env: Env, mode: DrawFeeMode) -> Result<(), CreditError>;
+
+    fn get_draw_fee_mode(env: Env) -> DrawFeeMode;
+
+    fn set_flash_fee(env: Env, flash_fee_bps: u32) -> Result<(), CreditError>;
+
+    fn get_flash_fee(env: Env) -> u32;
+
+    fn flash_draw(
+        env: Env,
+        caller: Address,
+        amount: i128,
+        callback_contract: Address,
+        args: Vec<Val>,
+    ) -> Result<i128, CreditError>;
+
+    fn set_opening_fee(env: Env, opening_fee_bps: u32) -> Result<(), CreditError>;
+
+    fn get_opening_fee(env: Env) -> u32;
+
+    fn set_disbursement_unit(env: Env, unit: i128) -> Result<(), CreditError>;
+
+    fn get_disbursement_unit(env: Env) -> i128;
+
+    fn get_rounding_carry(env: Env, borrower: Address) -> i128;
+
+    fn get_total_rounding_carry(env: Env) -> i128;
+
+    fn set_insurance_fund(env: Env, fund: Address) -> Result<(), CreditError>;
+
+    fn set_insurance_factor(env: Env, insurance_factor_bps: u32) -> Result<(), CreditError>;
+
+    fn write_off_credit_line(env: Env, borrower: Address) -> Result<(), CreditError>;
+
+    fn write_down_credit_line(env: Env, borrower: Address, amount: i128)
+        -> Result<(), CreditError>;
+
+    fn forgive_debt(env: Env, entries: Vec<(Address, i128)>) -> Result<(), CreditError>;
+
+    fn get_forgiven_debt(env: Env, borrower: Address) -> i128;
+
+    fn get_total_forgiven(env: Env) -> i128;
+
+    fn record_recovery(
+        env: Env,
+        payer: Address,
+        borrower: Address,
+        amount: i128,
+    ) -> Result<(), CreditError>;
+
+    fn restructure_defaulted_line(
+        env: Env,
+        borrower: Address,
+        credit_limit: i128,
+        interest_rate_bps: u32,
+        installment_count: u32,
+        interval_secs: u64,
+    ) -> Result<(), CreditError>;
+
+    fn get_fee_config(env: Env) -> (u32, u32);
+
+    fn get_treasury_balance(env: Env) -> i128;
+
+    fn withdraw_fees(env: Env, to: Address, amount: i128) -> Result<(), CreditError>;
+
+    fn set_referral_share(env: Env, share_bps: u32) -> Result<(), CreditError>;
+
+    fn get_referral_share(env: Env) -> u32;
+
+    fn get_referrer(env: Env, borrower: Address) -> Option<Address>;
+
+    fn get_referral_balance(env: Env, referrer: Address) -> i128;
+
+    fn claim_referral_fees(env: Env, referrer: Address) -> Result<i128, CreditError>;
+
+    fn set_timelock_delay(env: Env, seconds: u64) -> Result<(), CreditError>;
+
+    fn get_timelock_delay(env: Env) -> u64;
+
+    fn queue_admin_action(env: Env, action: TimelockAction) -> Result<u32, CreditError>;
+
+    fn cancel_admin_action(env: Env, id: u32) -> Result<(), CreditError>;
+
+    fn execute_admin_action(env: Env, id: u32) -> Result<(), CreditError>;
+
+    fn set_admin_council(
+        env: Env,
+        signers: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), CreditError>;
+
+    fn get_admin_council(env: Env) -> Option<AdminCouncil>;
+
+    fn propose_admin_action(
+        env: Env,
+        proposer: Address,
+        action: TimelockAction,
+    ) -> Result<u32, CreditError>;
+
+    fn approve_admin_proposal(env: Env, signer: Address, id: u32) -> Result<(), CreditError>;
+
+    fn execute_admin_proposal(env: Env, id: u32) -> Result<u32, CreditError>;
+
+    fn get_admin_proposal(env: Env, id: u32) -> Option<AdminProposal>;
+
+    fn get_queued_action(env: Env, id: u32) -> Option<QueuedAction>;
+
+    fn get_rate_history(env: Env, borrower: Address) -> Vec<RateChange>;
+
+    fn set_operator(env: Env, operator: Address, enabled: bool) -> Result<(), CreditError>;
+
+    fn set_operator_grant(
+        env: Env,
+        operator: Address,
+        grant: Option<OperatorGrant>,
+    ) -> Result<(), CreditError>;
+
+    fn get_operator_grant(env: Env, operator: Address) -> Option<OperatorGrant>;
+
+    fn can_operator_call(env: Env, operator: Address, function: Symbol) -> bool;
+
+    fn set_waiver_budget(
+        env: Env,
+        operator: Address,
+        monthly_budget: i128,
+    ) -> Result<(), CreditError>;
+
+    fn get_waiver_budget(env: Env, operator: Address) -> (i128, i128);
+
+    fn waive_fees(
+        env: Env,
+        operator: Address,
+        borrower: Address,
+        amount: i128,
+        reason_hash: BytesN<32>,
+    ) -> Result<(), CreditError>;
+
+    fn set_arbiter(
+        env: Env,
+        arbiter: Address,
+        limits: Option<ArbiterLimits>,
+    ) -> Result<(), CreditError>;
+
+    fn get_arbiter(env: Env, arbiter: Address) -> Option<ArbiterLimits>;
+
+    fn get_arbiter_actions(env: Env, arbiter: Address) -> (u32, u32);
+
+    fn resolve_dispute(
+        env: Env,
+        arbiter: Address,
+        borrower: Address,
+        resolution: DisputeResolution,
+        amount: i128,
+        case_hash: BytesN<32>,
+    ) -> Result<(), CreditError>;
+
+    fn append_line_note(
+        env: Env,
+        author: Address,
+        borrower: Address,
+        note_hash: BytesN<32>,
+    ) -> Result<(), CreditError>;
+
+    fn set_line_metadata(
+        env: Env,
+        borrower: Address,
+        metadata: Option<BytesN<32>>,
+    ) -> Result<(), CreditError>;
+
+    fn get_line_metadata(env: Env, borrower: Address) -> Option<BytesN<32>>;
+
+    fn set_utilization_alerts(
+        env: Env,
+        borrower: Address,
+        thresholds_bps: Vec<u32>,
+    ) -> Result<(), CreditError>;
+
+    fn get_utilization_alerts(env: Env, borrower: Address) -> Vec<u32>;
+
+    fn get_line_notes(env: Env, borrower: Address) -> Vec<LineNote>;
+
+    fn restore_credit_line(env: Env, borrower: Address) -> Result<(), CreditError>;
+
+    fn get_line_summary(env: Env, borrower: Address) -> Option<LineSummary>;
+
+    fn get_credit_line(env: Env, borrower: Address) -> Option<CreditLineData>;
+
+    fn get_available_credit(env: Env, borrower: Address) -> i128;
+
+    fn get_accrued_interest(env: Env, borrower: Address) -> Result<i128, CreditError>;
+
+    fn get_credit_summary(env: Env, borrower: Address) -> Option<CreditSummary>;
+
+    fn simulate_draw(env: Env, borrower: Address, amount: i128) -> DrawSimulation;
+
+    fn simulate_repay(env: Env, borrower: Address, amount: i128) -> RepaySimulation;
+
+    fn simulate_plan(env: Env, borrower: Address, monthly_payment: i128) -> PlanSimulation;
+
+    fn get_protocol_stats(env: Env) -> ProtocolStats;
+
+    fn set_risk_weights(env: Env, weights: RiskWeights) -> Result<(), CreditError>;
+
+    fn get_risk_weights(env: Env) -> RiskWeights;
+
+    fn set_tier_risk_weight(
+        env: Env,
+        tier: Symbol,
+        weight_bps: Option<u32>,
+    ) -> Result<(), CreditError>;
+
+    fn get_tier_risk_weight(env: Env, tier: Symbol) -> Option<u32>;
+
+    fn get_risk_weighted_assets(env: Env) -> RiskWeightedAssets;
+
+    fn get_history(env: Env, borrower: Address, offset: u32, limit: u32) -> Vec<HistoryEntry>;
+
+    fn get_behavior_score(env: Env, borrower: Address) -> Option<BehaviorScore>;
+
+    fn get_audit_trail(env: Env, borrower: Address, cursor: u32, limit: u32) -> AuditTrailPage;
+
+    fn get_history_len(env: Env, borrower: Address) -> u32;
+
+    fn set_price_oracle(env: Env, oracle: Option<Address>) -> Result<(), CreditError>;
+
+    fn get_price_oracle(env: Env) -> Option<Address>;
+
+    fn get_statement(env: Env, borrower: Address, cycle: u32) -> Option<Statement>;
+
+    fn get_balance_statement(env: Env, borrower: Address) -> Option<BalanceStatement>;
+
+    fn get_statement_in(
+        env: Env,
+        borrower: Address,
+        cycle: u32,
+        quote_asset: Address,
+    ) -> Result<Option<Statement>, CreditError>;
+
+    fn get_status_transitions(env: Env) -> Vec<StatusTransition>;
+
+    fn count_credit_lines(env: Env) -> u32;
+
+    fn get_info(env: Env) -> ContractInfo;
+
+    fn get_last_event_seq(env: Env) -> u64;
+
+    fn list_credit_lines(
+        env: Env,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<CreditLineData>, CreditError>;
+}
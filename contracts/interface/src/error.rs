@@ -0,0 +1,90 @@
+//! Errors of the Credit contract.
+
+use soroban_sdk::contracterror;
+
+/// Errors returned by every fallible entrypoint. Codes are stable and surface
+/// to clients as `Error(Contract, #code)`.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum CreditError {
+    CreditLineNotFound = 1,
+    InvalidCreditStatus = 2,
+    InvalidAmount = 3,
+    InsufficientUtilization = 4,
+    Unauthorized = 5,
+    LineFrozen = 6,
+    ObligorFrozen = 7,
+    CreditLineAlreadyExists = 8,
+    /// Checked arithmetic overflowed.
+    Overflow = 9,
+    /// The contract has not been initialized with an admin.
+    NotInitialized = 10,
+    /// A required address or policy (token, oracle, council, key) is not configured.
+    NotConfigured = 11,
+    InvalidRiskScore = 12,
+    InterestRateOutOfRange = 13,
+    /// The rate is below the protocol rate floor (pool target yield plus spread).
+    RateBelowFloor = 14,
+    /// A configuration parameter is out of range or malformed.
+    InvalidParameter = 15,
+    BatchTooLarge = 16,
+    /// The new limit would be below the line's utilized amount.
+    LimitBelowUtilization = 17,
+    ExceedsCreditLimit = 18,
+    InsufficientLiquidity = 19,
+    ExceedsDelegateLimit = 20,
+    Reentrancy = 21,
+    CreditLineClosed = 22,
+    /// The line cannot be closed while a balance is outstanding.
+    OutstandingBalance = 23,
+    TermLoanNoDraws = 24,
+    /// A proposal, queued action, pending draw, quote or similar record is missing.
+    NotFound = 25,
+    /// A voucher, quote, delegation or campaign has expired.
+    Expired = 26,
+    /// A timelock, settlement delay or schedule has not elapsed yet.
+    NotYetDue = 27,
+    /// The action must be queued through the timelock.
+    Timelocked = 28,
+    /// The action must go through the admin council.
+    CouncilApprovalRequired = 29,
+    InsufficientApprovals = 30,
+    /// The approval or voucher has already been used.
+    AlreadyProcessed = 31,
+    NotEligible = 32,
+    NotFrozen = 33,
+    UnsupportedAsset = 34,
+    StorageCorrupted = 35,
+    /// The waiver exceeds the operator's remaining budget for the period.
+    WaiverBudgetExceeded = 36,
+    /// The borrower's risk score is older than the configured maximum age.
+    ScoreStale = 37,
+    /// The action exceeds the arbiter's per-action cap or its actions for the period.
+    ArbiterLimitExceeded = 38,
+    /// The draw exceeds the line's velocity limit for the current window.
+    DrawVelocityExceeded = 39,
+    /// The line drew too recently; the draw cooldown has not elapsed.
+    DrawCooldownActive = 40,
+    /// The address is not on the KYC allowlist.
+    NotAllowlisted = 41,
+    /// The address is blacklisted.
+    Blacklisted = 42,
+    /// The action would take protocol-wide exposure over its cap.
+    ExposureCapExceeded = 43,
+    /// A flash draw was not paid back with its fee by the end of the call.
+    FlashDrawNotRepaid = 44,
+    /// `init` was called on a contract that is already initialized.
+    AlreadyInitialized = 45,
+    /// The operation is frozen on the line or protocol-wide (`set_operation_freeze`).
+    OperationFrozen = 46,
+    /// The line is past its maturity date and takes no further draws.
+    LineMatured = 47,
+    /// The line's default is under dispute, pausing collection until an arbiter resolves it.
+    LineDisputed = 48,
+    /// The dispute window for the line's default has passed.
+    DisputeWindowClosed = 49,
+    /// The default-rate circuit breaker has tripped; openings and draws are blocked until the
+    /// admin resets it.
+    CircuitBreakerTripped = 50,
+}
@@ -0,0 +1,14 @@
+#![no_std]
+#![allow(clippy::unused_unit)]
+
+//! Shared interface of the Creditra credit contract: its data types, its errors and a typed
+//! client. Contracts that call the credit contract (the pool, collateral and scoring
+//! contracts, or third-party integrations) depend on this crate instead of invoking it by
+//! function name.
+
+pub mod credit;
+mod error;
+pub mod types;
+
+pub use credit::{CreditClient, CreditInterface};
+pub use error::CreditError;
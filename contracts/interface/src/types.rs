@@ -0,0 +1,1017 @@
+//! Core data types for the Credit contract.
+
+use soroban_sdk::{contracttype, Address, BytesN, String, Symbol, Vec};
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CreditStatus {
+    Active = 0,
+    Suspended = 1,
+    Defaulted = 2,
+    Closed = 3,
+}
+
+impl CreditStatus {
+    /// Whether the line status machine allows moving from `self` to `next`.
+    ///
+    /// Active and Suspended lines may move to each other, to Defaulted or to Closed. A
+    /// Defaulted line can only return to Active by being restructured; Closed is terminal.
+    pub fn can_transition_to(self, next: CreditStatus) -> bool {
+        use CreditStatus::*;
+        matches!(
+            (self, next),
+            (Active, Suspended | Defaulted | Closed)
+                | (Suspended, Active | Defaulted | Closed)
+                | (Defaulted, Active | Suspended)
+        )
+    }
+}
+
+/// One allowed status transition and how it is performed, as returned by
+/// `get_status_transitions`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StatusTransition {
+    pub from: CreditStatus,
+    pub to: CreditStatus,
+    /// Entrypoint that performs (or, for governance-gated actions, starts) the transition.
+    pub method: Symbol,
+    /// Who must authorize it: `admin`, `council`, `borrower`, `oracle`, `arbiter` or `anyone`.
+    pub role: Symbol,
+    /// Extra precondition, if any: `zero_bal` (no outstanding balance), `term_paid` (final
+    /// term-loan installment), `low_score` (score below the auto-suspension minimum),
+    /// `estate` (estate transfer of an Active line) or `disputed` (default under dispute).
+    pub condition: Option<Symbol>,
+}
+
+/// Stored credit line for a borrower.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditLineData {
+    pub borrower: Address,
+    pub credit_limit: i128,
+    pub utilized_amount: i128,
+    pub interest_rate_bps: u32,
+    pub risk_score: u32,
+    pub status: CreditStatus,
+    /// Token the line is denominated in; `None` uses the configured liquidity token.
+    pub asset: Option<Address>,
+    /// Interest accrued and not yet repaid.
+    pub accrued_interest: i128,
+    /// Ledger timestamp up to which interest has been accrued.
+    pub last_accrual_ts: u64,
+    /// Ledger sequence of the line's most recent draw, if any.
+    pub last_draw_ledger: Option<u32>,
+}
+
+/// Minimum payment posted against a credit line, due by `due_ts`.
+/// Any portion still unpaid after the grace period accrues at the penalty rate.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentDue {
+    pub amount: i128,
+    pub due_ts: u64,
+}
+
+/// Cost of a draw held for a given term, returned by `get_effective_rates`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EffectiveRates {
+    /// The line's nominal annual rate.
+    pub interest_rate_bps: u32,
+    /// Origination fee charged on the draw.
+    pub fee: i128,
+    /// Interest and fee over the term: what is owed at the end beyond the cash received.
+    pub finance_charge: i128,
+    /// Finance charge as a simple annual rate on the cash received.
+    pub apr_bps: u32,
+    /// `apr_bps` compounded at the line's capitalization period.
+    pub apy_bps: u32,
+}
+
+/// Deployment metadata returned by `get_info`, for integrators and deploy tooling to verify a
+/// deployment in one read.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractInfo {
+    /// Crate version of the deployed contract.
+    pub version: String,
+    /// Schema version of the contract's events.
+    pub event_schema_version: u32,
+    /// Storage schema version all registered lines are stored in (see `migrate`).
+    pub storage_version: u32,
+    pub admin: Option<Address>,
+    /// Asset lines are drawn and repaid in.
+    pub liquidity_token: Option<Address>,
+    pub liquidity_pool: Option<Address>,
+    /// Risk oracle used by `sync_risk_score` and allowed to push scores.
+    pub risk_oracle: Option<Address>,
+    /// Backend key that alone opens lines while set; the admin does when it is `None`.
+    pub risk_engine: Option<Address>,
+    /// Whether sensitive admin actions need council approval.
+    pub council_enabled: bool,
+    pub timelock_delay: u64,
+    /// Default penalty APR in bps; `None` charges each line its own rate when overdue.
+    pub penalty_rate_bps: Option<u32>,
+    pub grace_period: u64,
+    pub rate_floor_bps: u32,
+    pub exposure_caps: ExposureCaps,
+    /// Number of non-closed credit lines.
+    pub line_count: u32,
+    /// Default rate (bps) above which the circuit breaker trips, `None` while it is disabled.
+    pub circuit_breaker_threshold_bps: Option<u32>,
+    /// When the circuit breaker tripped; openings and draws are blocked while set.
+    pub circuit_breaker_tripped_at: Option<u64>,
+}
+
+/// Protocol-wide aggregates returned by `get_protocol_stats`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProtocolStats {
+    /// Sum of credit limits across non-closed lines.
+    pub total_credit_limit: i128,
+    /// Sum of utilized amounts across all lines.
+    pub total_utilized: i128,
+    /// Cumulative amount drawn since deployment.
+    pub total_drawn: i128,
+    /// Cumulative amount repaid since deployment.
+    pub total_repaid: i128,
+    pub active_count: u32,
+    pub suspended_count: u32,
+    pub defaulted_count: u32,
+    pub closed_count: u32,
+    /// Number of transitions into `Defaulted` since deployment.
+    pub cumulative_defaults: u32,
+    /// Cumulative principal written off since deployment.
+    pub total_written_off: i128,
+    /// Cumulative written-off principal recovered since deployment.
+    pub total_recovered: i128,
+}
+
+/// Protocol-wide borrow index, returned by `get_borrow_index` and `bump_borrow_index`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BorrowIndex {
+    /// Sum of `utilized_amount * interest_rate_bps` over open lines: the book's interest per
+    /// year, in bps of principal.
+    pub rate_weighted_principal: i128,
+    /// Interest accrued across open lines since each line's own checkpoint (`last_accrual_ts`),
+    /// at their current rates, up to `updated_ts`.
+    pub pending_interest: i128,
+    pub updated_ts: u64,
+}
+
+/// Point-in-time summary of the book recorded by `snapshot`, for period-end reporting.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PortfolioSnapshot {
+    /// Sequential id, starting at 0.
+    pub id: u32,
+    pub taken_at: u64,
+    /// Protocol aggregates at the time, including the line counts by status and the
+    /// cumulative default count.
+    pub stats: ProtocolStats,
+    /// Interest accrued and unpaid across all lines at the time, including interest not yet
+    /// settled into a line (see `BorrowIndex`).
+    pub total_accrued_interest: i128,
+}
+
+/// Protocol-wide exposure caps; `None` leaves a total uncapped.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExposureCaps {
+    /// Cap on the sum of utilized amounts across all lines, checked on every draw.
+    pub max_total_utilized: Option<i128>,
+    /// Cap on the sum of credit limits across non-closed lines, checked when a line is opened.
+    pub max_total_committed: Option<i128>,
+}
+
+/// Default-rate circuit breaker settings. The breaker trips when the defaults of the last
+/// `window_days` days exceed `max_default_rate_bps` of the open (non-closed) lines.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircuitBreakerConfig {
+    pub max_default_rate_bps: u32,
+    pub window_days: u32,
+}
+
+/// State of the default-rate circuit breaker returned by `get_circuit_breaker_status`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircuitBreakerStatus {
+    /// When the breaker tripped; `None` while openings and draws are allowed.
+    pub tripped_at: Option<u64>,
+    /// Defaults recorded within the window.
+    pub defaults_in_window: u32,
+    /// Rolling default rate against the current open lines.
+    pub default_rate_bps: u32,
+}
+
+/// Protocol-wide bounds on line terms, checked when a line is opened and when its terms are set
+/// by the admin or risk engine. `None` leaves that side unbounded.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LineBounds {
+    pub min_credit_limit: Option<i128>,
+    pub max_credit_limit: Option<i128>,
+    pub min_interest_rate_bps: Option<u32>,
+    pub max_interest_rate_bps: Option<u32>,
+    pub min_risk_score: Option<u32>,
+    pub max_risk_score: Option<u32>,
+}
+
+/// Deployment settings applied by `init`. `None` leaves a setting at its built-in default, so
+/// `InitConfig::default()` configures nothing beyond the admin.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InitConfig {
+    /// Underlying asset lines are drawn and repaid in (as `set_liquidity_token`).
+    pub liquidity_token: Option<Address>,
+    /// Protocol-wide exposure caps (as `set_exposure_caps`).
+    pub exposure_caps: ExposureCaps,
+    /// Default penalty APR in bps for overdue balances (as `set_penalty_rate`).
+    pub penalty_rate_bps: Option<u32>,
+    /// Spread in bps over the pool's target yield that line rates may not go below (as
+    /// `set_rate_floor_spread`).
+    pub rate_floor_spread_bps: Option<u32>,
+    /// Seconds after a due date before the penalty rate applies (as `set_grace_period`).
+    pub grace_period: Option<u64>,
+}
+
+/// How the per-draw origination fee is charged.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DrawFeeMode {
+    /// Withheld from the disbursement: the borrower receives `amount - fee` and owes `amount`.
+    Withheld,
+    /// Added to the line: the borrower receives `amount` and owes `amount + fee`.
+    Capitalized,
+}
+
+/// What a repayment does with any amount beyond the line's outstanding balance.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OverpaymentPolicy {
+    /// Only the outstanding balance is collected; the excess stays with the payer.
+    Refund,
+    /// The excess is collected and credited to the borrower's repayment deposit.
+    CreditDeposit,
+    /// Repayments larger than the outstanding balance are rejected.
+    Reject,
+}
+
+/// The order in which a repayment is applied to a line's outstanding fees, accrued interest
+/// and principal. Fees are the opening, origination and late fees capitalized into the line.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RepaymentWaterfall {
+    /// Interest first, then fees, then principal (the default).
+    InterestFeesPrincipal,
+    /// Fees first, then interest, then principal.
+    FeesInterestPrincipal,
+    /// Interest first, then principal, with fees paid last.
+    InterestPrincipalFees,
+}
+
+/// Risk weights applied to outstanding balances by line status, in basis points
+/// (10000 = 100%).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskWeights {
+    pub active_bps: u32,
+    pub suspended_bps: u32,
+    pub defaulted_bps: u32,
+    /// Applies to balances left on force-closed lines.
+    pub closed_bps: u32,
+}
+
+/// Book-wide exposure returned by `get_risk_weighted_assets`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskWeightedAssets {
+    /// Outstanding principal plus accrued interest across all lines.
+    pub exposure: i128,
+    /// `exposure` weighted by tier and status.
+    pub risk_weighted: i128,
+}
+
+/// Expected-loss parameters for a risk tier, in basis points.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LossParams {
+    /// Probability of default of a performing line.
+    pub pd_bps: u32,
+    /// Share of a defaulted balance expected to be lost.
+    pub lgd_bps: u32,
+}
+
+/// Loss provision against the book's expected loss, returned by `get_provision_coverage`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProvisionCoverage {
+    /// Provision required by the current balances and loss parameters.
+    pub expected_loss: i128,
+    /// Provision funded from repaid interest and held by the contract.
+    pub funded: i128,
+    /// `funded` over `expected_loss`, in basis points; 10000 when nothing is required.
+    pub coverage_bps: u32,
+}
+
+/// Settings for permissionless `poke` calls: the bounty paid from the treasury when a poke
+/// changes a line's status, and how long a payment due may stay overdue (past its grace period)
+/// before a poke suspends or defaults the line. `None` disables that transition.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeeperConfig {
+    pub bounty: i128,
+    pub suspend_after: Option<u64>,
+    pub default_after: Option<u64>,
+}
+
+/// Progress of a paginated `daily_close`: the registry slot to resume from, the lines handled
+/// by this call and whether the pass reached the end of the registry.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DailyCloseProgress {
+    pub next_cursor: u32,
+    pub processed: u32,
+    pub done: bool,
+}
+
+/// Share of a line's lender-side repayments assigned to `assignee` by a receivable sale, in basis
+/// points.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReceivableAssignment {
+    pub assignee: Address,
+    pub share_bps: u32,
+}
+
+/// Late fee charged once per overdue payment due: `flat` plus `bps` of the amount due, capped
+/// at `max_per_period`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LateFeeConfig {
+    pub flat: i128,
+    pub bps: u32,
+    pub max_per_period: i128,
+}
+
+/// Preset terms for a named risk tier, applied by `open_credit_line_with_tier`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskTier {
+    /// Interest rate for lines opened in the tier, in basis points.
+    pub interest_rate_bps: u32,
+    /// Largest credit limit a line in the tier may have, including collateral unlocks.
+    pub max_limit: i128,
+    /// Limit unlocked per unit of posted collateral, in basis points; 0 for unsecured tiers.
+    pub ltv_bps: u32,
+    /// Penalty APR on overdue balances, in basis points.
+    pub penalty_rate_bps: u32,
+}
+
+/// Growth campaign that waives first-cycle interest for lines in a risk-score band.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RebateCampaign {
+    /// Remaining incentive budget, in the line's denomination.
+    pub budget_remaining: i128,
+    pub min_risk_score: u32,
+    pub max_risk_score: u32,
+    /// Length of the promotional first cycle, in seconds from enrollment.
+    pub first_cycle_secs: u64,
+    pub active: bool,
+}
+
+/// A line's enrollment in a rebate campaign.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RebateEnrollment {
+    pub campaign_id: u32,
+    /// Start of the promotional window (ledger timestamp).
+    pub enrolled_at: u64,
+    /// End of the promotional window (ledger timestamp).
+    pub until_ts: u64,
+    /// Origination fees charged on draws inside the window and not rebated yet.
+    pub origination_fees: i128,
+}
+
+/// Provenance of a line's current `risk_score`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskScoreRecord {
+    pub score: u32,
+    /// Address that supplied the score (admin or the configured risk oracle).
+    pub source: Address,
+    /// Time the score was produced (the oracle's timestamp for oracle scores).
+    pub scored_at: u64,
+}
+
+/// Scoped operator role: the operator entrypoints a key may call, until `expires_at`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OperatorGrant {
+    pub functions: Vec<Symbol>,
+    pub expires_at: u64,
+}
+
+/// Where the KYC allowlist that gates opening and drawing on lines is kept.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KycMode {
+    /// No allowlist: any address may hold credit.
+    Disabled,
+    /// Addresses added with `set_kyc_allowed`.
+    Internal,
+    /// External registry contract answering `is_allowed(account)`.
+    Registry(Address),
+}
+
+/// Window over which a line's draw velocity limit is measured.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VelocityWindow {
+    /// Rolling window of ledger time, in seconds (e.g. 86400 for 24 hours).
+    Seconds(u64),
+    /// Rolling window of this many ledgers.
+    Ledgers(u32),
+}
+
+/// Most a line may draw within any rolling `window`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DrawVelocityLimit {
+    pub max_amount: i128,
+    pub window: VelocityWindow,
+}
+
+/// Limits on an approved arbiter: the largest amount it may adjust in one action and how
+/// many actions it may take per 30-day period.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArbiterLimits {
+    pub max_amount: i128,
+    pub max_actions_per_period: u32,
+}
+
+/// How an arbiter resolves a case in the borrower's favour.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DisputeResolution {
+    /// Waive accrued interest and penalties.
+    FeeWaiver = 0,
+    /// Reverse disputed principal.
+    Chargeback = 1,
+}
+
+/// A borrower's dispute of their line's default.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DefaultDispute {
+    pub raised_at: u64,
+    /// Hash of the borrower's statement of grounds, kept off-chain.
+    pub reason_hash: BytesN<32>,
+    /// `None` while pending; `Some(true)` if an arbiter upheld the default, `Some(false)` if it
+    /// reversed it.
+    pub upheld: Option<bool>,
+}
+
+/// Deferred-interest promotion on a line: interest accrued until `ends_at` is kept in
+/// `deferred_interest` and only charged if the line is not paid in full by then.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeferredInterestPromo {
+    pub ends_at: u64,
+    pub deferred_interest: i128,
+}
+
+/// Maximum age of a borrower's risk score before it counts as stale.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScoreAgePolicy {
+    pub max_age_secs: u64,
+    /// Reject new draws on lines whose score is stale.
+    pub block_draws: bool,
+}
+
+/// Entry in a line's interest rate change log.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateChange {
+    pub interest_rate_bps: u32,
+    /// Time from which the rate applies.
+    pub effective_ts: u64,
+    /// Address that set the rate.
+    pub actor: Address,
+}
+
+/// How often accrued interest is added to principal.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CapitalizationPeriod {
+    /// Accrued interest is tracked separately and never capitalized.
+    None = 0,
+    /// Every 30 days.
+    Monthly = 1,
+    /// Every 90 days.
+    Quarterly = 2,
+    /// Every day.
+    Daily = 3,
+    /// Every ledger. Accrued as continuous compounding, so the result does not depend on how
+    /// often the line is touched.
+    PerLedger = 4,
+}
+
+/// Capitalization schedule for a line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CapitalizationSchedule {
+    pub period: CapitalizationPeriod,
+    /// Boundary from which the next period is counted.
+    pub last_capitalized_ts: u64,
+}
+
+/// Entry in a line's servicing note log: a hash referencing an off-chain ticket or document.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineNote {
+    pub note_hash: BytesN<32>,
+    pub author: Address,
+    pub created_at: u64,
+}
+
+/// Lifetime aggregates for a line, reported in the final `line_summary` event on closure.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineSummary {
+    pub opened_at: u64,
+    pub total_drawn: i128,
+    /// Principal and interest repaid.
+    pub total_repaid: i128,
+    /// Interest accrued net of rebates, including capitalized interest.
+    pub interest_accrued: i128,
+    pub interest_paid: i128,
+    /// Origination fees withheld from draws.
+    pub fees_paid: i128,
+    /// Highest `utilized_amount` reached.
+    pub max_utilization: i128,
+    /// Number of payment dues that went past their grace period unpaid.
+    pub delinquency_count: u32,
+    /// Principal written off after default.
+    pub written_off: i128,
+    /// Written-off principal recovered through post-default collections.
+    pub recovered: i128,
+}
+
+/// Terms under which posted collateral unlocks additional limit on a line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LimitUnlock {
+    /// Unsecured limit available without collateral.
+    pub base_limit: i128,
+    /// Limit unlocked per unit of collateral, in basis points (e.g. 15000 = 1.5x).
+    pub multiplier_bps: u32,
+    /// Cap on the effective credit limit.
+    pub max_limit: i128,
+}
+
+/// Health of a collateral-linked line returned by `get_health_factor`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HealthFactor {
+    /// Utilized amount plus accrued interest.
+    pub debt: i128,
+    /// Collateral posted, in the collateral token.
+    pub collateral: i128,
+    /// Collateral value in the line's token at the price oracle's latest prices.
+    pub collateral_value: i128,
+    /// Limit unlocked per unit of collateral value (the line's `multiplier_bps`).
+    pub ltv_bps: u32,
+    /// Credit limit the line would have at the collateral's current value.
+    pub borrowing_capacity: i128,
+    /// `borrowing_capacity / debt` in basis points (10_000 = 1.0); `None` without debt.
+    pub health_factor_bps: Option<u32>,
+}
+
+/// Drawing rights a borrower has granted a delegate on their line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Delegation {
+    /// Cumulative amount the delegate may draw under this approval.
+    pub max_amount: i128,
+    /// Amount the delegate has drawn under this approval.
+    pub drawn: i128,
+    /// Time after which the approval can no longer be drawn on; `None` never lapses.
+    pub expires_at: Option<u64>,
+}
+
+/// Approval policy for `correct_accrual`: the admin plus `threshold` distinct auditors must
+/// authorize each correction, and no correction may move accrued interest by more than
+/// `max_correction`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccrualCorrectionPolicy {
+    pub auditors: Vec<Address>,
+    pub threshold: u32,
+    pub max_correction: i128,
+}
+
+/// Installment schedule of a restructured line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TermLoan {
+    pub installment_amount: i128,
+    /// Seconds between installment due dates.
+    pub interval_secs: u64,
+    /// Due date of the installment currently posted.
+    pub next_due_ts: u64,
+}
+
+/// Maturity of a term facility: draws stop at `maturity_ts` and the full balance falls due.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineMaturity {
+    pub maturity_ts: u64,
+    /// Whether a keeper has marked the line matured, posting its full balance as the payment
+    /// due.
+    pub matured: bool,
+}
+
+/// Borrower's pending request to raise their credit limit.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LimitIncreaseRequest {
+    pub requested_limit: i128,
+    pub requested_at: u64,
+}
+
+/// Sensitive admin action executed through the timelock.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TimelockAction {
+    /// Mark the borrower's line as defaulted.
+    DefaultLine(Address),
+    /// Replace the risk oracle contract.
+    SetRiskOracle(Address),
+    /// Withdraw treasury fees: recipient, amount.
+    WithdrawFees(Address, i128),
+    /// Upgrade the contract to the uploaded WASM with this hash.
+    Upgrade(BytesN<32>),
+    /// Change the timelock delay (needed to shorten it).
+    SetDelay(u64),
+    /// Replace the admin council: signers, threshold.
+    SetCouncil(Vec<Address>, u32),
+    /// Transfer a deceased or incapacitated borrower's line to their estate or representative:
+    /// borrower, representative, hash of the supporting evidence. Always waits at least
+    /// `MIN_ESTATE_DELAY`.
+    TransferToEstate(Address, Address, BytesN<32>),
+    /// Approve an arbiter with the given limits, or update its limits.
+    SetArbiter(Address, ArbiterLimits),
+    /// Remove an arbiter from the registry.
+    RemoveArbiter(Address),
+    /// Propose a new admin, who must then call `accept_admin`.
+    ProposeAdmin(Address),
+    /// Set or remove the risk engine.
+    SetRiskEngine(Option<Address>),
+    /// Forgive debt across lines: `(borrower, amount)` entries.
+    ForgiveDebt(Vec<(Address, i128)>),
+    /// Replace the liquidity pool that funds draws.
+    SetLiquidityPool(Address),
+    /// Replace the liquidity source that funds draws without a pool.
+    SetLiquiditySource(Address),
+    /// Replace the liquidity token.
+    SetLiquidityToken(Address),
+    /// Set or clear the price oracle.
+    SetPriceOracle(Option<Address>),
+    /// Force-close the borrower's line in the asset (`None` for the liquidity token), whatever
+    /// is drawn on it.
+    CloseLine(Address, Option<Address>),
+    /// Write off the borrower's defaulted line in the asset (`None` for the liquidity token).
+    WriteOffLine(Address, Option<Address>),
+    /// Write an amount off the borrower's defaulted line: borrower, amount.
+    WriteDownLine(Address, i128),
+}
+
+/// Protocol parameter change applied by the governance contract through
+/// `apply_param_change`. Each variant is validated like the matching admin setter.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParamChange {
+    /// Same as `set_reserve_factor`.
+    ReserveFactor(u32),
+    /// Same as `set_exposure_caps`.
+    ExposureCaps(ExposureCaps),
+    /// Same as `set_risk_tier`: tier name, terms.
+    RiskTier(Symbol, RiskTier),
+    /// Same as `set_penalty_rate`.
+    PenaltyRate(u32),
+}
+
+/// Admin action waiting in the timelock until `eta`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueuedAction {
+    pub action: TimelockAction,
+    pub eta: u64,
+}
+
+/// M-of-N admin council: `threshold` of `signers` must approve a proposal.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminCouncil {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+}
+
+/// Admin action proposed to the council, with the signers that approved it so far.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminProposal {
+    pub action: TimelockAction,
+    pub approvals: Vec<Address>,
+    pub created_at: u64,
+}
+
+/// Terms for one line in a batch open or risk update.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditLineTerms {
+    pub borrower: Address,
+    pub credit_limit: i128,
+    pub interest_rate_bps: u32,
+    pub risk_score: u32,
+}
+
+/// Temporary limit increase: draws may use `extra` on top of the line's limit until `until`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LimitBoost {
+    pub extra: i128,
+    pub until: u64,
+}
+
+/// Rate offered for a draw, guaranteed until `expires_at` when drawn with its `quote_id`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateQuote {
+    pub quote_id: u32,
+    pub borrower: Address,
+    pub amount: i128,
+    pub interest_rate_bps: u32,
+    pub expires_at: u64,
+}
+
+/// Draws above `threshold` wait `delay_secs` before they can be released.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DrawSettlementConfig {
+    pub threshold: i128,
+    pub delay_secs: u64,
+}
+
+/// Large draw queued until `release_at`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingDraw {
+    pub borrower: Address,
+    /// Asset of the line drawn on; `None` for the line in the liquidity token.
+    pub asset: Option<Address>,
+    /// Address receiving the funds (the borrower, or a delegate).
+    pub recipient: Address,
+    pub amount: i128,
+    pub release_at: u64,
+}
+
+/// Borrower-facing snapshot of a line returned by `get_credit_summary`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditSummary {
+    pub status: CreditStatus,
+    pub credit_limit: i128,
+    pub utilized_amount: i128,
+    /// Amount that can be drawn right now, including any active limit boost.
+    pub available_credit: i128,
+    /// `utilized_amount / credit_limit` in basis points.
+    pub utilization_bps: u32,
+    /// Interest owed now, including interest accrued since the last state-changing call.
+    pub accrued_interest: i128,
+    /// Ledger time `accrued_interest` is brought up to.
+    pub last_accrual_ts: u64,
+    /// Operations frozen on the line, per line or protocol-wide.
+    pub frozen_operations: OperationFreezes,
+    /// Maturity date of a term facility; `None` for a revolving line without one.
+    pub maturity_ts: Option<u64>,
+}
+
+/// One-time draw approval signed off-chain by the borrower's voucher key.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DrawVoucher {
+    pub borrower: Address,
+    /// Address the drawn funds are sent to.
+    pub recipient: Address,
+    pub amount: i128,
+    /// Time from which the voucher can no longer be redeemed.
+    pub expires_at: u64,
+    /// Borrower-chosen nonce; each nonce can be redeemed once.
+    pub nonce: u64,
+}
+
+/// Outcome of `simulate_draw`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DrawSimulation {
+    /// Why the draw would fail (e.g. `limit`, `liquidity`, `frozen`); `None` if it would succeed.
+    pub error: Option<Symbol>,
+    /// Utilization after the draw; the current utilization if it would fail.
+    pub new_utilized_amount: i128,
+    /// Origination fee on the draw, withheld from the disbursement or added to utilization.
+    pub fee: i128,
+    /// Amount the recipient would receive, after disbursement rounding and any carried
+    /// remainder.
+    pub disbursed: i128,
+    /// Whether the draw would wait out the large-draw settlement delay.
+    pub queued: bool,
+}
+
+/// Outcome of `simulate_repay`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RepaySimulation {
+    /// Why the repayment would fail (e.g. `closed`, `balance`); `None` if it would succeed.
+    pub error: Option<Symbol>,
+    /// The repayment's split under the repayment waterfall.
+    pub fees_paid: i128,
+    pub interest_paid: i128,
+    pub principal_paid: i128,
+    /// Utilization after the repayment; the current utilization if it would fail.
+    pub new_utilized_amount: i128,
+    /// Whether the repayment would pay off a term loan and close the line.
+    pub closes_line: bool,
+}
+
+/// Line figures for one statement cycle. `start_ts..end_ts` is the cycle window (cut off at
+/// the current time for the running cycle); amounts are in `asset` base units.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Statement {
+    pub cycle: u32,
+    pub asset: Address,
+    pub start_ts: u64,
+    pub end_ts: u64,
+    pub opening_balance: i128,
+    pub drawn: i128,
+    /// Principal and interest repaid.
+    pub repaid: i128,
+    pub closing_balance: i128,
+}
+
+/// Where a line stands right now, returned by `get_balance_statement`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BalanceStatement {
+    pub status: CreditStatus,
+    /// Outstanding principal, including capitalized interest and fees.
+    pub principal: i128,
+    /// Interest accrued and unpaid as of `as_of`.
+    pub accrued_interest: i128,
+    /// Interest deferred under a running promo, owed only if the promo is not met.
+    pub deferred_interest: i128,
+    /// Fees charged to the line since it opened (origination and late fees).
+    pub fees_charged: i128,
+    /// `principal + accrued_interest`.
+    pub total_outstanding: i128,
+    /// Due date of the posted payment, if one is unpaid.
+    pub next_due_ts: Option<u64>,
+    /// Amount due by `next_due_ts`; 0 when nothing is due or the balance is under the grace
+    /// amount.
+    pub minimum_payment: i128,
+    /// Whole days since `next_due_ts` passed with `minimum_payment` unpaid.
+    pub days_past_due: u32,
+    /// Time the line's interest was last accrued to.
+    pub as_of: u64,
+}
+
+/// Behavioral credit score derived from the borrower's activity on this contract.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BehaviorScore {
+    /// 0 (worst) to 100 (best).
+    pub score: u32,
+    pub on_time_repayments: u32,
+    /// Repayments made while a payment due was past its grace period.
+    pub late_repayments: u32,
+    /// Payment dues that went past their grace period unpaid.
+    pub delinquencies: u32,
+    pub defaults: u32,
+    /// Utilization of the line at the last update.
+    pub utilization_bps: u32,
+    pub updated_at: u64,
+}
+
+/// Outcome of `simulate_plan`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlanSimulation {
+    /// Why the plan cannot pay off the line (e.g. `too_low`, `too_long`); `None` if it can.
+    pub error: Option<Symbol>,
+    /// Monthly payments until the balance reaches zero, the last one possibly partial.
+    pub months: u32,
+    /// Interest paid over the plan, including interest already accrued.
+    pub total_interest: i128,
+    pub total_paid: i128,
+}
+
+/// One entry in a borrower's line history: the action, the amount moved (0 for status and
+/// parameter changes) and the line's state right after it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HistoryEntry {
+    /// What happened, named after the matching event topic where there is one, e.g. `drawn`,
+    /// `repay`, `suspend` or `risk_upd`.
+    pub action: Symbol,
+    pub amount: i128,
+    pub utilized_amount: i128,
+    pub credit_limit: i128,
+    pub interest_rate_bps: u32,
+    pub status: CreditStatus,
+    pub timestamp: u64,
+}
+
+/// Administrative entry in a borrower's audit trail, with its position in the line history.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditEntry {
+    pub index: u32,
+    pub entry: HistoryEntry,
+}
+
+/// One page of a borrower's audit trail. `next_cursor` is the history index to resume from,
+/// or `None` once the whole history has been scanned.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditTrailPage {
+    pub entries: Vec<AuditEntry>,
+    pub next_cursor: Option<u32>,
+}
+
+/// Draw scheduled by the borrower for execution at `at`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduledDraw {
+    pub borrower: Address,
+    pub amount: i128,
+    /// Ledger timestamp from which a keeper may execute the draw.
+    pub at: u64,
+}
+
+/// Standing instruction to draw `amount` to `recipient` every `interval_secs`, from `next_at`
+/// until `end_at` (inclusive).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DrawMandate {
+    pub borrower: Address,
+    pub recipient: Address,
+    pub amount: i128,
+    pub interval_secs: u64,
+    /// Ledger timestamp from which the next installment may be executed.
+    pub next_at: u64,
+    pub end_at: u64,
+}
+
+/// Line lifecycle notification passed to registered lifecycle hooks' `on_credit_event`.
+/// `action` is `opened`, `draw`, `repay` or `default`; `amount` is zero for `opened` and
+/// `default`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditHookEvent {
+    pub action: Symbol,
+    pub borrower: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+/// Operations frozen on a line or protocol-wide by `set_operation_freeze`. Each flag blocks its
+/// operation independently of the line's status.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OperationFreezes {
+    /// Blocks every draw path.
+    pub draws: bool,
+    /// Blocks repayments, including auto-repay sweeps.
+    pub repayments: bool,
+    /// Blocks limit increase requests and approvals, and limit boosts.
+    pub limit_increases: bool,
+}
+
+/// Terms on which an alternate token is accepted for repayments (`repay_credit_in`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RepaymentAsset {
+    /// Share of the payment's oracle value not credited to the line, in basis points.
+    pub haircut_bps: u32,
+    /// Receives the alternate token and supplies the line's token in exchange, from an
+    /// allowance granted to the credit contract.
+    pub counterparty: Address,
+}
@@ -0,0 +1,971 @@
+#![no_std]
+
+//! Creditra liquidity pool: lenders deposit the underlying asset for shares, and the credit
+//! contract borrows from the pool to fund draws.
+//!
+//! Accounting is share-based: `total_assets = cash + total_borrowed`, where `cash` is tracked
+//! internally (token donations do not move the share price). Repaid interest increases `cash`
+//! without minting shares, so it accrues to existing lenders pro-rata.
+//!
+//! Every change to a lender's shares or the total supply is checkpointed by ledger, so
+//! `balance_at` and `total_supply_at` give the balances at the end of a past ledger: voting
+//! weight that shares deposited later, even within the same ledger, cannot inflate.
+//!
+//! The admin can stream an incentive token to lenders at a fixed rate per ledger. A global
+//! reward index grows by the emission per share each ledger; every lender's rewards are settled
+//! against it before their shares change, so each is paid pro-rata for the time they held.
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, symbol_short, token,
+    Address, Env, IntoVal, Val,
+};
+
+/// Errors the pool fails with, surfacing to clients as `Error(Contract, #code)`.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum PoolError {
+    /// Shares are outstanding but write-offs have left no assets behind them, so a deposit
+    /// cannot be priced.
+    NoAssetsBackingShares = 1,
+    /// `init` was called on a pool that is already initialized.
+    AlreadyInitialized = 2,
+    /// The pool has not been initialized.
+    NotInitialized = 3,
+    /// An amount is zero or negative where a positive one is required, or negative where a
+    /// non-negative one is.
+    InvalidAmount = 4,
+    /// The deposit is too small to mint a share.
+    DepositTooSmall = 5,
+    /// A share amount is not positive or exceeds the holder's balance.
+    InvalidShareAmount = 6,
+    /// The pool does not have enough idle cash; funds are lent out.
+    InsufficientLiquidity = 7,
+    /// `target_yield_bps` exceeds 10000.
+    InvalidTargetYield = 8,
+    /// The reward token is the pool asset, or differs from the token already being emitted.
+    InvalidRewardToken = 9,
+    /// The emission rate is negative, or the end ledger is not after the current ledger.
+    InvalidEmissionSchedule = 10,
+    /// No emissions are configured.
+    NoEmissions = 11,
+    /// The ledger has not closed yet, so its balances are not final.
+    LedgerNotClosed = 12,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Admin,
+    Asset,
+    CreditContract,
+    Cash,
+    TotalBorrowed,
+    TotalShares,
+    Shares(Address),
+    TargetYieldBps,
+    /// Number of share checkpoints of a lender, or of the total supply for `None`.
+    CheckpointCount(Option<Address>),
+    /// Share checkpoint `n` of a lender, or of the total supply for `None`.
+    Checkpoint(Option<Address>, u32),
+    Emissions,
+    /// Rewards emitted per share since the start, scaled by `REWARD_INDEX_SCALE`.
+    RewardIndex,
+    /// Ledger the reward index was last brought up to.
+    RewardIndexLedger,
+    /// Reward index a lender was last settled at.
+    RewardCheckpoint(Address),
+    /// Settled rewards a lender has not claimed.
+    RewardsOwed(Address),
+}
+
+/// Ledgers per day, at about 5 seconds per ledger.
+const DAY_IN_LEDGERS: u32 = 17_280;
+
+/// The instance (config, cash, supply and the reward index) is extended to live this long on
+/// every mutating call, once its TTL falls below the threshold.
+const INSTANCE_BUMP_THRESHOLD: u32 = 90 * DAY_IN_LEDGERS;
+const INSTANCE_BUMP_AMOUNT: u32 = 150 * DAY_IN_LEDGERS;
+
+/// Lenders' persistent entries (shares, checkpoints, rewards) are extended to live this long
+/// whenever they are written, once their TTL falls below the threshold.
+const LENDER_BUMP_THRESHOLD: u32 = 60 * DAY_IN_LEDGERS;
+const LENDER_BUMP_AMOUNT: u32 = 150 * DAY_IN_LEDGERS;
+
+/// Fixed-point scale of the reward index.
+const REWARD_INDEX_SCALE: i128 = 1_000_000_000_000;
+
+/// Incentive emissions to lenders, set with `set_emissions`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmissionSchedule {
+    /// Incentive token paid out; it must differ from the pool asset.
+    pub token: Address,
+    /// Tokens emitted per ledger, split across all shares.
+    pub rate_per_ledger: i128,
+    /// Last ledger that emits, `None` to emit until changed.
+    pub end_ledger: Option<u32>,
+}
+
+/// Shares held at the end of `ledger`, recorded whenever a balance changes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub shares: i128,
+}
+
+fn bump_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_BUMP_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+/// Write a persistent entry and extend its TTL.
+fn write_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, LENDER_BUMP_THRESHOLD, LENDER_BUMP_AMOUNT);
+}
+
+fn read_i128(env: &Env, key: &DataKey) -> i128 {
+    env.storage().instance().get(key).unwrap_or(0)
+}
+
+fn write_i128(env: &Env, key: &DataKey, value: i128) {
+    env.storage().instance().set(key, &value);
+}
+
+fn asset(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::Asset)
+        .unwrap_or_else(|| panic_with_error!(env, PoolError::NotInitialized))
+}
+
+fn require_admin_auth(env: &Env) {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .unwrap_or_else(|| panic_with_error!(env, PoolError::NotInitialized));
+    admin.require_auth();
+}
+
+fn require_credit_auth(env: &Env) {
+    let credit: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::CreditContract)
+        .unwrap_or_else(|| panic_with_error!(env, PoolError::NotInitialized));
+    credit.require_auth();
+}
+
+fn shares_of(env: &Env, lender: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Shares(lender.clone()))
+        .unwrap_or(0)
+}
+
+fn checkpoint_count(env: &Env, holder: &Option<Address>) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::CheckpointCount(holder.clone()))
+        .unwrap_or(0)
+}
+
+fn checkpoint(env: &Env, holder: &Option<Address>, index: u32) -> Checkpoint {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Checkpoint(holder.clone(), index))
+        .expect("checkpoint missing")
+}
+
+/// Record `shares` as the holder's balance in the current ledger, replacing a checkpoint
+/// already written in it.
+fn write_checkpoint(env: &Env, holder: Option<Address>, shares: i128) {
+    let ledger = env.ledger().sequence();
+    let count = checkpoint_count(env, &holder);
+    let index = match count.checked_sub(1) {
+        Some(last) if checkpoint(env, &holder, last).ledger == ledger => last,
+        _ => count,
+    };
+    write_persistent(
+        env,
+        &DataKey::Checkpoint(holder.clone(), index),
+        &Checkpoint { ledger, shares },
+    );
+    write_persistent(env, &DataKey::CheckpointCount(holder), &(index + 1));
+}
+
+/// The holder's shares at the end of `ledger`: the last checkpoint at or before it.
+fn shares_at(env: &Env, holder: Option<Address>, ledger: u32) -> i128 {
+    if ledger >= env.ledger().sequence() {
+        panic_with_error!(env, PoolError::LedgerNotClosed);
+    }
+    let (mut low, mut high) = (0, checkpoint_count(env, &holder));
+    while low < high {
+        let mid = (low + high) / 2;
+        if checkpoint(env, &holder, mid).ledger <= ledger {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    match low.checked_sub(1) {
+        Some(index) => checkpoint(env, &holder, index).shares,
+        None => 0,
+    }
+}
+
+fn emissions(env: &Env) -> Option<EmissionSchedule> {
+    env.storage().instance().get(&DataKey::Emissions)
+}
+
+/// The reward index brought up to the current ledger.
+fn reward_index(env: &Env) -> i128 {
+    let index = read_i128(env, &DataKey::RewardIndex);
+    let (Some(schedule), Some(last)) = (
+        emissions(env),
+        env.storage()
+            .instance()
+            .get::<_, u32>(&DataKey::RewardIndexLedger),
+    ) else {
+        return index;
+    };
+    let now = env.ledger().sequence();
+    let until = schedule.end_ledger.map_or(now, |end| end.min(now));
+    let total_shares = read_i128(env, &DataKey::TotalShares);
+    if until <= last || total_shares == 0 {
+        return index;
+    }
+    index + schedule.rate_per_ledger * i128::from(until - last) * REWARD_INDEX_SCALE / total_shares
+}
+
+/// Store the reward index brought up to the current ledger and return it. Must run before the
+/// total supply or the schedule changes.
+fn accrue_rewards(env: &Env) -> i128 {
+    let index = reward_index(env);
+    write_i128(env, &DataKey::RewardIndex, index);
+    env.storage()
+        .instance()
+        .set(&DataKey::RewardIndexLedger, &env.ledger().sequence());
+    index
+}
+
+/// Unclaimed rewards of `lender` at reward index `index`.
+fn rewards_owed(env: &Env, lender: &Address, index: i128) -> i128 {
+    let storage = env.storage().persistent();
+    let checkpoint: i128 = storage
+        .get(&DataKey::RewardCheckpoint(lender.clone()))
+        .unwrap_or(0);
+    let owed: i128 = storage
+        .get(&DataKey::RewardsOwed(lender.clone()))
+        .unwrap_or(0);
+    owed + shares_of(env, lender) * (index - checkpoint) / REWARD_INDEX_SCALE
+}
+
+/// Settle the lender's rewards on their current shares; returns what they are owed.
+fn settle_rewards(env: &Env, lender: &Address) -> i128 {
+    let index = accrue_rewards(env);
+    let owed = rewards_owed(env, lender, index);
+    write_persistent(env, &DataKey::RewardCheckpoint(lender.clone()), &index);
+    write_persistent(env, &DataKey::RewardsOwed(lender.clone()), &owed);
+    owed
+}
+
+fn set_shares(env: &Env, lender: &Address, shares: i128) {
+    settle_rewards(env, lender);
+    write_persistent(env, &DataKey::Shares(lender.clone()), &shares);
+    write_checkpoint(env, Some(lender.clone()), shares);
+}
+
+fn set_total_shares(env: &Env, total_shares: i128) {
+    accrue_rewards(env);
+    write_i128(env, &DataKey::TotalShares, total_shares);
+    write_checkpoint(env, None, total_shares);
+}
+
+/// Store `schedule`, first bringing the reward index up to now under the previous one.
+fn set_emissions(env: &Env, schedule: EmissionSchedule) {
+    accrue_rewards(env);
+    env.storage().instance().set(&DataKey::Emissions, &schedule);
+    env.events()
+        .publish((symbol_short!("pool"), symbol_short!("emission")), schedule);
+}
+
+fn total_assets(env: &Env) -> i128 {
+    read_i128(env, &DataKey::Cash) + read_i128(env, &DataKey::TotalBorrowed)
+}
+
+#[contract]
+pub struct Pool;
+
+#[contractimpl]
+impl Pool {
+    /// Initializes the pool with its underlying asset and the credit contract allowed to borrow.
+    /// `admin` must authorize, so the call cannot be front-run with another credit contract.
+    pub fn init(env: Env, admin: Address, asset: Address, credit_contract: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, PoolError::AlreadyInitialized);
+        }
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Asset, &asset);
+        env.storage()
+            .instance()
+            .set(&DataKey::CreditContract, &credit_contract);
+        bump_instance(&env);
+    }
+
+    /// Deposit `amount` of the underlying asset and mint shares to `lender`.
+    ///
+    /// # Panics
+    /// * `InvalidAmount` if `amount` <= 0
+    /// * `DepositTooSmall` if the deposit is too small to mint a share
+    /// * `NoAssetsBackingShares` if shares are outstanding but the pool's assets were written
+    ///   off to zero
+    ///
+    /// # Events
+    /// Emits `(pool, deposit)` with `(lender, amount, shares)`.
+    pub fn deposit(env: Env, lender: Address, amount: i128) -> i128 {
+        lender.require_auth();
+        bump_instance(&env);
+        if amount <= 0 {
+            panic_with_error!(&env, PoolError::InvalidAmount);
+        }
+        let total_shares = read_i128(&env, &DataKey::TotalShares);
+        let shares = if total_shares == 0 {
+            amount
+        } else {
+            let assets = total_assets(&env);
+            if assets == 0 {
+                panic_with_error!(&env, PoolError::NoAssetsBackingShares);
+            }
+            amount * total_shares / assets
+        };
+        if shares <= 0 {
+            panic_with_error!(&env, PoolError::DepositTooSmall);
+        }
+
+        token::Client::new(&env, &asset(&env)).transfer(
+            &lender,
+            &env.current_contract_address(),
+            &amount,
+        );
+        write_i128(
+            &env,
+            &DataKey::Cash,
+            read_i128(&env, &DataKey::Cash) + amount,
+        );
+        set_total_shares(&env, total_shares + shares);
+        set_shares(&env, &lender, shares_of(&env, &lender) + shares);
+        env.events().publish(
+            (symbol_short!("pool"), symbol_short!("deposit")),
+            (lender, amount, shares),
+        );
+        shares
+    }
+
+    /// Burn `shares` from `lender` and pay out their value in the underlying asset.
+    ///
+    /// # Panics
+    /// * `InvalidShareAmount` if `shares` <= 0 or exceeds the lender's balance
+    /// * `InsufficientLiquidity` if the pool does not have enough idle cash (funds are lent out)
+    ///
+    /// # Events
+    /// Emits `(pool, withdraw)` with `(lender, amount, shares)`.
+    pub fn withdraw(env: Env, lender: Address, shares: i128) -> i128 {
+        lender.require_auth();
+        bump_instance(&env);
+        let balance = shares_of(&env, &lender);
+        if shares <= 0 || shares > balance {
+            panic_with_error!(&env, PoolError::InvalidShareAmount);
+        }
+        let total_shares = read_i128(&env, &DataKey::TotalShares);
+        let amount = shares * total_assets(&env) / total_shares;
+        let cash = read_i128(&env, &DataKey::Cash);
+        if amount > cash {
+            panic_with_error!(&env, PoolError::InsufficientLiquidity);
+        }
+
+        write_i128(&env, &DataKey::Cash, cash - amount);
+        set_total_shares(&env, total_shares - shares);
+        set_shares(&env, &lender, balance - shares);
+        token::Client::new(&env, &asset(&env)).transfer(
+            &env.current_contract_address(),
+            &lender,
+            &amount,
+        );
+        env.events().publish(
+            (symbol_short!("pool"), symbol_short!("withdraw")),
+            (lender, amount, shares),
+        );
+        amount
+    }
+
+    /// Move `shares` from `from` to `to`. `from` must authorize.
+    ///
+    /// # Panics
+    /// * `InvalidShareAmount` if `shares` <= 0 or exceeds the balance of `from`
+    ///
+    /// # Events
+    /// Emits `(pool, transfer)` with `(from, to, shares)`.
+    pub fn transfer_shares(env: Env, from: Address, to: Address, shares: i128) {
+        from.require_auth();
+        bump_instance(&env);
+        let balance = shares_of(&env, &from);
+        if shares <= 0 || shares > balance {
+            panic_with_error!(&env, PoolError::InvalidShareAmount);
+        }
+        set_shares(&env, &from, balance - shares);
+        set_shares(&env, &to, shares_of(&env, &to) + shares);
+        env.events().publish(
+            (symbol_short!("pool"), symbol_short!("transfer")),
+            (from, to, shares),
+        );
+    }
+
+    /// Lend `amount` to `to` on behalf of the credit contract. Credit contract only.
+    ///
+    /// # Panics
+    /// * `InvalidAmount` if `amount` <= 0
+    /// * `InsufficientLiquidity` if `amount` exceeds idle cash
+    pub fn borrow(env: Env, to: Address, amount: i128) {
+        require_credit_auth(&env);
+        bump_instance(&env);
+        let cash = read_i128(&env, &DataKey::Cash);
+        if amount <= 0 {
+            panic_with_error!(&env, PoolError::InvalidAmount);
+        }
+        if amount > cash {
+            panic_with_error!(&env, PoolError::InsufficientLiquidity);
+        }
+        write_i128(&env, &DataKey::Cash, cash - amount);
+        write_i128(
+            &env,
+            &DataKey::TotalBorrowed,
+            read_i128(&env, &DataKey::TotalBorrowed) + amount,
+        );
+        token::Client::new(&env, &asset(&env)).transfer(
+            &env.current_contract_address(),
+            &to,
+            &amount,
+        );
+    }
+
+    /// Record a repayment whose tokens (`principal + interest`) the credit contract has already
+    /// transferred to the pool. Credit contract only.
+    ///
+    /// # Panics
+    /// * `InvalidAmount` if `principal` or `interest` < 0
+    pub fn repay(env: Env, principal: i128, interest: i128) {
+        require_credit_auth(&env);
+        bump_instance(&env);
+        if principal < 0 || interest < 0 {
+            panic_with_error!(&env, PoolError::InvalidAmount);
+        }
+        let borrowed = read_i128(&env, &DataKey::TotalBorrowed);
+        write_i128(
+            &env,
+            &DataKey::TotalBorrowed,
+            borrowed.saturating_sub(principal).max(0),
+        );
+        write_i128(
+            &env,
+            &DataKey::Cash,
+            read_i128(&env, &DataKey::Cash) + principal + interest,
+        );
+    }
+
+    /// Write off `amount` of outstanding principal that will not be repaid. The loss is borne
+    /// by lenders through a lower share price. Credit contract only.
+    ///
+    /// # Panics
+    /// * `InvalidAmount` if `amount` < 0
+    ///
+    /// # Events
+    /// Emits `(pool, write_off)` with `amount`.
+    pub fn write_off(env: Env, amount: i128) {
+        require_credit_auth(&env);
+        bump_instance(&env);
+        if amount < 0 {
+            panic_with_error!(&env, PoolError::InvalidAmount);
+        }
+        let borrowed = read_i128(&env, &DataKey::TotalBorrowed);
+        write_i128(&env, &DataKey::TotalBorrowed, (borrowed - amount).max(0));
+        env.events()
+            .publish((symbol_short!("pool"), symbol_short!("write_off")), amount);
+    }
+
+    /// Set the annual yield (bps) the pool targets for lenders, i.e. its cost of funds.
+    /// The credit contract prices lines no lower than this plus its spread. Admin only.
+    ///
+    /// # Panics
+    /// * `InvalidTargetYield` if `target_yield_bps` > 10000
+    pub fn set_target_yield(env: Env, target_yield_bps: u32) {
+        require_admin_auth(&env);
+        bump_instance(&env);
+        if target_yield_bps > 10_000 {
+            panic_with_error!(&env, PoolError::InvalidTargetYield);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::TargetYieldBps, &target_yield_bps);
+    }
+
+    /// Stream `rate_per_ledger` of `token` to lenders, pro-rata to their shares, until
+    /// `end_ledger` (or until changed, for `None`). Replaces the current schedule from this
+    /// ledger on; rewards emitted so far are kept. The admin funds the emissions by sending
+    /// `token` to the pool. Admin only.
+    ///
+    /// # Panics
+    /// * `InvalidRewardToken` if `token` is the pool asset, or differs from the token already
+    ///   being emitted
+    /// * `InvalidEmissionSchedule` if `rate_per_ledger` < 0, or `end_ledger` is not after the
+    ///   current ledger
+    ///
+    /// # Events
+    /// Emits `(pool, emission)` with the `EmissionSchedule`.
+    pub fn set_emissions(env: Env, token: Address, rate_per_ledger: i128, end_ledger: Option<u32>) {
+        require_admin_auth(&env);
+        bump_instance(&env);
+        if token == asset(&env) {
+            panic_with_error!(&env, PoolError::InvalidRewardToken);
+        }
+        if emissions(&env).is_some_and(|schedule| schedule.token != token) {
+            panic_with_error!(&env, PoolError::InvalidRewardToken);
+        }
+        if rate_per_ledger < 0 || end_ledger.is_some_and(|end| end <= env.ledger().sequence()) {
+            panic_with_error!(&env, PoolError::InvalidEmissionSchedule);
+        }
+        set_emissions(
+            &env,
+            EmissionSchedule {
+                token,
+                rate_per_ledger,
+                end_ledger,
+            },
+        );
+    }
+
+    /// Stop emissions from the next ledger on; rewards emitted so far stay claimable. Admin
+    /// only.
+    ///
+    /// # Panics
+    /// * `NoEmissions` if no emissions are configured
+    ///
+    /// # Events
+    /// Emits `(pool, emission)` with the `EmissionSchedule`.
+    pub fn end_emissions(env: Env) {
+        require_admin_auth(&env);
+        bump_instance(&env);
+        let mut schedule =
+            emissions(&env).unwrap_or_else(|| panic_with_error!(&env, PoolError::NoEmissions));
+        let now = env.ledger().sequence();
+        schedule.end_ledger = Some(schedule.end_ledger.map_or(now, |end| end.min(now)));
+        set_emissions(&env, schedule);
+    }
+
+    /// Current emission schedule (view function).
+    pub fn get_emissions(env: Env) -> Option<EmissionSchedule> {
+        emissions(&env)
+    }
+
+    /// Rewards `lender` can claim now (view function).
+    pub fn pending_rewards(env: Env, lender: Address) -> i128 {
+        rewards_owed(&env, &lender, reward_index(&env))
+    }
+
+    /// Pay `lender` their unclaimed rewards in the incentive token. Returns the amount paid.
+    ///
+    /// # Events
+    /// Emits `(pool, claim)` with `(lender, amount)` when anything is paid.
+    pub fn claim_rewards(env: Env, lender: Address) -> i128 {
+        lender.require_auth();
+        bump_instance(&env);
+        let owed = settle_rewards(&env, &lender);
+        let Some(schedule) = emissions(&env).filter(|_| owed > 0) else {
+            return 0;
+        };
+        write_persistent(&env, &DataKey::RewardsOwed(lender.clone()), &0_i128);
+        token::Client::new(&env, &schedule.token).transfer(
+            &env.current_contract_address(),
+            &lender,
+            &owed,
+        );
+        env.events().publish(
+            (symbol_short!("pool"), symbol_short!("claim")),
+            (lender, owed),
+        );
+        owed
+    }
+
+    /// Target lender yield in bps (view function).
+    pub fn target_yield_bps(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TargetYieldBps)
+            .unwrap_or(0)
+    }
+
+    /// Underlying asset of the pool (view function).
+    pub fn asset(env: Env) -> Address {
+        asset(&env)
+    }
+
+    /// Idle cash available to borrow or withdraw (view function).
+    pub fn available_liquidity(env: Env) -> i128 {
+        read_i128(&env, &DataKey::Cash)
+    }
+
+    /// Outstanding principal lent to the credit contract (view function).
+    pub fn total_borrowed(env: Env) -> i128 {
+        read_i128(&env, &DataKey::TotalBorrowed)
+    }
+
+    /// Cash plus outstanding principal (view function).
+    pub fn total_assets(env: Env) -> i128 {
+        total_assets(&env)
+    }
+
+    /// Total shares outstanding (view function).
+    pub fn total_shares(env: Env) -> i128 {
+        read_i128(&env, &DataKey::TotalShares)
+    }
+
+    /// Shares held by `lender` (view function).
+    pub fn shares_of(env: Env, lender: Address) -> i128 {
+        shares_of(&env, &lender)
+    }
+
+    /// Shares held by `lender` at the end of `ledger`, for voting weight snapshots (view
+    /// function).
+    ///
+    /// # Panics
+    /// * `LedgerNotClosed` if `ledger` is the current ledger or later
+    pub fn balance_at(env: Env, lender: Address, ledger: u32) -> i128 {
+        shares_at(&env, Some(lender), ledger)
+    }
+
+    /// Total shares outstanding at the end of `ledger` (view function).
+    ///
+    /// # Panics
+    /// * `LedgerNotClosed` if `ledger` is the current ledger or later
+    pub fn total_supply_at(env: Env, ledger: u32) -> i128 {
+        shares_at(&env, None, ledger)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::storage::{Instance as _, Persistent as _};
+    use soroban_sdk::testutils::Address as _;
+    use soroban_sdk::testutils::Ledger;
+    use soroban_sdk::token::StellarAssetClient;
+
+    struct Setup<'a> {
+        pool: PoolClient<'a>,
+        pool_id: Address,
+        token: token::Client<'a>,
+        credit: Address,
+    }
+
+    fn setup(env: &Env) -> Setup<'_> {
+        env.mock_all_auths();
+        let admin = Address::generate(env);
+        let credit = Address::generate(env);
+        let asset = env
+            .register_stellar_asset_contract_v2(Address::generate(env))
+            .address();
+        let pool_id = env.register(Pool, ());
+        let pool = PoolClient::new(env, &pool_id);
+        pool.init(&admin, &asset, &credit);
+        Setup {
+            pool,
+            pool_id,
+            token: token::Client::new(env, &asset),
+            credit,
+        }
+    }
+
+    fn fund(env: &Env, s: &Setup, who: &Address, amount: i128) {
+        StellarAssetClient::new(env, &s.token.address).mint(who, &amount);
+    }
+
+    #[test]
+    fn test_deposit_and_withdraw_round_trip() {
+        let env = Env::default();
+        let s = setup(&env);
+        let lender = Address::generate(&env);
+        fund(&env, &s, &lender, 1_000);
+
+        assert_eq!(s.pool.deposit(&lender, &1_000_i128), 1_000);
+        assert_eq!(s.pool.total_assets(), 1_000);
+        assert_eq!(s.token.balance(&s.pool_id), 1_000);
+
+        assert_eq!(s.pool.withdraw(&lender, &400_i128), 400);
+        assert_eq!(s.pool.shares_of(&lender), 600);
+        assert_eq!(s.token.balance(&lender), 400);
+    }
+
+    #[test]
+    fn test_interest_accrues_to_lenders_pro_rata() {
+        let env = Env::default();
+        let s = setup(&env);
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        let borrower = Address::generate(&env);
+        fund(&env, &s, &a, 1_000);
+        fund(&env, &s, &b, 3_000);
+        s.pool.deposit(&a, &1_000_i128);
+        s.pool.deposit(&b, &3_000_i128);
+
+        s.pool.borrow(&borrower, &2_000_i128);
+        assert_eq!(s.pool.available_liquidity(), 2_000);
+        assert_eq!(s.pool.total_assets(), 4_000);
+
+        // Borrower repays principal plus 400 interest through the credit contract.
+        fund(&env, &s, &borrower, 400);
+        s.token.transfer(&borrower, &s.pool_id, &2_400_i128);
+        s.pool.repay(&2_000_i128, &400_i128);
+
+        assert_eq!(s.pool.total_assets(), 4_400);
+        assert_eq!(s.pool.withdraw(&a, &1_000_i128), 1_100);
+        assert_eq!(s.pool.withdraw(&b, &3_000_i128), 3_300);
+    }
+
+    #[test]
+    fn test_later_depositor_gets_fewer_shares_after_interest() {
+        let env = Env::default();
+        let s = setup(&env);
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        fund(&env, &s, &a, 1_000);
+        fund(&env, &s, &b, 1_100);
+        s.pool.deposit(&a, &1_000_i128);
+        s.pool.repay(&0_i128, &100_i128);
+
+        assert_eq!(s.pool.deposit(&b, &1_100_i128), 1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #7)")]
+    fn test_withdraw_limited_by_idle_cash() {
+        let env = Env::default();
+        let s = setup(&env);
+        let lender = Address::generate(&env);
+        fund(&env, &s, &lender, 1_000);
+        s.pool.deposit(&lender, &1_000_i128);
+        s.pool.borrow(&Address::generate(&env), &800_i128);
+        s.pool.withdraw(&lender, &500_i128);
+    }
+
+    #[test]
+    fn test_write_off_lowers_share_value() {
+        let env = Env::default();
+        let s = setup(&env);
+        let lender = Address::generate(&env);
+        fund(&env, &s, &lender, 1_000);
+        s.pool.deposit(&lender, &1_000_i128);
+        s.pool.borrow(&Address::generate(&env), &400_i128);
+
+        s.pool.write_off(&100_i128);
+        assert_eq!(s.pool.total_borrowed(), 300);
+        assert_eq!(s.pool.total_assets(), 900);
+    }
+
+    #[test]
+    fn test_deposit_rejected_once_everything_is_written_off() {
+        let env = Env::default();
+        let s = setup(&env);
+        let lender = Address::generate(&env);
+        fund(&env, &s, &lender, 2_000);
+        s.pool.deposit(&lender, &1_000_i128);
+        s.pool.borrow(&Address::generate(&env), &1_000_i128);
+        s.pool.write_off(&1_000_i128);
+        assert_eq!(s.pool.total_assets(), 0);
+
+        assert_eq!(
+            s.pool.try_deposit(&lender, &1_000_i128),
+            Err(Ok(PoolError::NoAssetsBackingShares.into()))
+        );
+    }
+
+    fn set_ledger(env: &Env, sequence: u32) {
+        env.ledger().with_mut(|li| li.sequence_number = sequence);
+    }
+
+    #[test]
+    fn test_share_checkpoints_snapshot_past_ledgers() {
+        let env = Env::default();
+        let s = setup(&env);
+        let a = Address::generate(&env);
+        let b = Address::generate(&env);
+        fund(&env, &s, &a, 1_000);
+        fund(&env, &s, &b, 501);
+
+        set_ledger(&env, 10);
+        s.pool.deposit(&a, &1_000_i128);
+        set_ledger(&env, 20);
+        s.pool.transfer_shares(&a, &b, &300_i128);
+        s.pool.withdraw(&a, &200_i128);
+        // Both deposits land in one checkpoint, counted from the end of ledger 30.
+        set_ledger(&env, 30);
+        s.pool.deposit(&b, &500_i128);
+        s.pool.deposit(&b, &1_i128);
+        set_ledger(&env, 31);
+
+        assert_eq!(s.pool.balance_at(&a, &9), 0);
+        assert_eq!(s.pool.balance_at(&a, &10), 1_000);
+        assert_eq!(s.pool.balance_at(&a, &19), 1_000);
+        assert_eq!(s.pool.balance_at(&a, &20), 500);
+        assert_eq!(s.pool.balance_at(&b, &20), 300);
+        assert_eq!(s.pool.balance_at(&b, &29), 300);
+        assert_eq!(s.pool.balance_at(&b, &30), 801);
+        assert_eq!(s.pool.total_supply_at(&15), 1_000);
+        assert_eq!(s.pool.total_supply_at(&25), 800);
+        assert_eq!(s.pool.total_supply_at(&30), 1_301);
+        assert!(s.pool.try_balance_at(&a, &31).is_err());
+    }
+
+    #[test]
+    fn test_lender_entries_ttl_extended_on_write() {
+        let env = Env::default();
+        let s = setup(&env);
+        let lender = Address::generate(&env);
+        fund(&env, &s, &lender, 1_000);
+        s.pool.deposit(&lender, &1_000_i128);
+
+        env.as_contract(&s.pool_id, || {
+            let storage = env.storage().persistent();
+            for key in [
+                DataKey::Shares(lender.clone()),
+                DataKey::CheckpointCount(Some(lender.clone())),
+                DataKey::Checkpoint(Some(lender.clone()), 0),
+                DataKey::Checkpoint(None, 0),
+                DataKey::RewardCheckpoint(lender.clone()),
+                DataKey::RewardsOwed(lender.clone()),
+            ] {
+                assert_eq!(storage.get_ttl(&key), LENDER_BUMP_AMOUNT);
+            }
+        });
+    }
+
+    #[test]
+    fn test_instance_ttl_extended_on_mutating_calls() {
+        let env = Env::default();
+        let s = setup(&env);
+        let lender = Address::generate(&env);
+        fund(&env, &s, &lender, 1_000);
+        s.pool.deposit(&lender, &1_000_i128);
+        let instance_ttl = || env.as_contract(&s.pool_id, || env.storage().instance().get_ttl());
+        assert_eq!(instance_ttl(), INSTANCE_BUMP_AMOUNT);
+
+        // Once the TTL falls below the threshold, the next mutating call extends it again.
+        env.ledger()
+            .with_mut(|li| li.sequence_number += INSTANCE_BUMP_AMOUNT - 1_000);
+        assert!(instance_ttl() < INSTANCE_BUMP_THRESHOLD);
+        s.pool.set_target_yield(&450_u32);
+        assert_eq!(instance_ttl(), INSTANCE_BUMP_AMOUNT);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #4)")]
+    fn test_borrow_rejects_non_positive_amount() {
+        let env = Env::default();
+        let s = setup(&env);
+        let lender = Address::generate(&env);
+        fund(&env, &s, &lender, 1_000);
+        s.pool.deposit(&lender, &1_000_i128);
+        s.pool.borrow(&s.credit, &0_i128);
+    }
+
+    #[test]
+    fn test_emissions_stream_rewards_pro_rata_over_time() {
+        let env = Env::default();
+        let s = setup(&env);
+        let (a, b) = (Address::generate(&env), Address::generate(&env));
+        fund(&env, &s, &a, 1_000);
+        fund(&env, &s, &b, 1_000);
+        let reward = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        StellarAssetClient::new(&env, &reward).mint(&s.pool_id, &10_000_i128);
+
+        set_ledger(&env, 10);
+        s.pool.deposit(&a, &1_000_i128);
+        s.pool.set_emissions(&reward, &100_i128, &Some(60_u32));
+        // Ledgers 10-20: `a` holds every share.
+        set_ledger(&env, 20);
+        s.pool.deposit(&b, &1_000_i128);
+        // 20-30: half each; then `a` hands `b` half of its shares.
+        set_ledger(&env, 30);
+        s.pool.transfer_shares(&a, &b, &500_i128);
+        // 30-40: a quarter to `a`; then `b` withdraws 1_000.
+        set_ledger(&env, 40);
+        s.pool.withdraw(&b, &1_000_i128);
+        // 40-60: half each, and nothing after the end ledger.
+        set_ledger(&env, 70);
+        assert_eq!(s.pool.pending_rewards(&a), 1_000 + 500 + 250 + 1_000);
+        assert_eq!(s.pool.pending_rewards(&b), 500 + 750 + 1_000);
+
+        let reward_token = token::Client::new(&env, &reward);
+        assert_eq!(s.pool.claim_rewards(&a), 2_750);
+        assert_eq!(reward_token.balance(&a), 2_750);
+        assert_eq!(s.pool.pending_rewards(&a), 0);
+        assert_eq!(s.pool.claim_rewards(&a), 0);
+
+        // A new rate applies from now on, until emissions are ended.
+        s.pool.set_emissions(&reward, &10_i128, &None);
+        set_ledger(&env, 80);
+        s.pool.end_emissions();
+        set_ledger(&env, 90);
+        assert_eq!(s.pool.pending_rewards(&a), 50);
+        assert_eq!(s.pool.claim_rewards(&b), 2_250 + 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #9)")]
+    fn test_emissions_cannot_pay_out_the_pool_asset() {
+        let env = Env::default();
+        let s = setup(&env);
+        s.pool.set_emissions(&s.token.address, &100_i128, &None);
+    }
+
+    #[test]
+    fn test_target_yield_defaults_to_zero() {
+        let env = Env::default();
+        let s = setup(&env);
+        assert_eq!(s.pool.target_yield_bps(), 0);
+        s.pool.set_target_yield(&450_u32);
+        assert_eq!(s.pool.target_yield_bps(), 450);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_init_requires_admin_auth() {
+        let env = Env::default();
+        let asset = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        let pool = PoolClient::new(&env, &env.register(Pool, ()));
+        pool.init(&Address::generate(&env), &asset, &Address::generate(&env));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_borrow_requires_credit_contract_auth() {
+        let env = Env::default();
+        let s = setup(&env);
+        let lender = Address::generate(&env);
+        fund(&env, &s, &lender, 1_000);
+        s.pool.deposit(&lender, &1_000_i128);
+
+        env.set_auths(&[]);
+        s.pool.borrow(&s.credit, &100_i128);
+    }
+}
@@ -0,0 +1,140 @@
+#![no_std]
+
+//! Creditra risk oracle: publishes borrower risk scores for the credit contract.
+//!
+//! The credit contract reads scores through `get_score`; the returned `ScoreRecord`
+//! layout is part of the cross-contract interface and must stay stable.
+
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env};
+
+/// Maximum risk score (0–100 scale), matching the credit contract.
+const MAX_RISK_SCORE: u32 = 100;
+
+/// Latest score published for a borrower.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScoreRecord {
+    pub score: u32,
+    pub updated_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Admin,
+    Score(Address),
+}
+
+fn require_admin_auth(env: &Env) -> Address {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .expect("admin not set");
+    admin.require_auth();
+    admin
+}
+
+#[contract]
+pub struct RiskOracle;
+
+#[contractimpl]
+impl RiskOracle {
+    /// Initializes the oracle with the address allowed to publish scores.
+    pub fn init(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic!("already initialized");
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+    }
+
+    /// Publish the latest risk score for a borrower (admin only).
+    ///
+    /// # Panics
+    /// * If `score` > 100
+    ///
+    /// # Events
+    /// Emits `(oracle, score)` with `(borrower, ScoreRecord)`.
+    pub fn set_score(env: Env, borrower: Address, score: u32) {
+        require_admin_auth(&env);
+        if score > MAX_RISK_SCORE {
+            panic!("score exceeds maximum");
+        }
+        let record = ScoreRecord {
+            score,
+            updated_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Score(borrower.clone()), &record);
+        env.events().publish(
+            (symbol_short!("oracle"), symbol_short!("score")),
+            (borrower, record),
+        );
+    }
+
+    /// Latest score for a borrower, or `None` if never scored (view function).
+    pub fn get_score(env: Env, borrower: Address) -> Option<ScoreRecord> {
+        env.storage().persistent().get(&DataKey::Score(borrower))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger};
+
+    fn setup(env: &Env) -> (Address, RiskOracleClient<'_>) {
+        env.mock_all_auths();
+        let admin = Address::generate(env);
+        let contract_id = env.register(RiskOracle, ());
+        let client = RiskOracleClient::new(env, &contract_id);
+        client.init(&admin);
+        (admin, client)
+    }
+
+    #[test]
+    fn test_set_and_get_score() {
+        let env = Env::default();
+        let (_admin, client) = setup(&env);
+        let borrower = Address::generate(&env);
+
+        assert_eq!(client.get_score(&borrower), None);
+        env.ledger().with_mut(|li| li.timestamp = 42);
+        client.set_score(&borrower, &65_u32);
+        assert_eq!(
+            client.get_score(&borrower),
+            Some(ScoreRecord {
+                score: 65,
+                updated_at: 42
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "score exceeds maximum")]
+    fn test_set_score_rejects_out_of_range() {
+        let env = Env::default();
+        let (_admin, client) = setup(&env);
+        client.set_score(&Address::generate(&env), &101_u32);
+    }
+
+    #[test]
+    #[should_panic(expected = "already initialized")]
+    fn test_init_twice() {
+        let env = Env::default();
+        let (admin, client) = setup(&env);
+        client.init(&admin);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_set_score_requires_admin_auth() {
+        let env = Env::default();
+        let admin = Address::generate(&env);
+        let contract_id = env.register(RiskOracle, ());
+        let client = RiskOracleClient::new(&env, &contract_id);
+        client.init(&admin);
+        client.set_score(&Address::generate(&env), &50_u32);
+    }
+}
@@ -0,0 +1,802 @@
+#![no_std]
+
+//! Creditra securitization: pools purchased shares of credit line receivables and passes their
+//! repayments to investors through a senior and a junior tranche.
+//!
+//! Investors buy tranche shares one-for-one with the asset. The admin spends the raised cash on
+//! receivable shares of credit lines (`add_line`). From then on the credit contract pays this
+//! contract its share of every repayment and notifies it through `on_receivable_payment`. Each
+//! collection runs through a sequential waterfall: the senior coupon first, then senior
+//! principal, then junior principal, with whatever is left going to the junior tranche as
+//! excess spread. The junior tranche therefore absorbs shortfalls and losses first. Amounts
+//! paid to a tranche are credited to its share holders pro rata and withdrawn with `claim`.
+//!
+//! Tranche shares are transferable with a SEP-41-style interface (`balance`, `transfer`,
+//! `approve`, `allowance`, `transfer_from`), taking the tranche as an extra argument. Amounts
+//! already paid to transferred shares stay claimable by the seller.
+
+use creditra_interface::CreditClient;
+use soroban_sdk::auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, token, vec, Address, Env, IntoVal, Symbol,
+    Vec,
+};
+
+/// Basis point denominator for the senior coupon rate.
+const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Seconds in a year, for accruing the senior coupon.
+const SECONDS_PER_YEAR: i128 = 365 * 24 * 60 * 60;
+
+/// Fixed-point scale of the per-share distribution accumulators.
+const SCALE: i128 = 1_000_000_000;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Tranche {
+    Senior,
+    Junior,
+}
+
+/// Shares outstanding in a tranche, the principal not yet paid back to its holders and the
+/// total it has been paid.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TrancheState {
+    pub shares: i128,
+    pub principal: i128,
+    pub distributed: i128,
+}
+
+/// An investor's shares in a tranche, the accumulator value they were last credited at and
+/// what they are owed but have not claimed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Holding {
+    shares: i128,
+    paid_per_share: i128,
+    owed: i128,
+}
+
+/// Shares of a tranche a spender may transfer on a holder's behalf, until `expiration_ledger`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Allowance {
+    pub shares: i128,
+    pub expiration_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Admin,
+    Asset,
+    CreditContract,
+    Pool,
+    /// Annual senior coupon, in basis points of senior principal.
+    SeniorRate,
+    /// Raised cash not yet spent on receivables.
+    Cash,
+    /// Borrowers whose receivables the contract holds a share of.
+    Lines,
+    Tranche(Tranche),
+    /// Amount paid to a tranche per share, scaled by `SCALE`.
+    PerShare(Tranche),
+    /// Senior coupon accrued and not yet paid.
+    CouponOwed,
+    /// When the senior coupon was last accrued.
+    CouponTs,
+    Holding(Address, Tranche),
+    /// Allowance of a (holder, spender) pair in a tranche, kept in temporary storage.
+    Allowance(Address, Address, Tranche),
+}
+
+fn require_admin_auth(env: &Env) {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .expect("not initialized");
+    admin.require_auth();
+}
+
+fn require_credit_auth(env: &Env) {
+    credit(env).require_auth();
+}
+
+fn credit(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::CreditContract)
+        .expect("not initialized")
+}
+
+fn asset(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::Asset)
+        .expect("not initialized")
+}
+
+fn read_i128(env: &Env, key: &DataKey) -> i128 {
+    env.storage().instance().get(key).unwrap_or(0)
+}
+
+fn write_i128(env: &Env, key: &DataKey, value: i128) {
+    env.storage().instance().set(key, &value);
+}
+
+fn tranche_state(env: &Env, tranche: Tranche) -> TrancheState {
+    env.storage()
+        .instance()
+        .get(&DataKey::Tranche(tranche))
+        .unwrap_or(TrancheState {
+            shares: 0,
+            principal: 0,
+            distributed: 0,
+        })
+}
+
+fn set_tranche_state(env: &Env, tranche: Tranche, state: &TrancheState) {
+    env.storage()
+        .instance()
+        .set(&DataKey::Tranche(tranche), state);
+}
+
+fn lines(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Lines)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Senior coupon owed as of now: the unpaid coupon plus what senior principal has accrued since
+/// it was last brought up to date.
+fn coupon_owed(env: &Env) -> i128 {
+    let since: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::CouponTs)
+        .unwrap_or(env.ledger().timestamp());
+    let elapsed = (env.ledger().timestamp() - since) as i128;
+    let rate: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::SeniorRate)
+        .unwrap_or(0);
+    let principal = tranche_state(env, Tranche::Senior).principal;
+    read_i128(env, &DataKey::CouponOwed)
+        + principal * rate as i128 * elapsed / (BPS_DENOMINATOR * SECONDS_PER_YEAR)
+}
+
+/// Bring the senior coupon up to date, before senior principal changes.
+fn accrue_coupon(env: &Env) {
+    write_i128(env, &DataKey::CouponOwed, coupon_owed(env));
+    env.storage()
+        .instance()
+        .set(&DataKey::CouponTs, &env.ledger().timestamp());
+}
+
+fn holding(env: &Env, holder: &Address, tranche: Tranche) -> Holding {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Holding(holder.clone(), tranche))
+        .unwrap_or(Holding {
+            shares: 0,
+            paid_per_share: 0,
+            owed: 0,
+        })
+}
+
+/// The holder's holding with everything paid to the tranche since they were last credited
+/// moved into `owed`.
+fn settled(env: &Env, holder: &Address, tranche: Tranche) -> Holding {
+    let mut holding = holding(env, holder, tranche);
+    let per_share = read_i128(env, &DataKey::PerShare(tranche));
+    holding.owed += holding.shares * (per_share - holding.paid_per_share) / SCALE;
+    holding.paid_per_share = per_share;
+    holding
+}
+
+fn set_holding(env: &Env, holder: &Address, tranche: Tranche, holding: &Holding) {
+    let key = DataKey::Holding(holder.clone(), tranche);
+    if holding.shares == 0 && holding.owed == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, holding);
+    }
+}
+
+/// Move `shares` of `tranche` from `from` to `to`, settling both holdings first.
+fn move_shares(env: &Env, from: &Address, to: &Address, tranche: Tranche, shares: i128) {
+    let mut sender = settled(env, from, tranche);
+    if shares <= 0 || shares > sender.shares {
+        panic!("invalid share amount");
+    }
+    sender.shares -= shares;
+    set_holding(env, from, tranche, &sender);
+    let mut receiver = settled(env, to, tranche);
+    receiver.shares += shares;
+    set_holding(env, to, tranche, &receiver);
+    env.events().publish(
+        (symbol_short!("sec"), symbol_short!("transfer")),
+        (from.clone(), to.clone(), tranche, shares),
+    );
+}
+
+/// The spender's allowance from `holder`, or zero once it has expired.
+fn allowance(env: &Env, holder: &Address, spender: &Address, tranche: Tranche) -> i128 {
+    env.storage()
+        .temporary()
+        .get::<_, Allowance>(&DataKey::Allowance(
+            holder.clone(),
+            spender.clone(),
+            tranche,
+        ))
+        .filter(|allowance| allowance.expiration_ledger >= env.ledger().sequence())
+        .map_or(0, |allowance| allowance.shares)
+}
+
+fn set_allowance(
+    env: &Env,
+    holder: &Address,
+    spender: &Address,
+    tranche: Tranche,
+    shares: i128,
+    expiration_ledger: u32,
+) {
+    let key = DataKey::Allowance(holder.clone(), spender.clone(), tranche);
+    env.storage().temporary().set(
+        &key,
+        &Allowance {
+            shares,
+            expiration_ledger,
+        },
+    );
+    if shares > 0 {
+        let live_for = expiration_ledger - env.ledger().sequence();
+        env.storage()
+            .temporary()
+            .extend_ttl(&key, live_for, live_for);
+    }
+}
+
+/// Pay `amount` to a tranche, of which `principal` pays down its principal. Returns whether the
+/// tranche had holders to pay.
+fn distribute(env: &Env, tranche: Tranche, amount: i128, principal: i128) -> bool {
+    let mut state = tranche_state(env, tranche);
+    if state.shares == 0 {
+        return false;
+    }
+    if amount > 0 {
+        let key = DataKey::PerShare(tranche);
+        write_i128(
+            env,
+            &key,
+            read_i128(env, &key) + amount * SCALE / state.shares,
+        );
+        state.principal -= principal;
+        state.distributed += amount;
+        set_tranche_state(env, tranche, &state);
+    }
+    true
+}
+
+/// Run `amount` through the waterfall and return what is left once no tranche has holders to
+/// take it.
+fn waterfall(env: &Env, amount: i128, pays_coupon: bool) -> i128 {
+    accrue_coupon(env);
+    let mut left = amount;
+    let coupon = if pays_coupon {
+        left.min(read_i128(env, &DataKey::CouponOwed))
+    } else {
+        0
+    };
+    left -= coupon;
+    write_i128(
+        env,
+        &DataKey::CouponOwed,
+        read_i128(env, &DataKey::CouponOwed) - coupon,
+    );
+    let senior_principal = left.min(tranche_state(env, Tranche::Senior).principal);
+    left -= senior_principal;
+    distribute(
+        env,
+        Tranche::Senior,
+        coupon + senior_principal,
+        senior_principal,
+    );
+    let junior_principal = left.min(tranche_state(env, Tranche::Junior).principal);
+    if distribute(env, Tranche::Junior, left, junior_principal) {
+        left = 0;
+    }
+    left
+}
+
+#[contract]
+pub struct Securitization;
+
+#[contractimpl]
+impl Securitization {
+    /// Initializes the contract with its asset, the credit contract whose receivables it buys
+    /// and the liquidity pool that receives the purchase price, and subscribes to the payments
+    /// the credit contract makes to it. `admin` must authorize, so the call cannot be front-run
+    /// with another credit contract or pool.
+    ///
+    /// # Panics
+    /// * If already initialized or `senior_rate_bps` > 10000
+    pub fn init(
+        env: Env,
+        admin: Address,
+        asset: Address,
+        credit_contract: Address,
+        pool: Address,
+        senior_rate_bps: u32,
+    ) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic!("already initialized");
+        }
+        admin.require_auth();
+        if senior_rate_bps as i128 > BPS_DENOMINATOR {
+            panic!("senior_rate_bps exceeds maximum");
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Asset, &asset);
+        env.storage()
+            .instance()
+            .set(&DataKey::CreditContract, &credit_contract);
+        env.storage().instance().set(&DataKey::Pool, &pool);
+        env.storage()
+            .instance()
+            .set(&DataKey::SeniorRate, &senior_rate_bps);
+        let this = env.current_contract_address();
+        CreditClient::new(&env, &credit_contract).set_receivable_hook(&this, &Some(this.clone()));
+    }
+
+    /// Buy `amount` of `tranche` shares, one share per unit of the asset. Returns the
+    /// investor's shares in the tranche.
+    ///
+    /// # Panics
+    /// * If `amount` <= 0
+    ///
+    /// # Events
+    /// Emits `(sec, invest)` with `(investor, tranche, amount)`.
+    pub fn invest(env: Env, investor: Address, tranche: Tranche, amount: i128) -> i128 {
+        investor.require_auth();
+        if amount <= 0 {
+            panic!("amount must be positive");
+        }
+        token::Client::new(&env, &asset(&env)).transfer(
+            &investor,
+            &env.current_contract_address(),
+            &amount,
+        );
+        if tranche == Tranche::Senior {
+            accrue_coupon(&env);
+        }
+        let mut state = tranche_state(&env, tranche);
+        state.shares += amount;
+        state.principal += amount;
+        set_tranche_state(&env, tranche, &state);
+        let mut holding = settled(&env, &investor, tranche);
+        holding.shares += amount;
+        set_holding(&env, &investor, tranche, &holding);
+        write_i128(
+            &env,
+            &DataKey::Cash,
+            read_i128(&env, &DataKey::Cash) + amount,
+        );
+        env.events().publish(
+            (symbol_short!("sec"), symbol_short!("invest")),
+            (investor, tranche, amount),
+        );
+        holding.shares
+    }
+
+    /// Buy `share_bps` of the borrower's receivable from the credit contract for `price`, paid
+    /// from raised cash into the liquidity pool (admin only). The sale also needs the credit
+    /// contract admin's authorization. Returns the contract's share of the receivable.
+    ///
+    /// # Panics
+    /// * If `price` exceeds the raised cash
+    /// * If the credit contract rejects the sale
+    ///
+    /// # Events
+    /// Emits `(sec, add_line)` with `(borrower, share_bps, price)`.
+    pub fn add_line(env: Env, borrower: Address, share_bps: u32, price: i128) -> u32 {
+        require_admin_auth(&env);
+        let cash = read_i128(&env, &DataKey::Cash);
+        if price > cash {
+            panic!("price exceeds cash");
+        }
+        let this = env.current_contract_address();
+        if price > 0 {
+            let pool: Address = env
+                .storage()
+                .instance()
+                .get(&DataKey::Pool)
+                .expect("not initialized");
+            // The credit contract pulls the price from this contract, one call below this one.
+            env.authorize_as_current_contract(vec![
+                &env,
+                InvokerContractAuthEntry::Contract(SubContractInvocation {
+                    context: ContractContext {
+                        contract: asset(&env),
+                        fn_name: Symbol::new(&env, "transfer"),
+                        args: (this.clone(), pool, price).into_val(&env),
+                    },
+                    sub_invocations: Vec::new(&env),
+                }),
+            ]);
+        }
+        let share = CreditClient::new(&env, &credit(&env))
+            .assign_receivable(&borrower, &this, &share_bps, &price);
+        write_i128(&env, &DataKey::Cash, cash - price);
+        let mut lines = lines(&env);
+        if !lines.contains(&borrower) {
+            lines.push_back(borrower.clone());
+            env.storage().instance().set(&DataKey::Lines, &lines);
+        }
+        env.events().publish(
+            (symbol_short!("sec"), symbol_short!("add_line")),
+            (borrower, share_bps, price),
+        );
+        share
+    }
+
+    /// Run a payment received from a repayment on the borrower's line through the waterfall.
+    /// Credit contract only, called after it transfers the funds.
+    ///
+    /// # Events
+    /// Emits `(sec, collect)` with `(borrower, principal, interest)`.
+    pub fn on_receivable_payment(env: Env, borrower: Address, principal: i128, interest: i128) {
+        require_credit_auth(&env);
+        let left = waterfall(&env, principal + interest, true);
+        if left > 0 {
+            write_i128(&env, &DataKey::Cash, read_i128(&env, &DataKey::Cash) + left);
+        }
+        env.events().publish(
+            (symbol_short!("sec"), symbol_short!("collect")),
+            (borrower, principal, interest),
+        );
+    }
+
+    /// Pay `amount` of unspent raised cash back to investors as principal, senior first (admin
+    /// only).
+    ///
+    /// # Panics
+    /// * If `amount` <= 0 or exceeds the raised cash
+    ///
+    /// # Events
+    /// Emits `(sec, return)` with `amount`.
+    pub fn return_cash(env: Env, amount: i128) {
+        require_admin_auth(&env);
+        let cash = read_i128(&env, &DataKey::Cash);
+        if amount <= 0 || amount > cash {
+            panic!("invalid cash amount");
+        }
+        let left = waterfall(&env, amount, false);
+        write_i128(&env, &DataKey::Cash, cash - amount + left);
+        env.events().publish(
+            (symbol_short!("sec"), symbol_short!("return")),
+            amount - left,
+        );
+    }
+
+    /// Withdraw everything paid to the investor's `tranche` shares and not yet claimed.
+    /// Returns the amount paid.
+    ///
+    /// # Events
+    /// Emits `(sec, claim)` with `(investor, tranche, amount)`.
+    pub fn claim(env: Env, investor: Address, tranche: Tranche) -> i128 {
+        investor.require_auth();
+        let mut holding = settled(&env, &investor, tranche);
+        let amount = holding.owed;
+        holding.owed = 0;
+        set_holding(&env, &investor, tranche, &holding);
+        if amount > 0 {
+            token::Client::new(&env, &asset(&env)).transfer(
+                &env.current_contract_address(),
+                &investor,
+                &amount,
+            );
+        }
+        env.events().publish(
+            (symbol_short!("sec"), symbol_short!("claim")),
+            (investor, tranche, amount),
+        );
+        amount
+    }
+
+    /// Move `shares` of `tranche` from `from` to `to`. Amounts already paid to the shares stay
+    /// claimable by `from`.
+    ///
+    /// # Panics
+    /// * If `shares` <= 0 or exceeds what `from` holds
+    ///
+    /// # Events
+    /// Emits `(sec, transfer)` with `(from, to, tranche, shares)`.
+    pub fn transfer(env: Env, from: Address, to: Address, tranche: Tranche, shares: i128) {
+        from.require_auth();
+        move_shares(&env, &from, &to, tranche, shares);
+    }
+
+    /// Let `spender` transfer up to `shares` of `from`'s `tranche` shares until
+    /// `expiration_ledger`, replacing any previous allowance. An allowance of 0 revokes it.
+    ///
+    /// # Panics
+    /// * If `shares` < 0
+    /// * If `shares` > 0 and `expiration_ledger` is before the current ledger
+    ///
+    /// # Events
+    /// Emits `(sec, approve)` with `(from, spender, tranche, shares, expiration_ledger)`.
+    pub fn approve(
+        env: Env,
+        from: Address,
+        spender: Address,
+        tranche: Tranche,
+        shares: i128,
+        expiration_ledger: u32,
+    ) {
+        from.require_auth();
+        if shares < 0 {
+            panic!("invalid share amount");
+        }
+        if shares > 0 && expiration_ledger < env.ledger().sequence() {
+            panic!("expiration_ledger is in the past");
+        }
+        set_allowance(&env, &from, &spender, tranche, shares, expiration_ledger);
+        env.events().publish(
+            (symbol_short!("sec"), symbol_short!("approve")),
+            (from, spender, tranche, shares, expiration_ledger),
+        );
+    }
+
+    /// Move `shares` of `from`'s `tranche` shares to `to` on `from`'s behalf, spending
+    /// `spender`'s allowance.
+    ///
+    /// # Panics
+    /// * If `shares` exceeds the allowance, or the errors of `transfer`
+    ///
+    /// # Events
+    /// Emits `(sec, transfer)` with `(from, to, tranche, shares)`.
+    pub fn transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        tranche: Tranche,
+        shares: i128,
+    ) {
+        spender.require_auth();
+        let key = DataKey::Allowance(from.clone(), spender.clone(), tranche);
+        let allowed = allowance(&env, &from, &spender, tranche);
+        if shares > allowed {
+            panic!("insufficient allowance");
+        }
+        if shares > 0 {
+            let mut remaining: Allowance = env.storage().temporary().get(&key).unwrap();
+            remaining.shares -= shares;
+            env.storage().temporary().set(&key, &remaining);
+        }
+        move_shares(&env, &from, &to, tranche, shares);
+    }
+
+    /// A tranche's shares, outstanding principal and total paid (view function).
+    pub fn tranche(env: Env, tranche: Tranche) -> TrancheState {
+        tranche_state(&env, tranche)
+    }
+
+    /// Shares `id` holds in `tranche` (view function).
+    pub fn balance(env: Env, id: Address, tranche: Tranche) -> i128 {
+        holding(&env, &id, tranche).shares
+    }
+
+    /// Shares of `from`'s `tranche` that `spender` may still transfer (view function).
+    pub fn allowance(env: Env, from: Address, spender: Address, tranche: Tranche) -> i128 {
+        allowance(&env, &from, &spender, tranche)
+    }
+
+    /// Amount `holder` can claim from `tranche` (view function).
+    pub fn claimable(env: Env, holder: Address, tranche: Tranche) -> i128 {
+        settled(&env, &holder, tranche).owed
+    }
+
+    /// Senior coupon accrued and not yet paid (view function).
+    pub fn coupon_owed(env: Env) -> i128 {
+        coupon_owed(&env)
+    }
+
+    /// Raised cash not yet spent on receivables (view function).
+    pub fn cash(env: Env) -> i128 {
+        read_i128(&env, &DataKey::Cash)
+    }
+
+    /// Borrowers whose receivables the contract holds a share of (view function).
+    pub fn lines(env: Env) -> Vec<Address> {
+        lines(&env)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use creditra_credit::{Credit, CreditClient as CreditContractClient};
+    use creditra_interface::types::InitConfig;
+    use creditra_pool::{Pool, PoolClient};
+    use soroban_sdk::testutils::{Address as _, Ledger};
+    use soroban_sdk::token::StellarAssetClient;
+
+    struct Setup<'a> {
+        sec: SecuritizationClient<'a>,
+        credit: CreditContractClient<'a>,
+        pool: PoolClient<'a>,
+        token: token::Client<'a>,
+        borrower: Address,
+        senior: Address,
+        junior: Address,
+    }
+
+    /// A pool-funded line with 10_000 drawn, and a securitization paying a 5% senior coupon
+    /// with 5_000 raised in each tranche.
+    fn setup(env: &Env) -> Setup<'_> {
+        env.mock_all_auths_allowing_non_root_auth();
+        let admin = Address::generate(env);
+        let asset = env
+            .register_stellar_asset_contract_v2(Address::generate(env))
+            .address();
+        let minter = StellarAssetClient::new(env, &asset);
+        let credit = CreditContractClient::new(env, &env.register(Credit, ()));
+        credit.init(&admin, &InitConfig::default());
+        let pool = PoolClient::new(env, &env.register(Pool, ()));
+        pool.init(&admin, &asset, &credit.address);
+        let lender = Address::generate(env);
+        minter.mint(&lender, &1_000_000_i128);
+        pool.deposit(&lender, &1_000_000_i128);
+        credit.set_liquidity_pool(&pool.address);
+        let borrower = Address::generate(env);
+        credit.open_credit_line(&borrower, &100_000_i128, &1_000_u32, &50_u32);
+        credit.draw_credit(&borrower, &10_000_i128);
+
+        let sec = SecuritizationClient::new(env, &env.register(Securitization, ()));
+        sec.init(&admin, &asset, &credit.address, &pool.address, &500_u32);
+        let (senior, junior) = (Address::generate(env), Address::generate(env));
+        minter.mint(&senior, &5_000_i128);
+        minter.mint(&junior, &5_000_i128);
+        sec.invest(&senior, &Tranche::Senior, &5_000_i128);
+        sec.invest(&junior, &Tranche::Junior, &5_000_i128);
+        Setup {
+            sec,
+            credit,
+            pool,
+            token: token::Client::new(env, &asset),
+            borrower,
+            senior,
+            junior,
+        }
+    }
+
+    #[test]
+    fn test_repayments_flow_through_waterfall() {
+        let env = Env::default();
+        let s = setup(&env);
+        assert_eq!(
+            s.sec.add_line(&s.borrower, &10_000_u32, &10_000_i128),
+            10_000
+        );
+        assert_eq!(s.sec.cash(), 0);
+        assert_eq!(s.sec.lines(), vec![&env, s.borrower.clone()]);
+        assert_eq!(s.pool.total_assets(), 1_010_000);
+
+        // Principal goes to the senior tranche first.
+        s.credit.repay_credit(&s.borrower, &4_000_i128);
+        assert_eq!(s.sec.claimable(&s.senior, &Tranche::Senior), 4_000);
+        assert_eq!(s.sec.tranche(&Tranche::Senior).principal, 1_000);
+        assert_eq!(s.pool.total_assets(), 1_006_000);
+
+        // A year later the senior coupon (5% of 1_000) is paid before any principal, and the
+        // junior tranche takes what the senior tranche does not need.
+        env.ledger()
+            .with_mut(|li| li.timestamp = 365 * 24 * 60 * 60);
+        assert_eq!(s.sec.coupon_owed(), 50);
+        s.credit.repay_credit(&s.borrower, &3_000_i128);
+        assert_eq!(s.sec.claimable(&s.senior, &Tranche::Senior), 5_050);
+        assert_eq!(s.sec.claimable(&s.junior, &Tranche::Junior), 1_950);
+        assert_eq!(s.sec.tranche(&Tranche::Senior).principal, 0);
+        assert_eq!(s.sec.tranche(&Tranche::Junior).principal, 3_050);
+
+        assert_eq!(s.sec.claim(&s.senior, &Tranche::Senior), 5_050);
+        assert_eq!(s.token.balance(&s.senior), 5_050);
+        assert_eq!(s.sec.claimable(&s.senior, &Tranche::Senior), 0);
+    }
+
+    #[test]
+    fn test_transferred_shares_share_later_payments() {
+        let env = Env::default();
+        let s = setup(&env);
+        s.sec.add_line(&s.borrower, &10_000_u32, &10_000_i128);
+        s.credit.repay_credit(&s.borrower, &6_900_i128);
+        assert_eq!(s.sec.claimable(&s.junior, &Tranche::Junior), 1_900);
+
+        let buyer = Address::generate(&env);
+        s.sec
+            .transfer(&s.junior, &buyer, &Tranche::Junior, &2_000_i128);
+        assert_eq!(s.sec.balance(&buyer, &Tranche::Junior), 2_000);
+        s.credit.repay_credit(&s.borrower, &1_000_i128);
+        assert_eq!(s.sec.claimable(&s.junior, &Tranche::Junior), 2_500);
+        assert_eq!(s.sec.claimable(&buyer, &Tranche::Junior), 400);
+    }
+
+    #[test]
+    fn test_transfer_from_spends_allowance() {
+        let env = Env::default();
+        let s = setup(&env);
+        let (spender, buyer) = (Address::generate(&env), Address::generate(&env));
+        s.sec
+            .approve(&s.senior, &spender, &Tranche::Senior, &3_000_i128, &100_u32);
+        assert_eq!(
+            s.sec.allowance(&s.senior, &spender, &Tranche::Senior),
+            3_000
+        );
+        assert_eq!(s.sec.allowance(&s.senior, &spender, &Tranche::Junior), 0);
+
+        s.sec
+            .transfer_from(&spender, &s.senior, &buyer, &Tranche::Senior, &2_000_i128);
+        assert_eq!(s.sec.balance(&s.senior, &Tranche::Senior), 3_000);
+        assert_eq!(s.sec.balance(&buyer, &Tranche::Senior), 2_000);
+        assert_eq!(
+            s.sec.allowance(&s.senior, &spender, &Tranche::Senior),
+            1_000
+        );
+
+        // Beyond its expiration ledger the allowance is gone.
+        env.ledger().with_mut(|li| li.sequence_number = 101);
+        assert_eq!(s.sec.allowance(&s.senior, &spender, &Tranche::Senior), 0);
+        assert!(s
+            .sec
+            .try_transfer_from(&spender, &s.senior, &buyer, &Tranche::Senior, &1_i128)
+            .is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "insufficient allowance")]
+    fn test_transfer_from_rejects_amount_over_allowance() {
+        let env = Env::default();
+        let s = setup(&env);
+        let spender = Address::generate(&env);
+        s.sec
+            .approve(&s.junior, &spender, &Tranche::Junior, &1_000_i128, &100_u32);
+        s.sec
+            .transfer_from(&spender, &s.junior, &spender, &Tranche::Junior, &1_001_i128);
+    }
+
+    #[test]
+    fn test_return_cash_pays_senior_principal_first() {
+        let env = Env::default();
+        let s = setup(&env);
+        s.sec.return_cash(&7_000_i128);
+        assert_eq!(s.sec.claimable(&s.senior, &Tranche::Senior), 5_000);
+        assert_eq!(s.sec.claimable(&s.junior, &Tranche::Junior), 2_000);
+        assert_eq!(s.sec.cash(), 3_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "price exceeds cash")]
+    fn test_add_line_limited_to_raised_cash() {
+        let env = Env::default();
+        let s = setup(&env);
+        s.sec.add_line(&s.borrower, &10_000_u32, &10_001_i128);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_init_requires_admin_auth() {
+        let env = Env::default();
+        let asset = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        let sec = SecuritizationClient::new(&env, &env.register(Securitization, ()));
+        let (credit, pool) = (Address::generate(&env), Address::generate(&env));
+        sec.init(&Address::generate(&env), &asset, &credit, &pool, &500_u32);
+    }
+}